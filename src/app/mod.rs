@@ -0,0 +1,790 @@
+// src/app/mod.rs
+//
+// A CLI completa como função de biblioteca: o binário `cannon` só chama `run`, e um crate
+// externo pode fazer o mesmo passando um ProtocolRegistry com os seus modos.
+
+pub(crate) mod plan;
+
+use crate::args::parser::{Args, Command};
+use crate::client::protocol::ProtocolRegistry;
+use crate::distributed::agent;
+use crate::distributed::coordinator::{self, RunningCluster};
+use crate::engine::worker::SharedMetrics;
+use crate::load_test::RunningTest;
+use crate::report::cli::{
+    generate_html_report, print_adaptive_report, print_banner, print_capacity_report,
+    print_phases_report, print_steps_report, print_summary, print_thresholds_report,
+    print_udp_report, print_ws_report,
+};
+use crate::report::thresholds;
+use crate::LoadTestResult;
+use clap::Parser;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use plan::{PlanError, TestPlan};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+// Códigos de saída para o CI. 1 = configuração inválida (o clap usa 2 para flags erradas).
+// A regressão do --compare-baseline sempre saiu com 1 e continua assim.
+const EXIT_BASELINE_REGRESSION: i32 = 1;
+const EXIT_THRESHOLDS_FAILED: i32 = 3;
+const EXIT_TARGET_UNREACHABLE: i32 = 4;
+const EXIT_ABORT_RULE: i32 = 5;
+
+pub fn run(registry: ProtocolRegistry) -> Result<(), Box<dyn std::error::Error>> {
+    // Lemos os argumentos antes de ligar o motor
+    let args = Args::parse();
+
+    if args.pin_threads {
+        let core_ids = core_affinity::get_core_ids().expect("❌ Falha ao ler a topologia da CPU");
+        let core_count = core_ids.len();
+        let core_idx = Arc::new(AtomicUsize::new(0));
+
+        println!(
+            "CPU Pinning ativado: Aplicando Afinidade de CPU Estrita ({} núcleos)...",
+            core_count
+        );
+
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .worker_threads(core_count)
+            .on_thread_start(move || {
+                let idx = core_idx.fetch_add(1, Ordering::SeqCst) % core_count;
+                let target_core = core_ids[idx];
+                let _ = core_affinity::set_for_current(target_core);
+            })
+            .build()?;
+
+        rt.block_on(async { run_app(args, registry).await })
+    } else {
+        // Inicialização padrão (Deixa o SO decidir)
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(async { run_app(args, registry).await })
+    }
+}
+
+async fn run_app(
+    mut args: Args,
+    registry: ProtocolRegistry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if args.update {
+        update()?;
+        return Ok(());
+    }
+    // Importar não dispara nada: só escreve o YAML
+    if let Some(Command::Import { source }) = &args.command {
+        return crate::import::run(source);
+    }
+
+    if let Err(e) = crate::args::config::merge_with_yaml(&mut args) {
+        eprintln!(
+            "{} Falha ao carregar configuração YAML: {}",
+            "❌ Erro:".red().bold(),
+            e
+        );
+        std::process::exit(1);
+    }
+
+    if let Some(command) = args.from_curl.take() {
+        let req = crate::import::parse_curl(&[command]).unwrap_or_else(|e| {
+            eprintln!("{} {}", "❌ Erro no --from-curl:".red().bold(), e);
+            std::process::exit(1);
+        });
+        crate::import::warn_curl(&req.warnings);
+        req.apply(&mut args);
+    }
+
+    match &args.command {
+        Some(Command::Agent { listen, token }) => {
+            return serve_agent(listen, token, registry).await
+        }
+        Some(Command::Coordinate { .. }) | Some(Command::Import { .. }) | None => {}
+    }
+
+    let plan = TestPlan::from_args(&args, &registry).unwrap_or_else(|e| exit_with(e));
+    let (agents, token) = match &args.command {
+        Some(Command::Coordinate { agents, token }) => (Some(agents.clone()), token.clone()),
+        _ => (None, String::new()),
+    };
+
+    let parsed_percentiles: Vec<f64> = args
+        .percentiles
+        .split(',')
+        .filter_map(|s| s.trim().parse::<f64>().ok())
+        .map(|p| p / 100.0)
+        .collect();
+
+    let buffer_size = std::cmp::min(args.workers as usize, 10_000).max(1);
+
+    let executor = plan.executor(&args);
+    let planned_duration = executor.planned_duration();
+
+    // Configuração do CSV Assíncrono
+    let mut csv_tx = None;
+    if let Some(path) = args.csv.as_ref().filter(|_| agents.is_none()) {
+        let (tx, mut rx) = mpsc::channel::<crate::engine::worker::CsvRecord>(buffer_size);
+        csv_tx = Some(tx);
+        let path_clone = path.clone();
+
+        // Spawn Background Worker pro I/O de disco
+        tokio::spawn(async move {
+            if let Ok(mut w) = csv::Writer::from_path(&path_clone) {
+                let _ = w.write_record(["tempo_relativo_ms", "status", "latencia_ms", "erro"]);
+                while let Some(rec) = rx.recv().await {
+                    let _ =
+                        w.write_record(&[rec.relative_ms, rec.status, rec.latency_ms, rec.error]);
+                }
+                let _ = w.flush();
+            }
+        });
+    }
+
+    // No modo distribuído quem monta o teste são os agentes
+    let load_test = match agents {
+        Some(_) => None,
+        None => Some(
+            plan.load_test(&args, &registry, csv_tx)
+                .await
+                .unwrap_or_else(|e| exit_with(e)),
+        ),
+    };
+
+    print_banner();
+
+    println!("🎯 Alvo: {}", plan.target.bright_cyan().bold());
+    if let Some(agents) = &agents {
+        println!(
+            "🛰️ Modo distribuído: a carga abaixo é o total, dividido entre {} agente(s)",
+            agents.len().to_string().magenta()
+        );
+    }
+    if let Some(log) = &plan.replay {
+        let pace = match plan
+            .replay_executor
+            .as_ref()
+            .and_then(|e| e.planned_duration())
+        {
+            Some(d) => format!(
+                "em {:?} ({}x o ritmo original)",
+                d,
+                args.replay_speed.trim_end_matches('x')
+            ),
+            None => "o mais rápido possível".to_string(),
+        };
+        println!(
+            "⏪ {}",
+            format!(
+                "Replay de {} requisição(ões) de {} {} com {} workers...",
+                log.len().to_string().cyan(),
+                args.replay.as_deref().unwrap_or_default().yellow(),
+                pace,
+                args.workers.to_string().magenta()
+            )
+            .bold()
+        );
+    } else if let Some(search) = &plan.capacity_search {
+        println!(
+            "🧗 {}",
+            format!(
+                "Buscando a capacidade máxima: degraus de {} RPS ({} cada) com {} workers...",
+                search.step_rps.to_string().cyan(),
+                args.step_duration.yellow(),
+                args.workers.to_string().magenta()
+            )
+            .bold()
+        );
+    } else if let Some(algorithm) = &plan.adaptive {
+        println!(
+            "🎛️ {}",
+            format!(
+                "Concorrência adaptativa ({:?}): de 1 até {} workers...",
+                algorithm,
+                args.workers.to_string().magenta()
+            )
+            .bold()
+        );
+    } else if let Some(d) = plan.test_duration {
+        println!(
+            "🚀 {}",
+            format!(
+                "Preparando o canhão para disparar por {} com {} workers...",
+                format!("{:?}", d).cyan(),
+                args.workers.to_string().magenta()
+            )
+            .bold()
+        );
+    } else {
+        println!(
+            "🚀 {}",
+            format!(
+                "Preparando o canhão para {} disparo(s) com {} workers...",
+                args.count.to_string().cyan(),
+                args.workers.to_string().magenta()
+            )
+            .bold()
+        );
+    }
+
+    println!("⏱️ Timeout: {}ms", args.timeout.to_string().yellow());
+
+    if let Some(profile) = &plan.load_profile {
+        println!(
+            "📈 Ramp-up em {} estágio(s) ({:?} no total, ~{} disparos):",
+            profile.stages.len().to_string().cyan(),
+            profile.total_duration(),
+            profile.estimated_requests()
+        );
+        for stage in &profile.stages {
+            println!(
+                "   ➜ {:?} até {} RPS",
+                stage.duration,
+                stage.target.to_string().yellow()
+            );
+        }
+    }
+
+    if let (Some(scenario), Some(requests)) = (&plan.scenario, &args.requests) {
+        let mix: Vec<String> = scenario
+            .step_names()
+            .iter()
+            .zip(requests)
+            .map(|(name, r)| format!("{} ({})", name, r.weight))
+            .collect();
+        println!(
+            "🎯 Mix: cada disparo sorteia uma de {} requisição(ões) pelo peso: {}",
+            mix.len().to_string().cyan(),
+            mix.join(", ").bright_white()
+        );
+    } else if let Some(scenario) = &plan.scenario {
+        println!(
+            "🧭 Cenário: cada disparo é uma jornada de {} passo(s): {}",
+            scenario.step_names().len().to_string().cyan(),
+            scenario.step_names().join(" → ").bright_white()
+        );
+    }
+
+    if !plan.thresholds.is_empty() {
+        let checks: Vec<String> = plan
+            .thresholds
+            .iter()
+            .map(|t| match t.abort_on_fail {
+                true => format!("{} (aborta)", t.check),
+                false => t.check.clone(),
+            })
+            .collect();
+        println!("📏 Thresholds: {}", checks.join(", ").bright_white());
+    }
+    if !plan.abort_rules.is_empty() {
+        let rules: Vec<&str> = plan.abort_rules.iter().map(|r| r.spec.as_str()).collect();
+        println!(
+            "🛑 Para o teste se: {} (avaliado a cada {:?})",
+            rules.join(" | ").bright_white(),
+            plan.timeline_interval
+        );
+    }
+
+    if let Some(dist) = &plan.arrival {
+        let limit = args
+            .max_vus
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "sem limite".to_string());
+        println!(
+            "🌊 Modelo aberto: chegadas {:?}, máximo de {} requisições em voo",
+            dist,
+            limit.magenta()
+        );
+    }
+
+    if args.warmup > 0 {
+        println!(
+            "🔥 Modo Warm-up ativado: Desprezando os primeiros {}s de métricas...",
+            args.warmup.to_string().yellow()
+        );
+    }
+
+    let planned_requests = executor.planned_requests().unwrap_or(args.count as u64);
+
+    // Em testes por tempo a barra anda no relógio e mostra o tempo restante
+    let pb = match planned_duration {
+        // Busca de capacidade: sem fim conhecido, só o contador de disparos
+        _ if plan.capacity_search.is_some() => {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(
+                ProgressStyle::default_spinner()
+                    .template("{spinner:.bold.green} [{elapsed_precise}] {pos:>7} disparos {msg}")
+                    .unwrap(),
+            );
+            pb
+        }
+        Some(d) => {
+            let pb = ProgressBar::new(d.as_millis() as u64);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.bold.green} [{elapsed_precise}] {bar:40.magenta/blue} ⏳ {prefix} {msg}")
+                    .unwrap()
+                    .progress_chars("━╾─"),
+            );
+            pb
+        }
+        None => {
+            let pb = ProgressBar::new(planned_requests);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.bold.green} [{elapsed_precise}] {bar:40.magenta/blue} {pos:>7}/{len:7} {msg}")
+                    .unwrap()
+                    .progress_chars("━╾─"),
+            );
+            pb
+        }
+    };
+
+    let started = match (load_test, &agents) {
+        (Some(load_test), _) => {
+            if plan.mode == "tcp" {
+                println!("🔌 Estabelecendo pool de {} conexões TCP...", args.workers);
+            }
+            // Localmente, falhar no start é não conseguir conectar no alvo (pool TCP / DNS do UDP)
+            load_test
+                .start()
+                .await
+                .map(Running::Local)
+                .map_err(|e| (EXIT_TARGET_UNREACHABLE, e))
+        }
+        (None, Some(agents)) => {
+            println!("🛰️ Conectando em {}...", agents.join(", ").bright_cyan());
+            coordinator::start(&args, &executor, agents, &token)
+                .await
+                .map(Running::Cluster)
+                .map_err(|e| (1, e))
+        }
+        (None, None) => unreachable!("sem agentes o teste é sempre local"),
+    };
+    let running = match started {
+        Ok(running) => running,
+        Err((code, e)) => {
+            eprintln!("{} {}", "❌ Erro ao iniciar o teste:".red().bold(), e);
+            std::process::exit(code);
+        }
+    };
+    println!(
+        "{}",
+        "Pressione Ctrl+C para interromper e ver o relatório parcial".bright_black()
+    );
+
+    // UI Loop (Puxa os dados dos Atomics a cada 500ms)
+    let start_test = running.started_at();
+    let mut last_total = 0;
+    let mut last_time = Instant::now();
+
+    let mut interrupted = false;
+    let mut aborted_by = None;
+
+    while !running.is_finished() {
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {
+                let succ = running.metrics().successes.load(Ordering::Relaxed);
+                let fail = running.metrics().failures.load(Ordering::Relaxed);
+                let total = succ + fail;
+
+                match planned_duration {
+                    Some(d) => {
+                        let elapsed = start_test.elapsed().min(d);
+                        pb.set_position(elapsed.as_millis() as u64);
+                        pb.set_prefix(format!("{}s restantes", (d - elapsed).as_secs()));
+                    }
+                    None => pb.set_position(total),
+                }
+
+                let elapsed = last_time.elapsed().as_secs_f64();
+                if elapsed >= 0.1 && total > last_total {
+                    let rps = (total - last_total) as f64 / elapsed;
+                    match &plan.load_profile {
+                        Some(profile) => pb.set_message(format!(
+                            "| ⚡ {:.1} RPS (alvo {:.0})",
+                            rps,
+                            profile.rate_at(start_test.elapsed())
+                        )),
+                        None => pb.set_message(format!("| ⚡ {:.1} RPS", rps)),
+                    }
+                    last_total = total;
+                    last_time = Instant::now();
+                }
+
+                let tripped = plan
+                    .thresholds
+                    .iter()
+                    .position(|t| t.tripped_live(succ, fail, start_test.elapsed()));
+                if let Some(i) = tripped {
+                    println!(
+                        "\n\n{} '{}' falhou durante o teste. Drenando requisições em voo por até {:?}...",
+                        "🛑 Threshold com abort_on_fail:".red().bold(),
+                        plan.thresholds[i].check,
+                        plan.drain_timeout
+                    );
+                    aborted_by = Some(i);
+                    interrupted = true;
+                    break;
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                println!(
+                    "\n\n{} Drenando requisições em voo por até {:?} (Ctrl+C de novo aborta na hora)...",
+                    "⚠️ Interrupção detectada!".yellow().bold(),
+                    plan.drain_timeout
+                );
+                interrupted = true;
+                break;
+            }
+        }
+    }
+
+    let finished = if interrupted {
+        tokio::select! {
+            result = running.drain(plan.drain_timeout) => result,
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\n{}", "🛑 Abort forçado. Saindo sem relatório.".red().bold());
+                std::process::exit(130);
+            }
+        }
+    } else {
+        running.wait().await
+    };
+    pb.finish_with_message("Concluído");
+    let result = match finished {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{} {}", "❌ Erro no modo distribuído:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(abort) = &result.abort_reason {
+        println!(
+            "\n{} '{}' aos {:.1}s: {}",
+            "🛑 Teste interrompido pela regra".red().bold(),
+            abort.rule,
+            abort.at_secs,
+            abort.reason
+        );
+    }
+    if result.aborted {
+        println!(
+            "{}",
+            "⏱️ Drain esgotado: as requisições ainda em voo foram canceladas".yellow()
+        );
+    }
+
+    if let Some(path) = &args.csv {
+        println!("📊 Dados brutos exportados para {}!", path.bright_cyan());
+    }
+
+    if result.interrupted {
+        match result.unsent_requests {
+            Some(n) => println!(
+                "\n{} {} requisição(ões) planejada(s) nunca foram enviadas",
+                "⚠️ TESTE INTERROMPIDO:".yellow().bold(),
+                n.to_string().red()
+            ),
+            None => println!("\n{}", "⚠️ TESTE INTERROMPIDO pelo usuário".yellow().bold()),
+        }
+    }
+
+    let hist = &result.histogram;
+    let actual_rps = result.actual_rps();
+
+    print_summary(
+        result.successes,
+        result.failures,
+        hist,
+        Some(&result.response_histogram),
+        result.duration,
+        args.rps,
+        result.status_counts.clone(),
+        result.error_counts.clone(),
+        result.assertion_failures,
+        result.bytes_sent,
+        result.bytes_received,
+        &parsed_percentiles,
+    );
+
+    if !result.steps.is_empty() {
+        print_steps_report(
+            "🧭 CENÁRIO (por passo; os contadores acima são jornadas)",
+            &result.steps,
+        );
+    }
+    if !result.requests.is_empty() {
+        print_steps_report("🎯 MIX DE REQUISIÇÕES (por requisição)", &result.requests);
+    }
+    if !result.phases.is_empty() {
+        print_phases_report(&result.phases);
+    }
+    if !result.udp.is_empty() {
+        print_udp_report(&result.udp);
+    }
+    if !result.ws.is_empty() {
+        print_ws_report(&result.ws, result.duration);
+    }
+
+    if let Some(report) = &result.capacity {
+        print_capacity_report(report);
+    }
+
+    if let Some(report) = &result.adaptive {
+        print_adaptive_report(report);
+    }
+
+    let threshold_results =
+        thresholds::evaluate(&plan.thresholds, &result, args.apdex_t, aborted_by);
+    if !threshold_results.is_empty() {
+        print_thresholds_report(&threshold_results);
+    }
+
+    if plan.arrival.is_some() {
+        println!("\n{}", "🌊 MODELO ABERTO".bold().bright_white());
+        println!(
+            "Chegadas descartadas (sem VU livre): {}",
+            result.dropped_arrivals.to_string().red()
+        );
+        println!(
+            "Chegadas atrasadas (gerador fora da agenda): {}",
+            result.late_arrivals.to_string().yellow()
+        );
+    }
+
+    let current_p99_ms = hist.value_at_percentile(99.0) as f64 / 1000.0;
+
+    if let Some(path) = &args.save_baseline {
+        let baseline_data = serde_json::json!({
+            "p99_ms": current_p99_ms,
+            "rps": actual_rps
+        });
+
+        if let Ok(json_str) = serde_json::to_string_pretty(&baseline_data) {
+            let _ = std::fs::write(path, json_str);
+            println!(
+                "\n💾 Baseline de performance salvo com sucesso em: {}",
+                path.bright_green()
+            );
+        }
+    }
+
+    // A saída com erro espera as exportações: o CI ainda recebe o relatório
+    let mut baseline_regression = false;
+    if let Some(path) = &args.compare_baseline {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            if let Ok(baseline) = serde_json::from_str::<serde_json::Value>(&content) {
+                let base_p99 = baseline["p99_ms"].as_f64().unwrap_or(0.0);
+
+                println!(
+                    "\n⚖️  {}",
+                    "ANÁLISE DE BASELINE (CI/CD)".bright_blue().bold()
+                );
+                println!("   P99 Histórico: {:.2}ms", base_p99);
+                println!("   P99 Atual:     {:.2}ms", current_p99_ms);
+
+                if current_p99_ms > base_p99 {
+                    let degradation = ((current_p99_ms - base_p99) / base_p99) * 100.0;
+                    println!(
+                        "   Variação:      +{} pior",
+                        format!("{:.2}%", degradation).yellow()
+                    );
+
+                    if degradation > args.tolerance {
+                        println!(
+                            "\n❌ {} Tolerância de {}% excedida.",
+                            "REGRESSÃO DE PERFORMANCE DETECTADA!".red().bold(),
+                            args.tolerance
+                        );
+                        baseline_regression = true;
+                    } else {
+                        println!(
+                            "\n✅ Regressão aceitável. Dentro da tolerância de {}%.",
+                            args.tolerance
+                        );
+                    }
+                } else {
+                    let improvement = ((base_p99 - current_p99_ms) / base_p99) * 100.0;
+                    println!(
+                        "   Variação:      -{} melhor",
+                        format!("{:.2}%", improvement).green()
+                    );
+                    println!("\n✅ Performance melhorou ou se manteve constante!");
+                }
+            }
+        } else {
+            println!(
+                "\n⚠️ Aviso: Arquivo de baseline '{}' não encontrado. Comparação ignorada.",
+                path.yellow()
+            );
+        }
+    }
+
+    // Exportação de Dados (JSON / HTML)
+    if args.output.is_some() || args.html.is_some() {
+        let mut report = result.to_report(&plan.target, args.workers, args.apdex_t);
+        report.thresholds = threshold_results.clone();
+
+        let json_data = serde_json::to_string_pretty(&report)?;
+
+        if let Some(path) = &args.output {
+            std::fs::write(path, &json_data)?;
+            println!(
+                "📂 Relatório JSON salvo com sucesso em {}!",
+                path.bright_cyan()
+            );
+        }
+
+        if let Some(path) = &args.html {
+            generate_html_report(path, &json_data)?;
+            println!(
+                "🌐 Relatório HTML salvo com sucesso em {}!",
+                path.bright_cyan()
+            );
+        }
+    }
+
+    // Nenhuma resposta do alvo (nem um status HTTP): não adianta julgar os limites
+    if result.successes == 0 && result.status_counts.is_empty() && result.failures > 0 {
+        eprintln!(
+            "\n{}",
+            "🔌 O alvo não respondeu a nenhuma requisição.".red().bold()
+        );
+        std::process::exit(EXIT_TARGET_UNREACHABLE);
+    }
+    if result.abort_reason.is_some() {
+        std::process::exit(EXIT_ABORT_RULE);
+    }
+    if threshold_results.iter().any(|t| !t.passed) {
+        std::process::exit(EXIT_THRESHOLDS_FAILED);
+    }
+    if baseline_regression {
+        std::process::exit(EXIT_BASELINE_REGRESSION);
+    }
+
+    Ok(())
+}
+
+// Interrompe a CLI com a mensagem no formato de sempre ("❌ Erro no --flag: ...")
+fn exit_with(e: PlanError) -> ! {
+    let label = match e.flag {
+        Some(flag) => format!("❌ Erro no {}:", flag),
+        None => "❌ Erro:".to_string(),
+    };
+    eprintln!("{} {}", label.red().bold(), e.message);
+    std::process::exit(1);
+}
+
+// `cannon agent`: fica à disposição de um coordenador até o processo ser morto
+async fn serve_agent(
+    listen: &str,
+    token: &str,
+    registry: ProtocolRegistry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if token.is_empty() {
+        eprintln!(
+            "{} o token não pode ser vazio",
+            "❌ Erro no --token:".red().bold()
+        );
+        std::process::exit(1);
+    }
+    let listener = match TcpListener::bind(listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("{} {}", "❌ Erro no --listen:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    print_banner();
+    println!(
+        "🛰️ Agente aguardando o coordenador em {}",
+        listener.local_addr()?.to_string().bright_cyan().bold()
+    );
+    agent::serve(listener, registry, token.to_string()).await?;
+    Ok(())
+}
+
+// O teste em andamento: local (neste processo) ou espalhado pelos agentes
+enum Running {
+    Local(RunningTest),
+    Cluster(RunningCluster),
+}
+
+impl Running {
+    fn metrics(&self) -> &SharedMetrics {
+        match self {
+            Self::Local(test) => test.metrics(),
+            Self::Cluster(cluster) => cluster.metrics(),
+        }
+    }
+
+    fn started_at(&self) -> Instant {
+        match self {
+            Self::Local(test) => test.started_at(),
+            Self::Cluster(cluster) => cluster.started_at(),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        match self {
+            Self::Local(test) => test.is_finished(),
+            Self::Cluster(cluster) => cluster.is_finished(),
+        }
+    }
+
+    async fn wait(self) -> Result<LoadTestResult, String> {
+        match self {
+            Self::Local(test) => Ok(test.wait().await),
+            Self::Cluster(cluster) => cluster.wait().await,
+        }
+    }
+
+    // No cluster cada agente aplica o próprio --drain-timeout
+    async fn drain(self, timeout: std::time::Duration) -> Result<LoadTestResult, String> {
+        match self {
+            Self::Local(test) => Ok(test.drain(timeout).await),
+            Self::Cluster(cluster) => cluster.drain().await,
+        }
+    }
+}
+
+fn update() -> Result<(), Box<dyn std::error::Error>> {
+    // Definimos o identificador de destino que corresponde ao nome do asset no GitHub
+    let target = if cfg!(target_os = "linux") {
+        "linux-x64"
+    } else if cfg!(target_os = "windows") {
+        "windows-x64.exe"
+    } else if cfg!(target_os = "macos") {
+        "macos-x64"
+    } else {
+        ""
+    };
+
+    let status = self_update::backends::github::Update::configure()
+        .repo_owner("FelipeFelipeRenan")
+        .repo_name("cannon-project")
+        .bin_name("cannon")
+        .target(target)
+        .show_download_progress(true)
+        .current_version(env!("CARGO_PKG_VERSION"))
+        .build()?
+        .update()?;
+
+    if status.updated() {
+        println!(
+            "✅ Atualizado com sucesso para a versão {}",
+            status.version()
+        );
+    } else {
+        println!(
+            "✨ Você já está na versão mais recente: {}",
+            status.version()
+        );
+    }
+
+    Ok(())
+}
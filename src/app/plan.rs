@@ -0,0 +1,566 @@
+// src/app/plan.rs
+
+use crate::args::parser::Args;
+use crate::client::http::{ConnectionMode, HttpOptions};
+use crate::client::protocol::{ProtocolContext, ProtocolRegistry, BUILTIN_MODES};
+use crate::client::source::SourceIps;
+use crate::client::stream::{check_unix_target, unix_path};
+use crate::client::udp::UdpReply;
+use crate::client::ws::WsMatch;
+use crate::engine::abort::AbortRule;
+use crate::engine::adaptive::LimitAlgorithm;
+use crate::engine::capacity::{CapacitySearch, Slo};
+use crate::engine::open_model::ArrivalDistribution;
+use crate::engine::profile::LoadProfile;
+use crate::engine::worker::CsvRecord;
+use crate::replay::ReplayLog;
+use crate::report::thresholds::Threshold;
+use crate::scenario::Scenario;
+use crate::{Executor, LoadTest};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+// Erro de validação dos args; `flag` vira o "Erro no --flag:" da CLI
+#[derive(Debug)]
+pub struct PlanError {
+    pub flag: Option<&'static str>,
+    pub message: String,
+}
+
+impl PlanError {
+    fn on(flag: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            flag: Some(flag),
+            message: message.into(),
+        }
+    }
+
+    fn general(message: impl Into<String>) -> Self {
+        Self {
+            flag: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for PlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.flag {
+            Some(flag) => write!(f, "Erro no {}: {}", flag, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// Os args já interpretados: a CLI e o agente distribuído montam o teste a partir daqui
+pub struct TestPlan {
+    pub mode: String,
+    pub target: String,
+    pub load_profile: Option<Arc<LoadProfile>>,
+    pub test_duration: Option<Duration>,
+    pub arrival: Option<ArrivalDistribution>,
+    pub adaptive: Option<LimitAlgorithm>,
+    pub capacity_search: Option<CapacitySearch>,
+    pub drain_timeout: Duration,
+    pub timeline_interval: Duration,
+    // Jornada (scenario:) ou mix ponderado (requests:)
+    pub scenario: Option<Arc<Scenario>>,
+    pub thresholds: Vec<Threshold>,
+    pub abort_rules: Vec<AbortRule>,
+    // --replay: o log e o executor que segue a sua agenda
+    pub replay: Option<Arc<ReplayLog>>,
+    pub replay_executor: Option<Executor>,
+    // Cliente HTTP: os flags da CLI mais o --connection-mode e a rotação
+    pub http_options: HttpOptions,
+    // --source-ip: já conferidos contra as interfaces desta máquina
+    pub source_ips: SourceIps,
+    // --mode udp com --udp-wait-reply: prazo e casamento das respostas
+    pub udp_reply: Option<UdpReply>,
+    // --mode ws: como as respostas são reconhecidas (--ws-match)
+    pub ws_match: Option<WsMatch>,
+}
+
+impl TestPlan {
+    pub fn from_args(args: &Args, registry: &ProtocolRegistry) -> Result<Self, PlanError> {
+        Self::build(args, registry, true)
+    }
+
+    // O plano recebido por um agente: o mesmo, mas sem alvos unix:// em nenhum modo
+    pub fn from_agent_args(args: &Args, registry: &ProtocolRegistry) -> Result<Self, PlanError> {
+        Self::build(args, registry, false)
+    }
+
+    fn build(
+        args: &Args,
+        registry: &ProtocolRegistry,
+        allow_unix: bool,
+    ) -> Result<Self, PlanError> {
+        let mode = args.mode.to_lowercase();
+        if !BUILTIN_MODES.contains(&mode.as_str()) && !registry.contains(&mode) {
+            return Err(PlanError::on(
+                "--mode",
+                format!(
+                    "Modo desconhecido '{}' (disponíveis: {})",
+                    args.mode,
+                    registry.modes().join(", ")
+                ),
+            ));
+        }
+
+        // Só o HTTP passa pela validação de URL; TCP e protocolos registrados usam o endereço cru
+        let mut target = if mode == "http" {
+            crate::security::url_validator::check(&args.url, allow_unix)
+                .map_err(PlanError::general)?
+        } else {
+            args.url.clone().ok_or_else(|| {
+                PlanError::general("The address (IP:Port) from the target is required!")
+            })?
+        };
+        if !allow_unix && unix_path(&target).is_some() {
+            return Err(PlanError::on(
+                "--url",
+                crate::security::url_validator::UNIX_REFUSED,
+            ));
+        }
+        check_unix_target(&target).map_err(|e| PlanError::on("--url", e))?;
+        let unix_target = unix_path(&target).is_some();
+
+        // HTTP num socket Unix: o caminho vai para o cliente e a URL vira --unix-host + --unix-path
+        // (assim cenários, mixes e replay resolvem os caminhos como num alvo http:// comum)
+        let mut unix_socket = None;
+        if mode == "http" {
+            if let Some(path) = unix_path(&target) {
+                if !args.unix_path.starts_with('/') {
+                    return Err(PlanError::on(
+                        "--unix-path",
+                        format!("'{}' precisa começar com /", args.unix_path),
+                    ));
+                }
+                unix_socket = Some(PathBuf::from(path));
+                target = format!("http://{}{}", args.unix_host, args.unix_path);
+            }
+        }
+
+        let load_profile = args
+            .ramp_up
+            .as_deref()
+            .map(LoadProfile::parse)
+            .transpose()
+            .map_err(|e| PlanError::on("--ramp-up", e))?
+            .map(Arc::new);
+
+        let test_duration = args
+            .duration
+            .as_deref()
+            .map(crate::utils::parse_duration)
+            .transpose()
+            .map_err(|e| PlanError::on("--duration", e))?;
+
+        let arrival = args
+            .arrival
+            .as_deref()
+            .map(ArrivalDistribution::parse)
+            .transpose()
+            .map_err(|e| PlanError::on("--arrival", e))?;
+        if args.rps == Some(0) {
+            return Err(PlanError::on("--rps", "A taxa precisa ser maior que zero"));
+        }
+        if arrival.is_some() && args.rps.is_none() && load_profile.is_none() {
+            return Err(PlanError::general(
+                "O modelo aberto (--arrival) precisa de uma taxa: use --rps ou --ramp-up",
+            ));
+        }
+
+        let adaptive = args
+            .adaptive
+            .as_deref()
+            .map(|a| LimitAlgorithm::parse(a, args.latency_target))
+            .transpose()
+            .map_err(|e| PlanError::on("--adaptive", e))?;
+
+        let capacity_search = if args.find_capacity {
+            let step_duration = crate::utils::parse_duration(&args.step_duration)
+                .map_err(|e| PlanError::on("--step-duration", e))?;
+            Some(CapacitySearch {
+                start_rps: args.rps.unwrap_or(args.capacity_step),
+                step_rps: args.capacity_step,
+                max_rps: args.capacity_max,
+                step_duration,
+                slo: Slo {
+                    p99_ms: args.slo_p99,
+                    max_error_rate: args.slo_error_rate / 100.0,
+                },
+            })
+        } else {
+            None
+        };
+
+        // Os mesmos conflicts_with do clap, que valores vindos do --config não passam
+        let given = |flag: &str| match flag {
+            "--duration" => test_duration.is_some(),
+            "--ramp-up" => load_profile.is_some(),
+            "--arrival" => arrival.is_some(),
+            "--rps" => args.rps.is_some(),
+            "--find-capacity" => capacity_search.is_some(),
+            _ => adaptive.is_some(),
+        };
+        let conflicts = [
+            ("--duration", "--ramp-up"),
+            ("--find-capacity", "--duration"),
+            ("--find-capacity", "--ramp-up"),
+            ("--find-capacity", "--arrival"),
+            ("--adaptive", "--rps"),
+            ("--adaptive", "--ramp-up"),
+            ("--adaptive", "--arrival"),
+            ("--adaptive", "--find-capacity"),
+        ];
+        if let Some((flag, other)) = conflicts.iter().find(|(a, b)| given(a) && given(b)) {
+            return Err(PlanError::on(
+                flag,
+                format!(
+                    "não combina com {} (na linha de comando ou no YAML do --config)",
+                    other
+                ),
+            ));
+        }
+
+        let drain_timeout = crate::utils::parse_duration(&args.drain_timeout)
+            .map_err(|e| PlanError::on("--drain-timeout", e))?;
+        let timeline_interval = crate::utils::parse_duration(&args.timeline_interval)
+            .map_err(|e| PlanError::on("--timeline-interval", e))?;
+
+        // O cenário (ou o mix) vem do YAML; URLs relativas partem do --url
+        let scenario = match (&args.scenario, &args.requests) {
+            (Some(_), Some(_)) => {
+                return Err(PlanError::on(
+                    "--config",
+                    "Use scenario: ou requests:, não os dois",
+                ))
+            }
+            (Some(_), None) | (None, Some(_)) if mode != "http" => {
+                return Err(PlanError::on(
+                    "--config",
+                    "Cenários (scenario:) e mixes (requests:) só rodam no modo http",
+                ))
+            }
+            (Some(steps), None) => Some(Arc::new(
+                Scenario::from_config(&target, steps).map_err(|e| PlanError::on("--config", e))?,
+            )),
+            (None, Some(requests)) => Some(Arc::new(
+                Scenario::mix(&target, requests).map_err(|e| PlanError::on("--config", e))?,
+            )),
+            (None, None) => None,
+        };
+
+        // Escopo {request:...} precisa apontar para um passo ou requisição que existe
+        let names = scenario
+            .as_ref()
+            .map(|s| s.step_names())
+            .unwrap_or_default();
+        let mut thresholds = Vec::new();
+        for conf in args.thresholds.iter().flatten() {
+            let threshold = Threshold::parse(conf).map_err(|e| PlanError::on("--config", e))?;
+            if let Some(name) = threshold.request() {
+                if !names.iter().any(|n| n == name) {
+                    return Err(PlanError::on(
+                        "--config",
+                        format!(
+                            "Threshold '{}': não há passo nem requisição chamado '{}'",
+                            threshold.check, name
+                        ),
+                    ));
+                }
+            }
+            thresholds.push(threshold);
+        }
+
+        let (replay, replay_executor) = match &args.replay {
+            Some(path) => {
+                if mode != "http" {
+                    return Err(PlanError::on("--replay", "O replay só roda no modo http"));
+                }
+                if scenario.is_some() {
+                    return Err(PlanError::on(
+                        "--replay",
+                        "O replay não combina com scenario: nem requests:",
+                    ));
+                }
+                // O ritmo vem do log: nada de taxa, prazo ou executor próprio
+                if args.rps.is_some()
+                    || load_profile.is_some()
+                    || test_duration.is_some()
+                    || arrival.is_some()
+                    || adaptive.is_some()
+                    || capacity_search.is_some()
+                {
+                    return Err(PlanError::on(
+                        "--replay",
+                        "O ritmo vem do log: use --replay-speed em vez de --rps, --duration, --ramp-up, --arrival, --adaptive ou --find-capacity",
+                    ));
+                }
+                let speed = match args.replay_speed.trim().to_lowercase().as_str() {
+                    "max" => None,
+                    factor => Some(factor.trim_end_matches('x').parse::<f64>().map_err(|_| {
+                        PlanError::on(
+                            "--replay-speed",
+                            format!("'{}' (use um fator como 2 ou 0.5, ou 'max')", factor),
+                        )
+                    })?),
+                };
+                let log =
+                    ReplayLog::load(path, &target).map_err(|e| PlanError::on("--replay", e))?;
+                let executor = Executor::replay(args.workers, &log, speed)
+                    .map_err(|e| PlanError::on("--replay-speed", e))?;
+                (Some(Arc::new(log)), Some(executor))
+            }
+            None => (None, None),
+        };
+
+        let http_options = HttpOptions {
+            connection_mode: ConnectionMode::parse(&args.connection_mode)
+                .map_err(|e| PlanError::on("--connection-mode", e))?,
+            max_requests_per_connection: args.max_requests_per_connection,
+            max_connection_lifetime: args
+                .max_connection_lifetime
+                .as_deref()
+                .map(crate::utils::parse_duration)
+                .transpose()
+                .map_err(|e| PlanError::on("--max-connection-lifetime", e))?,
+            unix_socket,
+            ..HttpOptions::from(args)
+        };
+        http_options.validate().map_err(PlanError::general)?;
+
+        let source_ips =
+            SourceIps::parse(&args.source_ip).map_err(|e| PlanError::on("--source-ip", e))?;
+        if !source_ips.is_empty() && !matches!(mode.as_str(), "http" | "tcp" | "udp") {
+            return Err(PlanError::on(
+                "--source-ip",
+                format!(
+                    "O modo '{}' abre as próprias conexões; use http, tcp ou udp",
+                    mode
+                ),
+            ));
+        }
+        if !source_ips.is_empty() && unix_target {
+            return Err(PlanError::on(
+                "--source-ip",
+                "Um alvo unix:// não usa endereço de origem",
+            ));
+        }
+        source_ips
+            .check_local()
+            .map_err(|e| PlanError::on("--source-ip", e))?;
+
+        let udp_reply = if args.udp_wait_reply {
+            if mode != "udp" {
+                return Err(PlanError::on("--udp-wait-reply", "Só vale com --mode udp"));
+            }
+            let timeout = crate::utils::parse_duration(&args.udp_reply_timeout)
+                .map_err(|e| PlanError::on("--udp-reply-timeout", e))?;
+            if timeout.is_zero() {
+                return Err(PlanError::on(
+                    "--udp-reply-timeout",
+                    "O prazo precisa ser maior que zero",
+                ));
+            }
+            Some(UdpReply {
+                timeout,
+                match_bytes: args.udp_match_bytes,
+            })
+        } else {
+            if args.udp_match_bytes > 0 {
+                return Err(PlanError::on(
+                    "--udp-match-bytes",
+                    "Só vale com --udp-wait-reply",
+                ));
+            }
+            None
+        };
+        if mode == "udp" && unix_target {
+            return Err(PlanError::on("--url", "O modo udp precisa de host:porta"));
+        }
+
+        let ws_match = if mode == "ws" {
+            if !target.starts_with("ws://") && !target.starts_with("wss://") {
+                return Err(PlanError::on(
+                    "--url",
+                    format!("'{}': o modo ws precisa de ws:// ou wss://", target),
+                ));
+            }
+            Some(WsMatch::parse(&args.ws_match).map_err(|e| PlanError::on("--ws-match", e))?)
+        } else {
+            None
+        };
+
+        let abort_rules = args
+            .abort_on
+            .iter()
+            .map(|spec| AbortRule::parse(spec))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PlanError::on("--abort-on", e))?;
+
+        Ok(Self {
+            mode,
+            target,
+            load_profile,
+            test_duration,
+            arrival,
+            adaptive,
+            capacity_search,
+            drain_timeout,
+            timeline_interval,
+            scenario,
+            thresholds,
+            abort_rules,
+            replay,
+            replay_executor,
+            http_options,
+            source_ips,
+            udp_reply,
+            ws_match,
+        })
+    }
+
+    // Replay > capacidade > adaptativo > modelo aberto (--arrival) > workers fixos (padrão)
+    pub fn executor(&self, args: &Args) -> Executor {
+        if let Some(replay) = self.replay_executor.clone() {
+            replay
+        } else if let Some(search) = self.capacity_search.clone() {
+            Executor::Capacity {
+                workers: args.workers,
+                search,
+            }
+        } else if let Some(algorithm) = self.adaptive {
+            Executor::Adaptive {
+                count: args.count,
+                duration: self.test_duration,
+                max_workers: args.workers,
+                algorithm,
+            }
+        } else if let Some(distribution) = self.arrival {
+            Executor::Open {
+                count: args.count,
+                rps: args.rps,
+                duration: self.test_duration,
+                profile: self.load_profile.clone(),
+                distribution,
+                max_vus: args.max_vus,
+            }
+        } else {
+            Executor::Closed {
+                workers: args.workers,
+                count: args.count,
+                rps: args.rps,
+                duration: self.test_duration,
+                profile: self.load_profile.clone(),
+            }
+        }
+    }
+
+    // Monta o LoadTest (protocolos registrados já conectam aqui)
+    pub async fn load_test(
+        &self,
+        args: &Args,
+        registry: &ProtocolRegistry,
+        csv_tx: Option<mpsc::Sender<CsvRecord>>,
+    ) -> Result<LoadTest, PlanError> {
+        let mut builder = LoadTest::builder()
+            .executor(self.executor(args))
+            .source_ips(self.source_ips.clone())
+            .warmup(Duration::from_secs(args.warmup))
+            .timeline_interval(self.timeline_interval)
+            .drain_timeout(self.drain_timeout);
+        for rule in &self.abort_rules {
+            builder = builder.abort_on(rule.clone());
+        }
+        builder = match self.mode.as_str() {
+            "http" => match (&self.scenario, &self.replay) {
+                (Some(scenario), _) => builder.scenario(scenario.clone()),
+                (None, Some(log)) => builder.replay(log.clone()),
+                (None, None) => builder.http(self.target.clone()).method(
+                    reqwest::Method::from_bytes(args.method.as_bytes())
+                        .unwrap_or(reqwest::Method::GET),
+                ),
+            }
+            .headers(args.headers.clone())
+            .http_options(self.http_options.clone()),
+            "udp" => builder.udp(self.target.clone(), self.udp_reply),
+            "ws" => builder
+                .websocket(
+                    self.target.clone(),
+                    self.ws_match.clone().unwrap_or(WsMatch::Echo),
+                )
+                .headers(args.headers.clone())
+                .http_options(self.http_options.clone()),
+            "tcp" => {
+                let clean_addr = self.target.replace("http://", "").replace("https://", "");
+                builder.tcp(clean_addr, args.workers)
+            }
+            custom => {
+                let ctx = ProtocolContext {
+                    address: self.target.clone(),
+                    connections: args.workers,
+                    timeout: Duration::from_millis(args.timeout),
+                    headers: args.headers.clone(),
+                };
+                let protocol = registry
+                    .connect(custom, &ctx)
+                    .await
+                    .map_err(|e| PlanError::on("--mode", e))?;
+                builder.protocol(protocol)
+            }
+        };
+        if let Some(body) = &args.body {
+            builder = builder.body(body);
+        }
+        if let Some(expected) = &args.expect {
+            builder = builder.expect_body(expected);
+        }
+        if let Some(tx) = csv_tx {
+            builder = builder.csv(tx);
+        }
+
+        builder.build().map_err(PlanError::general)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn plan_from_yaml(name: &str, yaml: &str) -> Result<TestPlan, PlanError> {
+        let path =
+            std::env::temp_dir().join(format!("cannon-{}-{}.yaml", name, std::process::id()));
+        std::fs::write(&path, yaml).unwrap();
+        let mut args = Args::parse_from(["cannon", "--config", path.to_str().unwrap()]);
+        crate::args::config::merge_with_yaml(&mut args).unwrap();
+        let _ = std::fs::remove_file(&path);
+        TestPlan::from_args(&args, &ProtocolRegistry::new())
+    }
+
+    #[test]
+    fn test_yaml_cannot_combine_what_the_cli_refuses() {
+        let err = plan_from_yaml(
+            "duration-stages",
+            "url: http://localhost\nduration: 10s\nstages:\n  - duration: 5s\n    target: 100\n",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.flag, Some("--duration"));
+        assert!(err.message.contains("--ramp-up"));
+
+        let err = plan_from_yaml(
+            "adaptive-rps",
+            "url: http://localhost\nadaptive: aimd\nrps: 100\n",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.flag, Some("--adaptive"));
+
+        assert!(plan_from_yaml("duration", "url: http://localhost\nduration: 10s\n").is_ok());
+    }
+}
@@ -44,6 +44,37 @@ pub fn merge_with_yaml(args: &mut Args) -> Result<(), Box<dyn std::error::Error>
         if let Some(ct) = conf.connect_timeout {
             args.connect_timeout = ct;
         }
+        if let Some(cm) = conf.connection_mode {
+            args.connection_mode = cm;
+        }
+        if let Some(max) = conf.max_requests_per_connection {
+            args.max_requests_per_connection = Some(max);
+        }
+        if let Some(lifetime) = conf.max_connection_lifetime {
+            args.max_connection_lifetime = Some(lifetime);
+        }
+        if let Some(wait) = conf.udp_wait_reply {
+            args.udp_wait_reply = wait;
+        }
+        if let Some(timeout) = conf.udp_reply_timeout {
+            args.udp_reply_timeout = timeout;
+        }
+        if let Some(n) = conf.udp_match_bytes {
+            args.udp_match_bytes = n;
+        }
+        if let Some(matcher) = conf.ws_match {
+            args.ws_match = matcher;
+        }
+        if let Some(host) = conf.unix_host {
+            args.unix_host = host;
+        }
+        if let Some(path) = conf.unix_path {
+            args.unix_path = path;
+        }
+        if let Some(mut ips) = conf.source_ip {
+            ips.append(&mut args.source_ip);
+            args.source_ip = ips;
+        }
 
         if let Some(mut yaml_headers) = conf.headers {
             yaml_headers.append(&mut args.headers);
@@ -52,6 +83,76 @@ pub fn merge_with_yaml(args: &mut Args) -> Result<(), Box<dyn std::error::Error>
         if let Some(mode) = conf.mode {
             args.mode = mode;
         }
+        if let Some(d) = conf.duration {
+            args.duration = Some(d);
+        }
+        if let Some(ramp) = conf.ramp_up {
+            args.ramp_up = Some(ramp);
+        }
+        if let Some(arrival) = conf.arrival {
+            args.arrival = Some(arrival);
+        }
+        if let Some(max_vus) = conf.max_vus {
+            args.max_vus = Some(max_vus);
+        }
+        if let Some(fc) = conf.find_capacity {
+            args.find_capacity = fc;
+        }
+        if let Some(step) = conf.capacity_step {
+            args.capacity_step = step;
+        }
+        if let Some(max) = conf.capacity_max {
+            args.capacity_max = max;
+        }
+        if let Some(d) = conf.step_duration {
+            args.step_duration = d;
+        }
+        if let Some(p99) = conf.slo_p99 {
+            args.slo_p99 = p99;
+        }
+        if let Some(err) = conf.slo_error_rate {
+            args.slo_error_rate = err;
+        }
+        if let Some(algorithm) = conf.adaptive {
+            args.adaptive = Some(algorithm);
+        }
+        if let Some(lt) = conf.latency_target {
+            args.latency_target = lt;
+        }
+        if let Some(dt) = conf.drain_timeout {
+            args.drain_timeout = dt;
+        }
+        if let Some(ti) = conf.timeline_interval {
+            args.timeline_interval = ti;
+        }
+        // Como os headers: as regras do YAML somam com as do --abort-on
+        if let Some(mut rules) = conf.abort_on {
+            rules.append(&mut args.abort_on);
+            args.abort_on = rules;
+        }
+        if let Some(log) = conf.replay {
+            args.replay = Some(log);
+        }
+        if let Some(speed) = conf.replay_speed {
+            args.replay_speed = speed;
+        }
+        if let Some(steps) = conf.scenario {
+            args.scenario = Some(steps);
+        }
+        if let Some(requests) = conf.requests {
+            args.requests = Some(requests);
+        }
+        if let Some(thresholds) = conf.thresholds {
+            args.thresholds = Some(thresholds);
+        }
+        // A lista `stages:` vira a mesma string do --ramp-up (um único parser para os dois)
+        if let Some(stages) = conf.stages {
+            let spec: Vec<String> = stages
+                .iter()
+                .map(|s| format!("{}:{}", s.duration, s.target))
+                .collect();
+            args.ramp_up = Some(spec.join(","));
+        }
     }
 
     Ok(())
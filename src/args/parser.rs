@@ -1,13 +1,18 @@
-use clap::Parser;
-use serde::Deserialize;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
 
-#[derive(Parser, Debug)]
+// Os args viajam inteiros do coordenador para os agentes (modo distribuído)
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
 #[command(
     author,
     version,
     about = "Cannon - Uma ferramenta de teste de carga em Rust"
 )]
 pub struct Args {
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+
     #[arg(short, long)]
     pub url: Option<String>,
 
@@ -41,9 +46,125 @@ pub struct Args {
     #[arg(long)]
     pub expect: Option<String>,
 
-    #[arg(long)]
+    #[arg(
+        short = 'd',
+        long,
+        conflicts_with = "ramp_up",
+        help = "Duração do teste (ex: 30s, 10m, 1h). Ignora --count e roda até o prazo"
+    )]
+    pub duration: Option<String>,
+
+    #[arg(
+        long,
+        help = "Perfil de carga em estágios DURAÇÃO:RPS (ex: 30s:100,2m:500,30s:0)"
+    )]
     pub ramp_up: Option<String>,
 
+    #[arg(
+        long,
+        help = "Modelo aberto: distribuição das chegadas (constant, poisson, uniform:0.5). Exige --rps ou --ramp-up"
+    )]
+    pub arrival: Option<String>,
+
+    #[arg(
+        long,
+        help = "Modelo aberto: máximo de requisições em voo (sem limite por padrão)"
+    )]
+    pub max_vus: Option<u32>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["duration", "ramp_up", "arrival"],
+        help = "Busca a maior taxa sustentável dentro do SLO (degraus de RPS + busca binária)"
+    )]
+    pub find_capacity: bool,
+
+    #[arg(
+        long,
+        default_value_t = 100,
+        help = "Incremento de RPS entre degraus da busca"
+    )]
+    pub capacity_step: u32,
+
+    #[arg(
+        long,
+        default_value_t = 100_000,
+        help = "Teto de RPS da busca de capacidade"
+    )]
+    pub capacity_max: u32,
+
+    #[arg(long, default_value = "30s", help = "Duração de cada degrau da busca")]
+    pub step_duration: String,
+
+    #[arg(
+        long,
+        default_value_t = 500.0,
+        help = "SLO: p99 máximo por degrau (ms)"
+    )]
+    pub slo_p99: f64,
+
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        help = "SLO: taxa de erro máxima por degrau (%)"
+    )]
+    pub slo_error_rate: f64,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["rps", "ramp_up", "arrival", "find_capacity"],
+        help = "Concorrência adaptativa: 'aimd' ou 'gradient' (--workers vira o teto)"
+    )]
+    pub adaptive: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = 200.0,
+        help = "Latência média alvo do AIMD (ms)"
+    )]
+    pub latency_target: f64,
+
+    #[arg(
+        long,
+        default_value = "10s",
+        help = "Após o Ctrl+C, quanto esperar as requisições em voo antes de cancelá-las"
+    )]
+    pub drain_timeout: String,
+
+    #[arg(
+        long,
+        default_value = "1s",
+        help = "Tamanho de cada intervalo da série temporal do relatório (ex: 1s, 500ms, 10s)"
+    )]
+    pub timeline_interval: String,
+
+    #[arg(
+        long = "abort-on",
+        help = "Regra de parada durante o teste (repetível): 'error_rate > 20% for 30s', 'p99 > 2s for 1m', 'connection_failures > 50'"
+    )]
+    pub abort_on: Vec<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["duration", "ramp_up", "arrival", "find_capacity", "adaptive"],
+        help = "Re-dispara as requisições de um log JSONL (timestamp, method, url, headers, body) contra o --url"
+    )]
+    pub replay: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "1",
+        help = "Ritmo do --replay: fator sobre os intervalos originais (2 = o dobro) ou 'max'"
+    )]
+    pub replay_speed: String,
+
+    #[arg(
+        long,
+        conflicts_with = "url",
+        help = "Usa a requisição de um comando curl (URL, -X, -H, -d, -u, -k...); os -H somam"
+    )]
+    pub from_curl: Option<String>,
+
     #[arg(short = 'A', long, default_value = "Cannon/1.0")]
     pub user_agent: String,
 
@@ -75,6 +196,72 @@ pub struct Args {
     )]
     pub connect_timeout: u64,
 
+    #[arg(
+        long,
+        default_value = "shared-pool",
+        help = "Conexões HTTP: 'shared-pool' (um pool para todos), 'per-worker' (uma conexão por worker) ou 'new-per-request' (Connection: close)"
+    )]
+    pub connection_mode: String,
+
+    #[arg(
+        long,
+        help = "Troca a conexão do worker depois de N requisições (só com --connection-mode per-worker)"
+    )]
+    pub max_requests_per_connection: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Troca a conexão do worker depois desta idade, ex: 30s (só com --connection-mode per-worker)"
+    )]
+    pub max_connection_lifetime: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "localhost",
+        help = "Header Host das requisições quando o alvo é um socket Unix (unix:///run/app.sock)"
+    )]
+    pub unix_host: String,
+
+    #[arg(
+        long,
+        default_value = "/",
+        help = "Caminho HTTP requisitado quando o alvo é um socket Unix"
+    )]
+    pub unix_path: String,
+
+    #[arg(
+        long,
+        help = "Modo udp: espera a resposta de cada datagrama (latência = RTT; sem resposta = perda)"
+    )]
+    pub udp_wait_reply: bool,
+
+    #[arg(
+        long,
+        default_value = "1s",
+        help = "Modo udp: prazo da resposta antes de contar o datagrama como perdido"
+    )]
+    pub udp_reply_timeout: String,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Modo udp: a resposta precisa começar com os mesmos N bytes do datagrama (ex: um número de sequência)"
+    )]
+    pub udp_match_bytes: usize,
+
+    #[arg(
+        long,
+        default_value = "echo",
+        help = "Modo ws: como reconhecer a resposta de cada mensagem: 'echo' (a mesma mensagem de volta), 'none' (só envia) ou uma regex com um grupo que extrai o ID de correlação"
+    )]
+    pub ws_match: String,
+
+    #[arg(
+        long = "source-ip",
+        help = "Endereço local de onde saem as conexões, um IP ou um CIDR (ex: 10.0.0.0/28); repita ou separe por vírgula"
+    )]
+    pub source_ip: Vec<String>,
+
     #[arg(
         long,
         default_value = "50,95,99",
@@ -82,7 +269,11 @@ pub struct Args {
     )]
     pub percentiles: String,
 
-    #[arg(long, help = "Protocol mode: 'http' or 'tcp'", default_value = "http")]
+    #[arg(
+        long,
+        help = "Protocol mode: 'http', 'tcp', 'udp', 'ws' or a mode registered via ProtocolRegistry",
+        default_value = "http"
+    )]
     pub mode: String,
 
     #[arg(long, default_value_t = 0, help = "Warmup time in seconds")]
@@ -112,6 +303,125 @@ pub struct Args {
         help = "Modo Deus: Amarra as threads do Tokio aos núcleos físicos (Pinning)"
     )]
     pub pin_threads: bool,
+
+    // Jornada em passos (só pelo YAML, chave `scenario:`)
+    #[arg(skip)]
+    pub scenario: Option<Vec<StepConfig>>,
+
+    // Mix ponderado de requisições (só pelo YAML, chave `requests:`)
+    #[arg(skip)]
+    pub requests: Option<Vec<RequestConfig>>,
+
+    // Limites avaliados no fim do teste (só pelo YAML, chave `thresholds:`)
+    #[arg(skip)]
+    pub thresholds: Option<Vec<ThresholdConfig>>,
+}
+
+// Modo distribuído: os flags do teste vêm antes do subcomando
+// (ex: cannon -u http://alvo -d 1m --rps 3000 coordinate --agents h1:7878,h2:7878)
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    // Fica escutando um coordenador e roda a fatia do teste que ele mandar
+    // (só o loopback por padrão: para aceitar outras máquinas, --listen 0.0.0.0:7878)
+    Agent {
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        listen: String,
+        #[arg(
+            long,
+            required = true,
+            help = "Segredo compartilhado: o agente só roda planos de um coordenador com o mesmo --token"
+        )]
+        token: String,
+    },
+    // Divide o teste entre os agentes, dispara todos juntos e funde os resultados
+    Coordinate {
+        #[arg(long, value_delimiter = ',', required = true)]
+        agents: Vec<String>,
+        #[arg(long, required = true, help = "O --token dos agentes")]
+        token: String,
+    },
+    // Converte tráfego gravado em outras ferramentas num YAML do Cannon (ex: cannon import har s.har -o s.yml)
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportSource {
+    // Sessão gravada no navegador (DevTools → Save all as HAR) vira um `scenario:`
+    Har {
+        file: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Arquivo YAML de saída (sem ele, vai para a saída padrão)"
+        )]
+        output: Option<String>,
+
+        #[arg(
+            long = "domain",
+            help = "Só importa estes domínios (repetível; inclui subdomínios)"
+        )]
+        domains: Vec<String>,
+
+        #[arg(long = "exclude-domain", help = "Ignora estes domínios (repetível)")]
+        exclude_domains: Vec<String>,
+
+        #[arg(
+            long,
+            help = "Mantém arquivos estáticos (js, css, imagens, fontes), ignorados por padrão"
+        )]
+        include_static: bool,
+
+        #[arg(
+            long,
+            default_value = "100ms",
+            help = "Pausas menores que isto não viram think time"
+        )]
+        min_think_time: String,
+
+        #[arg(long, default_value = "10s", help = "Teto de cada think time")]
+        max_think_time: String,
+    },
+    // Uma requisição por operação da spec (OpenAPI 3 ou Swagger 2), num mix `requests:`
+    Openapi {
+        file: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Arquivo YAML de saída (sem ele, vai para a saída padrão)"
+        )]
+        output: Option<String>,
+
+        #[arg(long = "tag", help = "Só importa operações com estas tags (repetível)")]
+        tags: Vec<String>,
+
+        #[arg(long, help = "Importa também as operações marcadas como deprecated")]
+        include_deprecated: bool,
+
+        #[arg(
+            long,
+            default_value_t = 3,
+            help = "Peso das leituras (GET/HEAD) no mix; as escritas pesam 1"
+        )]
+        read_weight: u32,
+    },
+    // "Copy as cURL" vira url/method/headers/body do YAML
+    Curl {
+        #[arg(
+            short,
+            long,
+            help = "Arquivo YAML de saída (sem ele, vai para a saída padrão)"
+        )]
+        output: Option<String>,
+
+        // O comando entre aspas ou, depois de --, já separado pelo shell
+        #[arg(required = true, num_args = 1.., trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -130,12 +440,140 @@ pub struct FileConfig {
     pub csv: Option<String>,
     pub http2: Option<bool>,
     pub connect_timeout: Option<u64>,
+    pub connection_mode: Option<String>,
+    pub max_requests_per_connection: Option<u64>,
+    pub max_connection_lifetime: Option<String>,
+    pub udp_wait_reply: Option<bool>,
+    pub udp_reply_timeout: Option<String>,
+    pub udp_match_bytes: Option<usize>,
+    pub ws_match: Option<String>,
+    pub unix_host: Option<String>,
+    pub unix_path: Option<String>,
+    pub source_ip: Option<Vec<String>>,
     pub mode: Option<String>,
+    pub duration: Option<String>,
+    pub ramp_up: Option<String>,
+    pub stages: Option<Vec<StageConfig>>,
+    pub arrival: Option<String>,
+    pub max_vus: Option<u32>,
+    pub find_capacity: Option<bool>,
+    pub capacity_step: Option<u32>,
+    pub capacity_max: Option<u32>,
+    pub step_duration: Option<String>,
+    pub slo_p99: Option<f64>,
+    pub slo_error_rate: Option<f64>,
+    pub adaptive: Option<String>,
+    pub latency_target: Option<f64>,
+    pub drain_timeout: Option<String>,
+    pub timeline_interval: Option<String>,
+    pub abort_on: Option<Vec<String>>,
+    pub replay: Option<String>,
+    pub replay_speed: Option<String>,
+    #[serde(default)]
     pub warmup: u64,
     pub save_baseline: Option<String>,
     pub compare_baseline: Option<String>,
+    #[serde(default)]
     pub tolerance: f64,
+    #[serde(default)]
     pub pin_threads: bool,
+    pub scenario: Option<Vec<StepConfig>>,
+    pub requests: Option<Vec<RequestConfig>>,
+    pub thresholds: Option<Vec<ThresholdConfig>>,
+}
+
+// Estágio do perfil de carga no YAML (ex: { duration: "30s", target: 100 })
+#[derive(Deserialize, Debug)]
+pub struct StageConfig {
+    pub duration: String,
+    pub target: u32,
+}
+
+// Um passo da jornada. `url` relativa é resolvida contra o --url; url, headers e body
+// aceitam as tags do template e as variáveis extraídas nos passos anteriores ({{token}})
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StepConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default = "default_step_method")]
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expect: Option<String>,
+    // Variável → de onde tirar o valor
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub extract: std::collections::BTreeMap<String, ExtractConfig>,
+    // Pausa do usuário antes do passo (ex: "1.5s"); não entra na latência da jornada
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub think_time: Option<String>,
+}
+
+fn default_step_method() -> String {
+    "GET".to_string()
+}
+
+// Uma requisição do mix: cada disparo sorteia uma delas, na proporção do `weight`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default = "default_step_method")]
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expect: Option<String>,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl RequestConfig {
+    // No motor, uma requisição do mix é um passo sem extrações
+    pub fn as_step(&self) -> StepConfig {
+        StepConfig {
+            name: self.name.clone(),
+            method: self.method.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+            expect: self.expect.clone(),
+            extract: Default::default(),
+            think_time: None,
+        }
+    }
+}
+
+// Um limite: só a expressão ("p95 < 200ms") ou com a parada antecipada
+// ({ check: "error_rate < 5%", abort_on_fail: true, abort_delay: "30s" })
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ThresholdConfig {
+    Check(String),
+    Full {
+        check: String,
+        #[serde(default)]
+        abort_on_fail: bool,
+        abort_delay: Option<String>,
+    },
+}
+
+// Exatamente uma das fontes: `json: "$.data.token"`, `regex: "id=(\\d+)"` ou `header: "Location"`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExtractConfig {
+    pub json: Option<String>,
+    pub regex: Option<String>,
+    pub header: Option<String>,
 }
 
 #[cfg(test)]
@@ -189,4 +627,67 @@ mod tests {
             "O tempo tolerável do Apdex deve ser 50ms por padrão"
         );
     }
+
+    #[test]
+    fn test_yaml_stages_without_optional_flags() {
+        let yaml = "url: http://localhost\nstages:\n  - duration: 30s\n    target: 100\n  - duration: 1m\n    target: 0\n";
+        let conf: FileConfig = serde_yaml::from_str(yaml).unwrap();
+        let stages = conf.stages.unwrap();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].duration, "30s");
+        assert_eq!(stages[1].target, 0);
+        assert_eq!(conf.warmup, 0);
+    }
+
+    #[test]
+    fn test_coordinate_subcommand_takes_test_flags_first() {
+        let args = Args::try_parse_from([
+            "cannon",
+            "-u",
+            "http://localhost",
+            "--rps",
+            "300",
+            "coordinate",
+            "--agents",
+            "10.0.0.1:7878,10.0.0.2:7878",
+            "--token",
+            "s3cret",
+        ])
+        .unwrap();
+        assert_eq!(args.rps, Some(300));
+        match args.command {
+            Some(Command::Coordinate { agents, token }) => {
+                assert_eq!(agents.len(), 2);
+                assert_eq!(token, "s3cret");
+            }
+            other => panic!("subcomando inesperado: {:?}", other),
+        }
+
+        // Sem --token o agente não sobe; o padrão escuta só no loopback
+        assert!(Args::try_parse_from(["cannon", "agent"]).is_err());
+        match Args::try_parse_from(["cannon", "agent", "--token", "s3cret"])
+            .unwrap()
+            .command
+        {
+            Some(Command::Agent { listen, .. }) => assert_eq!(listen, "127.0.0.1:7878"),
+            other => panic!("subcomando inesperado: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_duration_conflicts_with_ramp_up() {
+        let args = Args::try_parse_from(["cannon", "-u", "http://localhost", "-d", "10m"]).unwrap();
+        assert_eq!(args.duration.as_deref(), Some("10m"));
+
+        let conflict = Args::try_parse_from([
+            "cannon",
+            "-u",
+            "http://localhost",
+            "--duration",
+            "10m",
+            "--ramp-up",
+            "30s:100",
+        ]);
+        assert!(conflict.is_err());
+    }
 }
@@ -1,24 +1,381 @@
 use crate::args::parser::Args;
+use crate::client::source::SourceIps;
+use crate::client::target::TargetResult;
+use crate::client::timing::{self, TimedResolver, TimingLayer};
+use reqwest::header::{HeaderMap, HeaderValue, CONNECTION};
 use reqwest::Client;
-use std::time::Duration;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-pub fn build_optimized_client(args: &Args) -> Result<Client, reqwest::Error> {
+// --connection-mode: como os workers dividem as conexões HTTP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionMode {
+    // Um cliente para todos; o pool guarda até --workers conexões ociosas
+    #[default]
+    SharedPool,
+    // Cada worker com o seu cliente e a sua conexão, como um usuário de verdade
+    PerWorker,
+    // Conexão nova a cada requisição (Connection: close, sem pool)
+    NewPerRequest,
+}
+
+impl ConnectionMode {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec.trim().to_lowercase().as_str() {
+            "shared-pool" | "shared" => Ok(Self::SharedPool),
+            "per-worker" | "per-vu" => Ok(Self::PerWorker),
+            "new-per-request" | "close" => Ok(Self::NewPerRequest),
+            other => Err(format!(
+                "Modo '{}' desconhecido (use shared-pool, per-worker ou new-per-request)",
+                other
+            )),
+        }
+    }
+}
+
+// Ajustes do cliente HTTP (o que a CLI expõe como flags)
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    pub pool_size: u32,
+    pub user_agent: String,
+    pub connect_timeout: Duration,
+    pub timeout: Duration,
+    pub insecure: bool,
+    pub http2: bool,
+    pub connection_mode: ConnectionMode,
+    // Rotação (só no per-worker): a conexão do worker é trocada ao atingir um dos limites
+    pub max_requests_per_connection: Option<u64>,
+    pub max_connection_lifetime: Option<Duration>,
+    // Alvo unix:///caminho: todas as conexões vão para este socket (a URL só dá Host e caminho)
+    pub unix_socket: Option<PathBuf>,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            pool_size: 10,
+            user_agent: "Cannon/1.0".to_string(),
+            connect_timeout: Duration::from_millis(5000),
+            timeout: Duration::from_millis(30000),
+            insecure: false,
+            http2: false,
+            connection_mode: ConnectionMode::SharedPool,
+            max_requests_per_connection: None,
+            max_connection_lifetime: None,
+            unix_socket: None,
+        }
+    }
+}
+
+impl From<&Args> for HttpOptions {
+    fn from(args: &Args) -> Self {
+        Self {
+            pool_size: args.workers,
+            user_agent: args.user_agent.clone(),
+            connect_timeout: Duration::from_millis(args.connect_timeout),
+            timeout: Duration::from_millis(args.timeout),
+            insecure: args.insecure,
+            http2: args.http2,
+            ..Default::default()
+        }
+    }
+}
+
+impl HttpOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        let rotates =
+            self.max_requests_per_connection.is_some() || self.max_connection_lifetime.is_some();
+        if rotates && self.connection_mode != ConnectionMode::PerWorker {
+            return Err(
+                "A rotação de conexões (--max-requests-per-connection / --max-connection-lifetime) só vale com --connection-mode per-worker"
+                    .to_string(),
+            );
+        }
+        if self.max_requests_per_connection == Some(0) {
+            return Err("--max-requests-per-connection precisa ser maior que zero".to_string());
+        }
+        if self.max_connection_lifetime.is_some_and(|d| d.is_zero()) {
+            return Err("--max-connection-lifetime precisa ser maior que zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub fn build_client(opts: &HttpOptions) -> Result<Client, reqwest::Error> {
+    build_bound_client(opts, None)
+}
+
+// Cliente cujas conexões saem do endereço local `source` (ver --source-ip)
+pub fn build_bound_client(
+    opts: &HttpOptions,
+    source: Option<IpAddr>,
+) -> Result<Client, reqwest::Error> {
+    let idle_per_host = match opts.connection_mode {
+        ConnectionMode::SharedPool => opts.pool_size as usize,
+        ConnectionMode::PerWorker => 1,
+        ConnectionMode::NewPerRequest => 0,
+    };
     let mut builder = Client::builder()
         .tcp_nodelay(true)
         .tcp_keepalive(Duration::from_secs(60))
-        .pool_max_idle_per_host(args.workers as usize)
+        .local_address(source)
+        .pool_max_idle_per_host(idle_per_host)
         .pool_idle_timeout(Some(Duration::from_secs(90)))
-        .user_agent(&args.user_agent)
-        .connect_timeout(Duration::from_millis(args.connect_timeout))
-        .timeout(Duration::from_millis(args.timeout));
+        .user_agent(&opts.user_agent)
+        .connect_timeout(opts.connect_timeout)
+        .timeout(opts.timeout)
+        // Ganchos das fases da requisição (ver client::timing); o --insecure vai no TLS
+        .dns_resolver(Arc::new(TimedResolver))
+        .connector_layer(TimingLayer)
+        .use_preconfigured_tls(timing::tls_config(opts.insecure, opts.http2));
 
-    if args.insecure {
-        builder = builder.danger_accept_invalid_certs(true);
+    #[cfg(unix)]
+    if let Some(path) = &opts.unix_socket {
+        builder = builder.unix_socket(path.as_path());
     }
 
-    if args.http2 {
+    if opts.http2 {
         builder = builder.http2_prior_knowledge();
+    } else if opts.connection_mode == ConnectionMode::NewPerRequest {
+        // Avisa o servidor também (no HTTP/2 o header é proibido; sem pool já basta)
+        builder = builder.default_headers(HeaderMap::from_iter([(
+            CONNECTION,
+            HeaderValue::from_static("close"),
+        )]));
     }
 
     builder.build()
 }
+
+// Os clientes HTTP de um alvo. No per-worker cada worker ganha o seu na primeira requisição;
+// nos outros modos há um cliente compartilhado por --source-ip (ou um só, sem amarração).
+#[derive(Clone)]
+pub struct HttpClients {
+    shared: Arc<Vec<(Client, Option<IpAddr>)>>,
+    next: Arc<AtomicUsize>,
+    sources: SourceIps,
+    options: Arc<HttpOptions>,
+}
+
+impl HttpClients {
+    pub fn new(opts: &HttpOptions, sources: &SourceIps) -> Result<Self, reqwest::Error> {
+        let shared = if sources.is_empty() {
+            vec![(build_client(opts)?, None)]
+        } else {
+            sources
+                .addrs()
+                .iter()
+                .map(|ip| Ok((build_bound_client(opts, Some(*ip))?, Some(*ip))))
+                .collect::<Result<_, reqwest::Error>>()?
+        };
+        Ok(Self {
+            shared: Arc::new(shared),
+            next: Arc::new(AtomicUsize::new(0)),
+            sources: sources.clone(),
+            options: Arc::new(opts.clone()),
+        })
+    }
+
+    pub fn session(&self) -> Result<HttpSession, reqwest::Error> {
+        let (client, source) = if self.per_worker() {
+            self.build_own()?
+        } else {
+            self.next_shared()
+        };
+        Ok(HttpSession {
+            clients: self.clone(),
+            client,
+            source,
+            requests: 0,
+            opened: Instant::now(),
+        })
+    }
+
+    fn per_worker(&self) -> bool {
+        self.options.connection_mode == ConnectionMode::PerWorker
+    }
+
+    // Rodízio entre os clientes compartilhados (um por endereço de origem)
+    fn next_shared(&self) -> (Client, Option<IpAddr>) {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        self.shared[i % self.shared.len()].clone()
+    }
+
+    // Cliente próprio, saindo do próximo endereço de origem. As mesmas opções já montaram os
+    // clientes compartilhados em `new`, então opções ruins param o teste na largada; um erro
+    // aqui (ex.: sem descritores para o TLS) vira falha do disparo, nunca um cliente compartilhado
+    fn build_own(&self) -> Result<(Client, Option<IpAddr>), reqwest::Error> {
+        let source = self.sources.next();
+        Ok((build_bound_client(&self.options, source)?, source))
+    }
+}
+
+// O cliente HTTP de um worker e a conta da conexão corrente (para a rotação)
+pub struct HttpSession {
+    clients: HttpClients,
+    client: Client,
+    source: Option<IpAddr>,
+    requests: u64,
+    opened: Instant,
+}
+
+impl HttpSession {
+    // O cliente do próximo disparo. No per-worker, se a conexão venceu, um cliente novo (e o
+    // velho a fecha); com vários --source-ip nos outros modos, o próximo do rodízio.
+    pub fn client(&mut self) -> Result<&Client, reqwest::Error> {
+        if self.clients.per_worker() {
+            let opts = &self.clients.options;
+            let spent = opts
+                .max_requests_per_connection
+                .is_some_and(|max| self.requests >= max);
+            let expired = opts
+                .max_connection_lifetime
+                .is_some_and(|max| self.requests > 0 && self.opened.elapsed() >= max);
+            if spent || expired {
+                (self.client, self.source) = self.clients.build_own()?;
+                self.requests = 0;
+            }
+        } else if self.clients.shared.len() > 1 {
+            (self.client, self.source) = self.clients.next_shared();
+        }
+        Ok(&self.client)
+    }
+
+    // De onde saiu a conexão do último disparo (só com --source-ip)
+    pub fn source(&self) -> Option<IpAddr> {
+        self.source
+    }
+
+    // Conta as trocas do disparo; uma conexão nova (a nossa ou reaberta pelo servidor) zera a conta
+    pub fn observe(&mut self, res: &TargetResult) {
+        for phases in res.phases() {
+            if phases.new_connection() {
+                self.requests = 0;
+                self.opened = Instant::now();
+            }
+            self.requests += 1;
+        }
+    }
+}
+
+pub fn build_optimized_client(args: &Args) -> Result<Client, reqwest::Error> {
+    build_client(&HttpOptions::from(args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, LoadTest};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Keep-alive: responde a cada leitura na mesma conexão até o cliente fechar
+    async fn keep_alive_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        let close = String::from_utf8_lossy(&buf[..n])
+                            .to_lowercase()
+                            .contains("connection: close");
+                        if n == 0
+                            || socket
+                                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                                .await
+                                .is_err()
+                            || close
+                        {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    async fn connections_opened(url: &str, options: HttpOptions) -> u64 {
+        let result = LoadTest::builder()
+            .http(url)
+            .http_options(options)
+            .executor(Executor::iterations(1, 20))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(result.successes, 20);
+        result.phases.new_connections
+    }
+
+    #[tokio::test]
+    async fn test_connection_modes_and_rotation() {
+        let url = keep_alive_server().await;
+        let mode = |connection_mode| HttpOptions {
+            connection_mode,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            connections_opened(&url, mode(ConnectionMode::SharedPool)).await,
+            1
+        );
+        assert_eq!(
+            connections_opened(&url, mode(ConnectionMode::NewPerRequest)).await,
+            20
+        );
+        let rotating = HttpOptions {
+            max_requests_per_connection: Some(5),
+            ..mode(ConnectionMode::PerWorker)
+        };
+        assert_eq!(connections_opened(&url, rotating).await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_per_worker_client_errors_stop_the_start() {
+        let options = HttpOptions {
+            connection_mode: ConnectionMode::PerWorker,
+            user_agent: "cannon\n".to_string(),
+            ..Default::default()
+        };
+        let clients = HttpClients::new(&options, &SourceIps::default());
+        assert!(clients.is_err());
+
+        let started = LoadTest::builder()
+            .http("http://127.0.0.1:9/")
+            .http_options(options)
+            .executor(Executor::iterations(1, 1))
+            .build()
+            .unwrap()
+            .start()
+            .await;
+        assert!(started.is_err_and(|e| e.contains("Falha ao criar o cliente HTTP")));
+    }
+
+    #[test]
+    fn test_rotation_needs_per_worker() {
+        assert_eq!(
+            ConnectionMode::parse("Per-Worker").unwrap(),
+            ConnectionMode::PerWorker
+        );
+        assert!(ConnectionMode::parse("pooled").is_err());
+
+        let options = HttpOptions {
+            max_connection_lifetime: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+        assert!(HttpOptions {
+            connection_mode: ConnectionMode::PerWorker,
+            ..options
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -1,2 +1,8 @@
 pub mod http;
+pub mod protocol;
+pub mod source;
+pub mod stream;
 pub mod target;
+pub mod timing;
+pub mod udp;
+pub mod ws;
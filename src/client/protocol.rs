@@ -0,0 +1,144 @@
+// src/client/protocol.rs
+
+use crate::client::target::TargetResult;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Modos que o próprio Target resolve por match (static dispatch, sem vtable)
+pub const BUILTIN_MODES: [&str; 4] = ["http", "tcp", "udp", "ws"];
+
+// Protocolo plugável: um crate externo implementa isto e o Cannon cuida do resto
+// (workers, SharedMetrics, cancelamento no drain e relatórios)
+#[async_trait]
+pub trait Protocol: Send + Sync {
+    // Um disparo com o payload já renderizado. A duração medida aqui é a latência registrada.
+    async fn fire(&self, payload: &[u8]) -> TargetResult;
+}
+
+// O que a CLI sabe sobre o alvo na hora de montar um protocolo registrado
+#[derive(Debug, Clone)]
+pub struct ProtocolContext {
+    // O valor cru do --url (sem validação de HTTP)
+    pub address: String,
+    // Tamanho do pool sugerido (--workers)
+    pub connections: u32,
+    pub timeout: Duration,
+    // Os -H / headers do YAML, no formato "Nome: valor"
+    pub headers: Vec<String>,
+}
+
+// Constrói o protocolo antes do teste (é aqui que pools de conexão são abertos)
+#[async_trait]
+pub trait ProtocolFactory: Send + Sync {
+    async fn connect(&self, ctx: &ProtocolContext) -> Result<Arc<dyn Protocol>, String>;
+}
+
+// Nome do --mode → fábrica. Os modos embutidos não passam por aqui.
+#[derive(Default, Clone)]
+pub struct ProtocolRegistry {
+    factories: BTreeMap<String, Arc<dyn ProtocolFactory>>,
+}
+
+impl ProtocolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        mode: impl Into<String>,
+        factory: impl ProtocolFactory + 'static,
+    ) -> Result<(), String> {
+        let mode = mode.into().to_lowercase();
+        if BUILTIN_MODES.contains(&mode.as_str()) {
+            return Err(format!(
+                "O modo '{}' é embutido e não pode ser substituído",
+                mode
+            ));
+        }
+        self.factories.insert(mode, Arc::new(factory));
+        Ok(())
+    }
+
+    pub fn contains(&self, mode: &str) -> bool {
+        self.factories.contains_key(&mode.to_lowercase())
+    }
+
+    // Todos os modos aceitos pelo --mode, embutidos primeiro
+    pub fn modes(&self) -> Vec<String> {
+        BUILTIN_MODES
+            .iter()
+            .map(|m| m.to_string())
+            .chain(self.factories.keys().cloned())
+            .collect()
+    }
+
+    pub async fn connect(
+        &self,
+        mode: &str,
+        ctx: &ProtocolContext,
+    ) -> Result<Arc<dyn Protocol>, String> {
+        let factory = self.factories.get(&mode.to_lowercase()).ok_or_else(|| {
+            format!(
+                "Modo desconhecido '{}' (disponíveis: {})",
+                mode,
+                self.modes().join(", ")
+            )
+        })?;
+        factory.connect(ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    #[async_trait]
+    impl Protocol for Echo {
+        async fn fire(&self, payload: &[u8]) -> TargetResult {
+            TargetResult::success(Duration::from_millis(1), payload.len() as u64, 0)
+        }
+    }
+
+    struct EchoFactory;
+
+    #[async_trait]
+    impl ProtocolFactory for EchoFactory {
+        async fn connect(&self, _ctx: &ProtocolContext) -> Result<Arc<dyn Protocol>, String> {
+            Ok(Arc::new(Echo))
+        }
+    }
+
+    fn ctx() -> ProtocolContext {
+        ProtocolContext {
+            address: "in-house://svc".to_string(),
+            connections: 1,
+            timeout: Duration::from_secs(1),
+            headers: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_resolves_custom_modes_case_insensitively() {
+        let mut registry = ProtocolRegistry::new();
+        registry.register("Echo", EchoFactory).unwrap();
+
+        assert!(registry.contains("ECHO"));
+        assert_eq!(registry.modes(), vec!["http", "tcp", "udp", "ws", "echo"]);
+        let proto = registry.connect("echo", &ctx()).await.unwrap();
+        assert_eq!(proto.fire(b"abc").await.bytes_sent, 3);
+
+        let err = registry.connect("grpc", &ctx()).await.err().unwrap();
+        assert!(err.contains("http, tcp, udp, ws, echo"));
+    }
+
+    #[test]
+    fn test_builtin_modes_cannot_be_replaced() {
+        let mut registry = ProtocolRegistry::new();
+        assert!(registry.register("http", EchoFactory).is_err());
+    }
+}
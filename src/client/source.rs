@@ -0,0 +1,166 @@
+// src/client/source.rs
+//
+// --source-ip: endereços locais de onde as conexões saem. Cada IP de origem tem as suas ~28 mil
+// portas efêmeras para o mesmo destino; com vários, milhares de conexões não esgotam as portas.
+// As conexões são distribuídas em rodízio entre os endereços.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::net::{TcpSocket, TcpStream};
+
+// Um CIDR maior que isso é quase certamente engano (e nenhuma máquina tem tantos IPs)
+const MAX_ADDRESSES: u128 = 65_536;
+
+#[derive(Debug, Clone, Default)]
+pub struct SourceIps {
+    addrs: Arc<Vec<IpAddr>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl SourceIps {
+    // Cada item é um IP ("10.0.0.2") ou um CIDR ("10.0.0.0/29": só os hosts, sem rede e broadcast)
+    pub fn parse(specs: &[String]) -> Result<Self, String> {
+        let mut addrs = Vec::new();
+        for spec in specs.iter().flat_map(|s| s.split(',')) {
+            let spec = spec.trim();
+            if spec.is_empty() {
+                continue;
+            }
+            for ip in expand(spec)? {
+                if !addrs.contains(&ip) {
+                    addrs.push(ip);
+                }
+            }
+        }
+        Ok(Self {
+            addrs: Arc::new(addrs),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addrs.is_empty()
+    }
+
+    pub fn addrs(&self) -> &[IpAddr] {
+        &self.addrs
+    }
+
+    // O próximo endereço do rodízio (None sem --source-ip: o sistema escolhe)
+    pub fn next(&self) -> Option<IpAddr> {
+        if self.addrs.is_empty() {
+            return None;
+        }
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        Some(self.addrs[i % self.addrs.len()])
+    }
+
+    // Antes do teste: todo endereço precisa estar configurado numa interface desta máquina
+    pub fn check_local(&self) -> Result<(), String> {
+        for ip in self.addrs.iter() {
+            std::net::TcpListener::bind((*ip, 0))
+                .map_err(|e| format!("{} não é um endereço desta máquina ({})", ip, e))?;
+        }
+        Ok(())
+    }
+
+    // Conexão TCP saindo do próximo endereço do rodízio
+    pub async fn connect(&self, address: &str) -> std::io::Result<TcpStream> {
+        let Some(local) = self.next() else {
+            return TcpStream::connect(address).await;
+        };
+        let remote = tokio::net::lookup_host(address)
+            .await?
+            .find(|a| a.is_ipv4() == local.is_ipv4())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::AddrNotAvailable,
+                    format!("{} não tem endereço da mesma família de {}", address, local),
+                )
+            })?;
+        let socket = match local {
+            IpAddr::V4(_) => TcpSocket::new_v4()?,
+            IpAddr::V6(_) => TcpSocket::new_v6()?,
+        };
+        socket.bind(SocketAddr::new(local, 0))?;
+        socket.connect(remote).await
+    }
+}
+
+fn expand(spec: &str) -> Result<Vec<IpAddr>, String> {
+    let Some((ip, prefix)) = spec.split_once('/') else {
+        return spec
+            .parse()
+            .map(|ip| vec![ip])
+            .map_err(|_| format!("'{}' não é um IP nem um CIDR", spec));
+    };
+    let ip: IpAddr = ip
+        .parse()
+        .map_err(|_| format!("'{}' não é um IP nem um CIDR", spec))?;
+    let bits = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix: u32 = prefix
+        .parse()
+        .ok()
+        .filter(|p| *p <= bits)
+        .ok_or_else(|| format!("Prefixo inválido em '{}' (0 a {})", spec, bits))?;
+
+    let host_bits = bits - prefix;
+    if host_bits >= 128 || (1u128 << host_bits) > MAX_ADDRESSES {
+        return Err(format!(
+            "'{}' tem endereços demais (no máximo {})",
+            spec, MAX_ADDRESSES
+        ));
+    }
+    let size = 1u128 << host_bits;
+    let base = match ip {
+        IpAddr::V4(v4) => u32::from(v4) as u128,
+        IpAddr::V6(v6) => u128::from(v6),
+    } & !(size - 1);
+    // No IPv4 o primeiro e o último são rede e broadcast (menos nos /31 e /32)
+    let (first, last) = if ip.is_ipv4() && host_bits >= 2 {
+        (base + 1, base + size - 2)
+    } else {
+        (base, base + size - 1)
+    };
+    Ok((first..=last)
+        .map(|n| match ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(n as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(n)),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ips_and_ranges() {
+        let specs = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let ips = SourceIps::parse(&specs(&["10.0.0.0/29", "10.0.0.3,192.168.1.7"])).unwrap();
+        let shown: Vec<String> = ips.addrs().iter().map(|ip| ip.to_string()).collect();
+        assert_eq!(
+            shown,
+            [
+                "10.0.0.1",
+                "10.0.0.2",
+                "10.0.0.3",
+                "10.0.0.4",
+                "10.0.0.5",
+                "10.0.0.6",
+                "192.168.1.7"
+            ]
+        );
+
+        let v6 = SourceIps::parse(&specs(&["fd00::10/126"])).unwrap();
+        assert_eq!(v6.addrs().len(), 4);
+        assert_eq!(v6.next(), Some("fd00::10".parse().unwrap()));
+        assert_eq!(v6.next(), Some("fd00::11".parse().unwrap()));
+
+        assert!(SourceIps::parse(&specs(&["10.0.0.0/8"])).is_err());
+        assert!(SourceIps::parse(&specs(&["10.0.0.1/33"])).is_err());
+        assert!(SourceIps::parse(&specs(&["eth0"])).is_err());
+        assert_eq!(SourceIps::default().next(), None);
+    }
+}
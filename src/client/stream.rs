@@ -0,0 +1,150 @@
+// src/client/stream.rs
+//
+// Conexão do modo cru (--mode tcp): um socket TCP ou, com alvo unix:///caminho, um socket Unix.
+// O pool e a reconexão do Target::Tcp não sabem qual dos dois estão segurando.
+
+use crate::client::source::SourceIps;
+use std::io;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+const UNIX_SCHEME: &str = "unix://";
+
+// "unix:///run/app.sock" → "/run/app.sock"
+pub fn unix_path(target: &str) -> Option<&str> {
+    target.strip_prefix(UNIX_SCHEME)
+}
+
+// O caminho de um alvo unix:// precisa ser absoluto (unix:///run/app.sock)
+pub fn check_unix_target(target: &str) -> Result<(), String> {
+    match unix_path(target) {
+        Some(path) if !path.starts_with('/') => Err(format!(
+            "'{}': o caminho do socket precisa ser absoluto (ex: unix:///run/app.sock)",
+            target
+        )),
+        Some(_) if cfg!(not(unix)) => {
+            Err("Sockets Unix (unix://) não existem nesta plataforma".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub enum RawStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl RawStream {
+    // Endereço `host:porta` ou `unix:///caminho`; o TCP sai do próximo --source-ip
+    pub async fn connect(address: &str, sources: &SourceIps) -> io::Result<Self> {
+        match unix_path(address) {
+            #[cfg(unix)]
+            Some(path) => Ok(Self::Unix(UnixStream::connect(path).await?)),
+            #[cfg(not(unix))]
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Sockets Unix não existem nesta plataforma",
+            )),
+            None => Ok(Self::Tcp(sources.connect(address).await?)),
+        }
+    }
+
+    // O IP local da conexão (socket Unix não tem)
+    pub fn local_ip(&self) -> Option<IpAddr> {
+        match self {
+            Self::Tcp(stream) => stream.local_addr().ok().map(|a| a.ip()),
+            #[cfg(unix)]
+            Self::Unix(_) => None,
+        }
+    }
+}
+
+impl AsyncRead for RawStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for RawStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(unix)]
+            Self::Unix(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            #[cfg(unix)]
+            Self::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{Executor, LoadTest};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    #[tokio::test]
+    async fn test_raw_mode_over_unix_socket() {
+        let path = std::env::temp_dir().join(format!("cannon-raw-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 64];
+                    while socket.read(&mut buf).await.unwrap_or(0) > 0 {
+                        let _ = socket.write_all(b"k").await;
+                    }
+                });
+            }
+        });
+
+        let result = LoadTest::builder()
+            .tcp(format!("unix://{}", path.display()), 2)
+            .body("ping")
+            .executor(Executor::iterations(2, 10))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(result.successes, 10);
+
+        assert!(check_unix_target("unix://run/app.sock").is_err());
+        assert!(check_unix_target("unix:///run/app.sock").is_ok());
+    }
+}
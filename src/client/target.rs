@@ -1,8 +1,18 @@
+use crate::client::http::{HttpClients, HttpSession};
+use crate::client::protocol::Protocol;
+use crate::client::source::SourceIps;
+use crate::client::stream::RawStream;
+use crate::client::timing::{self, PhaseTimings};
+use crate::client::udp::{DatagramOutcome, UdpSession, UdpTarget};
+use crate::client::ws::{WsConnection, WsOutcome, WsTarget};
+use crate::replay::ReplayLog;
+use crate::scenario::Scenario;
 use async_channel::{Receiver, Sender};
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio_util::sync::CancellationToken;
 
 pub struct TargetResult {
     pub success: bool,
@@ -12,6 +22,25 @@ pub struct TargetResult {
     pub bytes_sent: u64,
     pub bytes_received: u64,
     pub assertion_success: bool,
+    // Só em cenários: um resultado por passo executado (a jornada para no primeiro que falhar).
+    // No mix ponderado, um único resultado com a posição da requisição sorteada.
+    pub steps: Vec<StepResult>,
+    // Fases da troca HTTP (DNS, TCP, TLS, TTFB, download); nas jornadas vêm por passo
+    pub phases: Option<PhaseTimings>,
+    // Só no --mode udp: resposta, duplicatas e atrasadas do datagrama
+    pub datagram: Option<DatagramOutcome>,
+    // Só no --mode ws: handshake, resposta e desconexão da conexão do worker
+    pub ws: Option<WsOutcome>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepResult {
+    // Posição do passo / requisição na configuração
+    pub index: usize,
+    pub success: bool,
+    pub duration: Duration,
+    pub status_code: Option<u16>,
+    pub phases: Option<PhaseTimings>,
 }
 
 impl TargetResult {
@@ -24,6 +53,10 @@ impl TargetResult {
             bytes_sent,
             bytes_received,
             assertion_success: true,
+            steps: Vec::new(),
+            phases: None,
+            datagram: None,
+            ws: None,
         }
     }
     pub fn fail(duration: Duration, error: String) -> Self {
@@ -35,37 +68,96 @@ impl TargetResult {
             bytes_sent: 0,
             bytes_received: 0,
             assertion_success: false,
+            steps: Vec::new(),
+            phases: None,
+            datagram: None,
+            ws: None,
         }
     }
+
+    // As trocas HTTP do disparo: a do próprio resultado ou, nas jornadas, as dos passos
+    pub fn phases(&self) -> impl Iterator<Item = &PhaseTimings> {
+        self.phases
+            .iter()
+            .chain(self.steps.iter().filter_map(|s| s.phases.as_ref()))
+    }
+
+    pub fn new_connections(&self) -> u64 {
+        self.phases().filter(|p| p.new_connection()).count() as u64
+    }
+}
+
+// O estado de um worker entre disparos: o seu cliente HTTP (ver --connection-mode), o seu
+// socket UDP ou conexão WebSocket e de qual endereço local saiu o último disparo (ver --source-ip)
+#[derive(Default)]
+pub struct Session {
+    http: Option<HttpSession>,
+    udp: Option<UdpSession>,
+    ws: Option<WsConnection>,
+    source: Option<IpAddr>,
+}
+
+impl Session {
+    // O cliente HTTP do próximo disparo (o do worker é montado no primeiro) e de onde ele sai
+    fn http_client(&mut self, clients: &HttpClients) -> Result<reqwest::Client, reqwest::Error> {
+        let http = match &mut self.http {
+            Some(http) => http,
+            None => self.http.insert(clients.session()?),
+        };
+        let client = http.client()?.clone();
+        self.source = http.source();
+        Ok(client)
+    }
 }
 
 // --- ENUM POLIMÓRFICO (STATIC DISPATCH - ZERO VTABLE OVERHEAD) ---
 pub enum Target {
     Http {
-        client: reqwest::Client,
+        clients: HttpClients,
         url: String,
         method: reqwest::Method,
         headers: Arc<Vec<String>>,
         expected_body: Option<Arc<String>>,
     },
+    // Modo cru: socket TCP ou Unix (unix:///caminho), ver client::stream
     Tcp {
-        pool_tx: Sender<TcpStream>,
-        pool_rx: Receiver<TcpStream>,
+        pool_tx: Sender<RawStream>,
+        pool_rx: Receiver<RawStream>,
         address: String,
+        sources: SourceIps,
+    },
+    // Datagramas (--mode udp), com espera opcional pela resposta
+    Udp(UdpTarget),
+    // WebSocket (--mode ws): uma conexão por worker, cada disparo é uma mensagem e a sua resposta
+    Ws(WsTarget),
+    // Protocolos de terceiros (ver client::protocol): aqui sim há uma chamada dinâmica
+    Custom(Arc<dyn Protocol>),
+    // Jornada em passos: um disparo = o usuário virtual percorrendo todos os passos
+    Scenario {
+        clients: HttpClients,
+        scenario: Arc<Scenario>,
+        headers: Arc<Vec<String>>,
+    },
+    // Replay de um log: cada disparo é a próxima linha do arquivo
+    Replay {
+        clients: HttpClients,
+        log: Arc<ReplayLog>,
+        headers: Arc<Vec<String>>,
+        expected_body: Option<Arc<String>>,
     },
 }
 
 impl Target {
     // Factory method para HTTP (Aceita os Arcs diretamente)
     pub fn new_http(
-        client: reqwest::Client,
+        clients: HttpClients,
         url: String,
         method: reqwest::Method,
         headers: Arc<Vec<String>>,
         expected_body: Option<Arc<String>>,
     ) -> Self {
         Self::Http {
-            client,
+            clients,
             url,
             method,
             headers,
@@ -73,12 +165,12 @@ impl Target {
         }
     }
 
-    // Factory method para TCP (Assíncrono, constrói o Pool e devolve Result)
-    pub async fn new_tcp(address: &str, workers: u32) -> Result<Self, String> {
+    // Factory method para TCP (Assíncrono, constrói o Pool e devolve Result). `address` é
+    // host:porta ou unix:///caminho; com --source-ip o TCP sai em rodízio dos endereços locais.
+    pub async fn new_tcp(address: &str, workers: u32, sources: SourceIps) -> Result<Self, String> {
         let (tx, rx) = async_channel::bounded(workers as usize);
-        println!("🔌 Estabelecendo pool de {} conexões TCP...", workers);
         for _ in 0..workers {
-            match TcpStream::connect(address).await {
+            match RawStream::connect(address, &sources).await {
                 Ok(stream) => {
                     let _ = tx.send(stream).await;
                 }
@@ -89,32 +181,81 @@ impl Target {
             pool_tx: tx,
             pool_rx: rx,
             address: address.to_string(),
+            sources,
         })
     }
 
+    pub fn custom(protocol: Arc<dyn Protocol>) -> Self {
+        Self::Custom(protocol)
+    }
+
+    fn client_failed(start: std::time::Instant, e: reqwest::Error) -> TargetResult {
+        TargetResult::fail(
+            start.elapsed(),
+            format!("Falha ao montar o cliente HTTP: {}", e),
+        )
+    }
+
     #[inline(always)]
-    fn trigger_reconnect(pool_tx: async_channel::Sender<TcpStream>, address: String) {
+    fn trigger_reconnect(
+        pool_tx: async_channel::Sender<RawStream>,
+        address: String,
+        sources: SourceIps,
+    ) {
         tokio::spawn(async move {
-            if let Ok(new_stream) = TcpStream::connect(&address).await {
+            if let Ok(new_stream) = RawStream::connect(&address, &sources).await {
                 let _ = pool_tx.send(new_stream).await;
             }
         });
     }
 
-    // O compilador injeta esse match direto no loop do Worker!
+    // Dispara uma vez. Se `cancel` disparar no meio (fim do drain / abort), a requisição
+    // em voo é abandonada e volta como falha "Cancelado".
     #[inline(always)]
-    pub async fn fire(&self, payload: &[u8]) -> TargetResult {
+    pub async fn fire(
+        &self,
+        session: &mut Session,
+        payload: &[u8],
+        cancel: &CancellationToken,
+    ) -> TargetResult {
         let start = std::time::Instant::now();
+        session.source = None;
 
+        let mut res = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => TargetResult::fail(start.elapsed(), "Cancelado".to_string()),
+            res = self.fire_inner(session, payload, start) => res,
+        };
+        if let Some(http) = &mut session.http {
+            http.observe(&res);
+        }
+        // Com --source-ip o relatório de erros separa por endereço de origem
+        if let (Some(source), Some(error)) = (session.source, &mut res.error) {
+            *error = format!("[{}] {}", source, error);
+        }
+        res
+    }
+
+    // O compilador injeta esse match direto no loop do Worker!
+    #[inline(always)]
+    async fn fire_inner(
+        &self,
+        session: &mut Session,
+        payload: &[u8],
+        start: std::time::Instant,
+    ) -> TargetResult {
         match self {
             Target::Http {
-                client,
+                clients,
                 url,
                 method,
                 headers,
                 expected_body,
             } => {
-                let mut req = client.request(method.clone(), url);
+                let mut req = match session.http_client(clients) {
+                    Ok(client) => client.request(method.clone(), url),
+                    Err(e) => return Self::client_failed(start, e),
+                };
                 if !payload.is_empty() {
                     req = req.body(payload.to_vec());
                 }
@@ -124,25 +265,30 @@ impl Target {
                     }
                 }
 
-                match req.send().await {
+                let (res, mut phases) = timing::send(req).await;
+                match res {
                     Ok(resp) => {
                         let status = resp.status().as_u16();
-                        let (error_msg, bytes_recv, assert_ok) = match resp.bytes().await {
-                            Ok(bytes) => {
-                                let mut err = None;
-                                let mut ok = true;
-                                if let Some(expected) = expected_body {
-                                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                                        if !text.contains(expected.as_ref().as_str()) {
-                                            err = Some(format!("Mismatch: missing '{}'", expected));
-                                            ok = false;
+                        let (error_msg, bytes_recv, assert_ok) =
+                            match timing::read_body(resp, &mut phases).await {
+                                Ok(bytes) => {
+                                    let mut err = None;
+                                    let mut ok = true;
+                                    if let Some(expected) = expected_body {
+                                        if let Ok(text) = std::str::from_utf8(&bytes) {
+                                            if !text.contains(expected.as_ref().as_str()) {
+                                                err = Some(format!(
+                                                    "Mismatch: missing '{}'",
+                                                    expected
+                                                ));
+                                                ok = false;
+                                            }
                                         }
                                     }
+                                    (err, bytes.len() as u64, ok)
                                 }
-                                (err, bytes.len() as u64, ok)
-                            }
-                            Err(e) => (Some(format!("Read Error: {}", e)), 0, false),
-                        };
+                                Err(e) => (Some(format!("Read Error: {}", e)), 0, false),
+                            };
                         TargetResult {
                             duration: start.elapsed(),
                             status_code: Some(status),
@@ -151,6 +297,10 @@ impl Target {
                             bytes_received: bytes_recv,
                             success: (200..300).contains(&status) && assert_ok,
                             assertion_success: assert_ok,
+                            steps: Vec::new(),
+                            phases: Some(phases),
+                            datagram: None,
+                            ws: None,
                         }
                     }
                     Err(e) => TargetResult::fail(start.elapsed(), format!("Network Error: {}", e)),
@@ -161,12 +311,16 @@ impl Target {
                 pool_tx,
                 pool_rx,
                 address,
+                sources,
             } => {
                 if let Ok(mut stream) = pool_rx.recv().await {
+                    if !sources.is_empty() {
+                        session.source = stream.local_ip();
+                    }
                     // 1. Escreve APENAS os bytes puros do template. Sem \n, sem magia.
                     // 1. Tenta escrever. Se falhar, a conexão caiu. Dispara a cura!
                     if let Err(e) = stream.write_all(payload).await {
-                        Self::trigger_reconnect(pool_tx.clone(), address.clone());
+                        Self::trigger_reconnect(pool_tx.clone(), address.clone(), sources.clone());
                         return TargetResult::fail(start.elapsed(), format!("Broken Pipe: {}", e));
                     }
                     let _ = stream.flush().await;
@@ -174,7 +328,7 @@ impl Target {
                     // 2. Tenta ler o ACK. Se falhar, o alvo fechou a porta na nossa cara. Cura!
                     let mut buffer = [0; 1];
                     if let Err(e) = stream.read_exact(&mut buffer).await {
-                        Self::trigger_reconnect(pool_tx.clone(), address.clone());
+                        Self::trigger_reconnect(pool_tx.clone(), address.clone(), sources.clone());
                         return TargetResult::fail(
                             start.elapsed(),
                             format!("Connection Reset: {}", e),
@@ -189,6 +343,59 @@ impl Target {
                     TargetResult::fail(start.elapsed(), "TCP Pool Exhausted".to_string())
                 }
             }
+
+            Target::Udp(udp) => {
+                if session.udp.is_none() {
+                    match udp.open().await {
+                        Ok(socket) => session.udp = Some(socket),
+                        Err(e) => {
+                            return TargetResult::fail(
+                                start.elapsed(),
+                                format!("Socket Error: {}", e),
+                            )
+                        }
+                    }
+                }
+                let socket = session.udp.as_mut().unwrap();
+                if !udp.sources.is_empty() {
+                    session.source = socket.local_ip();
+                }
+                socket.fire(payload, udp.reply, start).await
+            }
+
+            Target::Ws(ws) => ws.fire(&mut session.ws, payload, start).await,
+
+            Target::Custom(protocol) => protocol.fire(payload).await,
+
+            Target::Scenario {
+                clients,
+                scenario,
+                headers,
+            } => {
+                let client = match session.http_client(clients) {
+                    Ok(client) => client,
+                    Err(e) => return Self::client_failed(start, e),
+                };
+                scenario.run(&client, headers).await
+            }
+
+            Target::Replay {
+                clients,
+                log,
+                headers,
+                expected_body,
+            } => {
+                let client = match session.http_client(clients) {
+                    Ok(client) => client,
+                    Err(e) => return Self::client_failed(start, e),
+                };
+                log.fire(
+                    &client,
+                    headers,
+                    expected_body.as_deref().map(String::as_str),
+                )
+                .await
+            }
         }
     }
 }
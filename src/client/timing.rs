@@ -0,0 +1,339 @@
+// src/client/timing.rs
+//
+// Fases de uma requisição HTTP: DNS, conexão TCP, handshake TLS, tempo até o primeiro byte e
+// download do corpo. O reqwest só devolve a resposta pronta, então o cliente é montado com três
+// ganchos que anotam instantes num task-local aberto por `send`:
+//   - o resolvedor de DNS (quanto a resolução levou);
+//   - uma camada em volta do conector (quando a conexão ficou pronta);
+//   - o `has_certs` do rustls, chamado uma vez no começo de cada handshake (fim do TCP).
+// Nada é anotado quando o pool entrega uma conexão aberta: é assim que a reutilização aparece.
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::ResolvesClientCert;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::sign::CertifiedKey;
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseTimings {
+    // Só em conexões novas; o DNS também fica vazio quando o host já é um IP
+    pub dns: Option<Duration>,
+    pub connect: Option<Duration>,
+    // Só em https
+    pub tls: Option<Duration>,
+    // Da conexão pronta (ou do envio, se reutilizada) até os headers da resposta
+    pub ttfb: Duration,
+    pub download: Duration,
+}
+
+impl PhaseTimings {
+    pub fn new_connection(&self) -> bool {
+        self.connect.is_some()
+    }
+}
+
+// Instantes anotados pelos ganchos durante um `send`
+#[derive(Default)]
+struct ConnectionTrace {
+    dns: Option<Duration>,
+    dns_end: Option<Instant>,
+    tls_start: Option<Instant>,
+    started: Option<Instant>,
+    done: Option<Instant>,
+}
+
+tokio::task_local! {
+    static TRACE: RefCell<ConnectionTrace>;
+}
+
+// Fora de um `send` (ex.: conexão terminada em segundo plano depois que o pool venceu a corrida)
+// não há task-local e a anotação é descartada
+fn annotate(f: impl FnOnce(&mut ConnectionTrace)) {
+    let _ = TRACE.try_with(|t| f(&mut t.borrow_mut()));
+}
+
+// Envia a requisição e devolve as fases até os headers; `download` é preenchido por `read_body`
+pub async fn send(
+    req: reqwest::RequestBuilder,
+) -> (Result<reqwest::Response, reqwest::Error>, PhaseTimings) {
+    let start = Instant::now();
+    let (res, trace) = TRACE
+        .scope(RefCell::new(ConnectionTrace::default()), async {
+            let res = req.send().await;
+            (res, TRACE.with(|t| t.take()))
+        })
+        .await;
+    let headers_at = Instant::now();
+
+    let mut phases = PhaseTimings::default();
+    let mut ready = start;
+    // Uma conexão que começou e não terminou aqui não foi a usada por esta requisição
+    if let (Some(started), Some(done)) = (trace.started, trace.done) {
+        let tcp_start = trace.dns_end.unwrap_or(started);
+        let tcp_end = trace.tls_start.unwrap_or(done);
+        phases.dns = trace.dns;
+        phases.connect = Some(tcp_end.saturating_duration_since(tcp_start));
+        phases.tls = trace.tls_start.map(|t| done.saturating_duration_since(t));
+        ready = done.max(start);
+    }
+    phases.ttfb = headers_at.saturating_duration_since(ready);
+    (res, phases)
+}
+
+pub async fn read_body(
+    resp: reqwest::Response,
+    phases: &mut PhaseTimings,
+) -> Result<bytes::Bytes, reqwest::Error> {
+    let start = Instant::now();
+    let body = resp.bytes().await;
+    phases.download = start.elapsed();
+    body
+}
+
+// Resolução pelo getaddrinfo do sistema (o mesmo do resolvedor padrão), cronometrada
+pub struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            annotate(|t| {
+                t.dns = Some(start.elapsed());
+                t.dns_end = Some(Instant::now());
+            });
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// Camada em volta do conector do reqwest (DNS + TCP + TLS): marca início e fim da conexão
+#[derive(Clone)]
+pub struct TimingLayer;
+
+impl<S> tower_layer::Layer<S> for TimingLayer {
+    type Service = TimedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnector(inner)
+    }
+}
+
+#[derive(Clone)]
+pub struct TimedConnector<S>(S);
+
+impl<S, R> tower_service::Service<R> for TimedConnector<S>
+where
+    S: tower_service::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: R) -> Self::Future {
+        let started = Instant::now();
+        let connecting = self.0.call(dst);
+        Box::pin(async move {
+            let conn = connecting.await?;
+            annotate(|t| {
+                t.started = Some(started);
+                t.done = Some(Instant::now());
+            });
+            Ok(conn)
+        })
+    }
+}
+
+// O TLS que o reqwest montaria sozinho (raízes do webpki, ALPN h2 + http/1.1), mais o gancho
+// que marca o começo do handshake
+pub fn tls_config(insecure: bool, http2: bool) -> rustls::ClientConfig {
+    let builder = rustls::ClientConfig::builder();
+    let builder = if insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerifier))
+    } else {
+        builder.with_root_certificates(rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        })
+    };
+    let mut config = builder.with_client_cert_resolver(Arc::new(HandshakeStart));
+    config.alpn_protocols = if http2 {
+        vec![b"h2".to_vec()]
+    } else {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    };
+    config
+}
+
+// Não tem certificado de cliente; só anota quando o rustls pergunta. No rustls 0.23 (fixado em
+// =0.23.35 no Cargo.toml) o `has_certs` é chamado uma única vez, ao montar o ClientHello; se
+// uma versão nova passar a chamá-lo em outro ponto, o `get_or_insert_with` mantém só a primeira
+// marca e o teste de https abaixo acusa a mudança
+#[derive(Debug)]
+struct HandshakeStart;
+
+impl ResolvesClientCert for HandshakeStart {
+    fn resolve(&self, _: &[&[u8]], _: &[SignatureScheme]) -> Option<Arc<CertifiedKey>> {
+        None
+    }
+
+    fn has_certs(&self) -> bool {
+        annotate(|t| {
+            t.tls_start.get_or_insert_with(Instant::now);
+        });
+        false
+    }
+}
+
+// --insecure: aceita qualquer certificado
+#[derive(Debug)]
+struct NoVerifier;
+
+impl ServerCertVerifier for NoVerifier {
+    fn verify_server_cert(
+        &self,
+        _: &CertificateDer<'_>,
+        _: &[CertificateDer<'_>],
+        _: &ServerName<'_>,
+        _: &[u8],
+        _: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _: &[u8],
+        _: &CertificateDer<'_>,
+        _: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _: &[u8],
+        _: &CertificateDer<'_>,
+        _: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        rustls::crypto::ring::default_provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::http::{build_client, HttpOptions};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_phases_split_new_and_reused_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 1024];
+                    while socket.read(&mut buf).await.unwrap_or(0) > 0 {
+                        let _ = socket
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                            .await;
+                    }
+                });
+            }
+        });
+
+        let client = build_client(&HttpOptions::default()).unwrap();
+        // "localhost" passa pelo resolvedor; um IP literal não
+        let url = format!("http://localhost:{}/", port);
+
+        let (resp, mut first) = send(client.get(&url)).await;
+        read_body(resp.unwrap(), &mut first).await.unwrap();
+        assert!(first.new_connection());
+        assert!(first.dns.is_some());
+        assert_eq!(first.tls, None);
+
+        let (resp, mut second) = send(client.get(&url)).await;
+        assert_eq!(
+            &read_body(resp.unwrap(), &mut second).await.unwrap()[..],
+            b"ok"
+        );
+        assert!(!second.new_connection());
+        assert_eq!(second.dns, None);
+    }
+
+    #[tokio::test]
+    async fn test_https_phases_fit_in_the_request() {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let key = rustls::pki_types::PrivateKeyDer::Pkcs8(cert.key_pair.serialize_der().into());
+        let server = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert.cert.der().clone()], key)
+        .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    let Ok(mut tls) = acceptor.accept(socket).await else {
+                        return;
+                    };
+                    let mut buf = vec![0u8; 1024];
+                    while tls.read(&mut buf).await.unwrap_or(0) > 0 {
+                        let _ = tls
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                            .await;
+                    }
+                });
+            }
+        });
+
+        let opts = HttpOptions {
+            insecure: true,
+            ..HttpOptions::default()
+        };
+        let client = build_client(&opts).unwrap();
+        let url = format!("https://localhost:{}/", port);
+
+        let start = Instant::now();
+        let (resp, mut phases) = send(client.get(&url)).await;
+        let total = start.elapsed();
+        assert_eq!(
+            &read_body(resp.unwrap(), &mut phases).await.unwrap()[..],
+            b"ok"
+        );
+
+        let tls = phases.tls.expect("handshake TLS não cronometrado");
+        assert!(tls > Duration::ZERO);
+        let dns = phases.dns.unwrap_or_default();
+        let connect = phases.connect.unwrap();
+        assert!(dns + connect + tls <= total);
+    }
+}
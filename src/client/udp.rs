@@ -0,0 +1,239 @@
+// src/client/udp.rs
+//
+// --mode udp: cada disparo manda o payload renderizado como um datagrama. Cada worker tem o seu
+// socket (conectado no alvo, então só recebe dele). Com --udp-wait-reply o disparo espera a
+// resposta: a latência vira o RTT e o que não voltar até o prazo conta como perdido.
+//
+// Casamento das respostas: com --udp-match-bytes N, a resposta precisa começar com os mesmos N
+// bytes do datagrama (ex.: um número de sequência que o servidor devolve); sem isso, o primeiro
+// datagrama que chegar responde o disparo em curso. O que chega fora de hora é classificado:
+// resposta de um datagrama já dado como perdido (atrasada) ou repetição de uma já recebida.
+
+use crate::client::source::SourceIps;
+use crate::client::target::TargetResult;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+
+// Quantas chaves (respondidas e perdidas) cada worker lembra para classificar os atrasados
+const REMEMBERED: usize = 64;
+const MAX_DATAGRAM: usize = 65_535;
+
+#[derive(Debug, Clone, Copy)]
+pub struct UdpReply {
+    pub timeout: Duration,
+    // 0 = qualquer datagrama do alvo responde o disparo em curso
+    pub match_bytes: usize,
+}
+
+// O que aconteceu com um datagrama, além do sucesso / falha do TargetResult
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DatagramOutcome {
+    // None quando não se espera resposta
+    pub replied: Option<bool>,
+    // Datagramas fora de hora recebidos durante o disparo
+    pub duplicates: u32,
+    pub late: u32,
+}
+
+pub struct UdpTarget {
+    pub address: SocketAddr,
+    pub reply: Option<UdpReply>,
+    pub sources: SourceIps,
+}
+
+impl UdpTarget {
+    pub async fn new(
+        address: &str,
+        reply: Option<UdpReply>,
+        sources: SourceIps,
+    ) -> Result<Self, String> {
+        let address = tokio::net::lookup_host(address)
+            .await
+            .map_err(|e| format!("Falha ao resolver '{}': {}", address, e))?
+            .next()
+            .ok_or_else(|| format!("'{}' não resolveu para nenhum endereço", address))?;
+        Ok(Self {
+            address,
+            reply,
+            sources,
+        })
+    }
+
+    // O socket de um worker: sai do próximo --source-ip (ou de qualquer porta local)
+    pub async fn open(&self) -> std::io::Result<UdpSession> {
+        let local = match self.sources.next() {
+            Some(ip) => SocketAddr::new(ip, 0),
+            None if self.address.is_ipv4() => SocketAddr::from(([0, 0, 0, 0], 0)),
+            None => SocketAddr::from(([0u16; 8], 0)),
+        };
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect(self.address).await?;
+        Ok(UdpSession {
+            socket,
+            buf: vec![0; MAX_DATAGRAM],
+            answered: VecDeque::new(),
+            lost: VecDeque::new(),
+        })
+    }
+}
+
+pub struct UdpSession {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+    // Chaves (os primeiros --udp-match-bytes) dos datagramas respondidos e dos dados como perdidos
+    answered: VecDeque<Vec<u8>>,
+    lost: VecDeque<Vec<u8>>,
+}
+
+impl UdpSession {
+    pub fn local_ip(&self) -> Option<std::net::IpAddr> {
+        self.socket.local_addr().ok().map(|a| a.ip())
+    }
+
+    pub async fn fire(
+        &mut self,
+        payload: &[u8],
+        reply: Option<UdpReply>,
+        start: Instant,
+    ) -> TargetResult {
+        let mut outcome = DatagramOutcome::default();
+        // O que chegou desde o último disparo é atrasado ou repetido
+        while let Ok(n) = self.socket.try_recv(&mut self.buf) {
+            let key = self.key(&self.buf[..n], reply);
+            self.classify(key, &mut outcome);
+        }
+
+        if let Err(e) = self.socket.send(payload).await {
+            let mut res = TargetResult::fail(start.elapsed(), format!("Send Error: {}", e));
+            res.datagram = Some(outcome);
+            return res;
+        }
+        let Some(reply) = reply else {
+            let mut res = TargetResult::success(start.elapsed(), payload.len() as u64, 0);
+            res.datagram = Some(outcome);
+            return res;
+        };
+
+        let key = self.key(payload, Some(reply));
+        let deadline = tokio::time::Instant::from_std(start) + reply.timeout;
+        let mut res = loop {
+            match tokio::time::timeout_at(deadline, self.socket.recv(&mut self.buf)).await {
+                Err(_) => {
+                    outcome.replied = Some(false);
+                    remember(&mut self.lost, key);
+                    break TargetResult::fail(
+                        start.elapsed(),
+                        format!("Timeout: sem resposta em {:?}", reply.timeout),
+                    );
+                }
+                // Num socket conectado o ICMP "porta inalcançável" volta como erro aqui
+                Ok(Err(e)) => {
+                    outcome.replied = Some(false);
+                    break TargetResult::fail(start.elapsed(), format!("Recv Error: {}", e));
+                }
+                Ok(Ok(n)) => {
+                    let got = self.key(&self.buf[..n], Some(reply));
+                    if got == key {
+                        outcome.replied = Some(true);
+                        self.lost.retain(|k| *k != key);
+                        remember(&mut self.answered, key);
+                        break TargetResult::success(
+                            start.elapsed(),
+                            payload.len() as u64,
+                            n as u64,
+                        );
+                    }
+                    self.classify(got, &mut outcome);
+                }
+            }
+        };
+        res.datagram = Some(outcome);
+        res
+    }
+
+    fn key(&self, datagram: &[u8], reply: Option<UdpReply>) -> Vec<u8> {
+        let n = reply.map_or(0, |r| r.match_bytes).min(datagram.len());
+        datagram[..n].to_vec()
+    }
+
+    // Um datagrama que não responde o disparo em curso
+    fn classify(&mut self, key: Vec<u8>, outcome: &mut DatagramOutcome) {
+        if let Some(i) = self.lost.iter().position(|k| *k == key) {
+            self.lost.remove(i);
+            remember(&mut self.answered, key);
+            outcome.late += 1;
+        } else if self.answered.contains(&key) {
+            outcome.duplicates += 1;
+        } else {
+            // Ninguém perguntou (chave desconhecida ou já esquecida): também chegou fora de hora
+            outcome.late += 1;
+        }
+    }
+}
+
+fn remember(keys: &mut VecDeque<Vec<u8>>, key: Vec<u8>) {
+    if keys.len() == REMEMBERED {
+        keys.pop_front();
+    }
+    keys.push_back(key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, LoadTest};
+
+    // Responde cada datagrama duas vezes, menos os que começam com "drop"
+    async fn doubling_server() -> String {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            while let Ok((n, peer)) = socket.recv_from(&mut buf).await {
+                if !buf[..n].starts_with(b"drop") {
+                    let _ = socket.send_to(&buf[..n], peer).await;
+                    let _ = socket.send_to(&buf[..n], peer).await;
+                }
+            }
+        });
+        addr.to_string()
+    }
+
+    #[tokio::test]
+    async fn test_udp_loss_and_duplicates() {
+        let address = doubling_server().await;
+        let reply = UdpReply {
+            timeout: Duration::from_millis(200),
+            match_bytes: 8,
+        };
+
+        let result = LoadTest::builder()
+            .udp(&address, Some(reply))
+            // Os 8 primeiros bytes (aleatórios) identificam o datagrama
+            .body("{{number:u64be}}ping")
+            .executor(Executor::iterations(1, 10))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(result.successes, 10);
+        assert_eq!(result.udp.replies, 10);
+        // A cópia de cada resposta aparece no disparo seguinte (a última fica no socket)
+        assert_eq!(result.udp.duplicates, 9);
+
+        let lost = LoadTest::builder()
+            .udp(&address, Some(reply))
+            .body("drop{{number:u64be}}")
+            .executor(Executor::iterations(1, 2))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(lost.udp.lost, 2);
+        assert_eq!(lost.udp.loss_rate(), 1.0);
+    }
+}
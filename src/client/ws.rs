@@ -0,0 +1,407 @@
+// src/client/ws.rs
+//
+// --mode ws: cada worker abre uma conexão WebSocket (ws:// ou wss://) e cada disparo manda uma
+// mensagem e espera a resposta correspondente: a latência do relatório é o RTT da mensagem.
+// O handshake é um GET com Upgrade feito pelo reqwest (TLS, --insecure e timeouts de conexão
+// iguais aos do HTTP); depois disso a conexão vira um WebSocketStream do tokio-tungstenite,
+// que cuida dos frames (RFC 6455), das máscaras e dos pings.
+//
+// Casamento das respostas (--ws-match):
+//   - echo: a resposta é a própria mensagem de volta;
+//   - none: só envia, sem esperar nada;
+//   - uma regex com um grupo: o ID capturado na mensagem enviada precisa aparecer na resposta.
+// Mensagens que não casam (pushes do servidor, respostas atrasadas) são contadas e ignoradas.
+
+use crate::client::http::HttpOptions;
+use crate::client::target::TargetResult;
+use crate::client::timing;
+use bytes::Bytes;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::header::{CONNECTION, UPGRADE};
+use reqwest::{Client, StatusCode};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::error::{CapacityError, ProtocolError};
+use tokio_tungstenite::tungstenite::handshake::client::generate_key;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role, WebSocketConfig};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::WebSocketStream;
+
+const MAX_MESSAGE: usize = 64 * 1024 * 1024;
+const NO_CLOSE_FRAME: &str = "Conexão encerrada sem close frame";
+
+// Uma conexão WebSocket já aberta (a de cada worker)
+pub type WsConnection = WebSocketStream<reqwest::Upgraded>;
+
+#[derive(Debug, Clone)]
+pub enum WsMatch {
+    Echo,
+    None,
+    // O primeiro grupo da regex é o ID de correlação
+    Correlation(regex::bytes::Regex),
+}
+
+impl WsMatch {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec.trim() {
+            "echo" => Ok(Self::Echo),
+            "none" => Ok(Self::None),
+            pattern => {
+                let re = regex::bytes::Regex::new(pattern)
+                    .map_err(|e| format!("Regex inválida '{}': {}", pattern, e))?;
+                if re.captures_len() < 2 {
+                    return Err(format!(
+                        "'{}' precisa de um grupo de captura com o ID, ex: \"id\":\"([^\"]+)\" (ou use echo / none)",
+                        pattern
+                    ));
+                }
+                Ok(Self::Correlation(re))
+            }
+        }
+    }
+
+    fn id<'a>(re: &regex::bytes::Regex, message: &'a [u8]) -> Option<&'a [u8]> {
+        re.captures(message)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_bytes())
+    }
+}
+
+// O que aconteceu na conexão durante um disparo, além do sucesso / falha do TargetResult
+#[derive(Debug, Clone, Default)]
+pub struct WsOutcome {
+    // Só no disparo que abriu a conexão: quanto o handshake levou
+    pub connected: Option<Duration>,
+    pub handshake_failed: bool,
+    pub sent: bool,
+    // None quando não se espera resposta (--ws-match none) ou a mensagem não saiu
+    pub replied: Option<bool>,
+    // Mensagens recebidas que não eram a resposta esperada
+    pub unmatched: u32,
+    // A conexão caiu neste disparo (close do servidor, reset, EOF...)
+    pub disconnect: Option<String>,
+}
+
+pub struct WsTarget {
+    client: Client,
+    // A URL do handshake (ws:// → http://, wss:// → https://)
+    url: String,
+    headers: Arc<Vec<String>>,
+    matcher: WsMatch,
+    timeout: Duration,
+}
+
+impl WsTarget {
+    pub fn new(
+        url: &str,
+        headers: Arc<Vec<String>>,
+        matcher: WsMatch,
+        opts: &HttpOptions,
+    ) -> Result<Self, String> {
+        let url = if let Some(rest) = url.strip_prefix("ws://") {
+            format!("http://{}", rest)
+        } else if let Some(rest) = url.strip_prefix("wss://") {
+            format!("https://{}", rest)
+        } else {
+            return Err(format!("'{}': use ws:// ou wss://", url));
+        };
+        // O Upgrade só existe no HTTP/1.1: nada de h2 no ALPN
+        let mut tls = timing::tls_config(opts.insecure, false);
+        tls.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let client = Client::builder()
+            .http1_only()
+            .tcp_nodelay(true)
+            .pool_max_idle_per_host(0)
+            .user_agent(&opts.user_agent)
+            .connect_timeout(opts.connect_timeout)
+            .use_preconfigured_tls(tls)
+            .build()
+            .map_err(|e| format!("Falha ao criar o cliente WebSocket: {}", e))?;
+        Ok(Self {
+            client,
+            url,
+            headers,
+            matcher,
+            timeout: opts.timeout,
+        })
+    }
+
+    async fn connect(&self) -> Result<WsConnection, String> {
+        let key = generate_key();
+        let mut req = self
+            .client
+            .get(&self.url)
+            .header(CONNECTION, "Upgrade")
+            .header(UPGRADE, "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", &key);
+        for h in self.headers.iter() {
+            if let Some((k, v)) = h.split_once(':') {
+                req = req.header(k.trim(), v.trim());
+            }
+        }
+
+        let resp = req.send().await.map_err(|e| e.to_string())?;
+        if resp.status() != StatusCode::SWITCHING_PROTOCOLS {
+            return Err(format!(
+                "HTTP {} em vez de 101 Switching Protocols",
+                resp.status().as_u16()
+            ));
+        }
+        let accepted = resp
+            .headers()
+            .get("Sec-WebSocket-Accept")
+            .map(|v| v.as_bytes() == derive_accept_key(key.as_bytes()).as_bytes());
+        if accepted != Some(true) {
+            return Err("Sec-WebSocket-Accept ausente ou errado".to_string());
+        }
+        let io = resp.upgrade().await.map_err(|e| e.to_string())?;
+        let config = WebSocketConfig::default()
+            .max_message_size(Some(MAX_MESSAGE))
+            .max_frame_size(Some(MAX_MESSAGE));
+        Ok(WebSocketStream::from_raw_socket(io, Role::Client, Some(config)).await)
+    }
+
+    // Um disparo: abre a conexão do worker se preciso, manda a mensagem e espera a resposta
+    pub async fn fire(
+        &self,
+        conn: &mut Option<WsConnection>,
+        payload: &[u8],
+        start: Instant,
+    ) -> TargetResult {
+        let mut outcome = WsOutcome::default();
+        let with = |mut res: TargetResult, outcome: WsOutcome| {
+            res.ws = Some(outcome);
+            res
+        };
+
+        if conn.is_none() {
+            match tokio::time::timeout(self.timeout, self.connect()).await {
+                Ok(Ok(c)) => {
+                    outcome.connected = Some(start.elapsed());
+                    *conn = Some(c);
+                }
+                Ok(Err(e)) => {
+                    outcome.handshake_failed = true;
+                    let res =
+                        TargetResult::fail(start.elapsed(), format!("Handshake Error: {}", e));
+                    return with(res, outcome);
+                }
+                Err(_) => {
+                    outcome.handshake_failed = true;
+                    let res = TargetResult::fail(
+                        start.elapsed(),
+                        format!("Handshake Error: sem resposta em {:?}", self.timeout),
+                    );
+                    return with(res, outcome);
+                }
+            }
+        }
+        let ws = conn.as_mut().unwrap();
+
+        let expected_id = match &self.matcher {
+            WsMatch::Correlation(re) => match WsMatch::id(re, payload) {
+                Some(id) => Some(id.to_vec()),
+                None => {
+                    let res = TargetResult::fail(
+                        start.elapsed(),
+                        "A mensagem não tem o ID de correlação do --ws-match".to_string(),
+                    );
+                    return with(res, outcome);
+                }
+            },
+            _ => None,
+        };
+
+        // O RTT começa no envio: o handshake fica no `connected`
+        // Texto quando o payload é UTF-8, binário caso contrário
+        let message = match std::str::from_utf8(payload) {
+            Ok(text) => Message::text(text),
+            Err(_) => Message::binary(payload.to_vec()),
+        };
+        let sent_at = Instant::now();
+        if let Err(reason) = ws.send(message).await.map_err(ws_reason) {
+            *conn = None;
+            outcome.disconnect = Some(reason.clone());
+            let res = TargetResult::fail(start.elapsed(), format!("Desconectado: {}", reason));
+            return with(res, outcome);
+        }
+        outcome.sent = true;
+        if matches!(self.matcher, WsMatch::None) {
+            let res = TargetResult::success(sent_at.elapsed(), payload.len() as u64, 0);
+            return with(res, outcome);
+        }
+
+        let deadline = tokio::time::Instant::from_std(sent_at) + self.timeout;
+        loop {
+            match tokio::time::timeout_at(deadline, next_message(ws)).await {
+                Err(_) => {
+                    outcome.replied = Some(false);
+                    let res = TargetResult::fail(
+                        sent_at.elapsed(),
+                        format!("Timeout: sem resposta em {:?}", self.timeout),
+                    );
+                    return with(res, outcome);
+                }
+                Ok(Err(reason)) => {
+                    *conn = None;
+                    outcome.replied = Some(false);
+                    outcome.disconnect = Some(reason.clone());
+                    let res =
+                        TargetResult::fail(sent_at.elapsed(), format!("Desconectado: {}", reason));
+                    return with(res, outcome);
+                }
+                Ok(Ok(message)) => {
+                    let matched = match (&self.matcher, &expected_id) {
+                        (WsMatch::Correlation(re), Some(id)) => {
+                            WsMatch::id(re, &message) == Some(id.as_slice())
+                        }
+                        _ => message[..] == *payload,
+                    };
+                    if matched {
+                        outcome.replied = Some(true);
+                        let res = TargetResult::success(
+                            sent_at.elapsed(),
+                            payload.len() as u64,
+                            message.len() as u64,
+                        );
+                        return with(res, outcome);
+                    }
+                    outcome.unmatched += 1;
+                }
+            }
+        }
+    }
+}
+
+// A próxima mensagem de dados; pings são respondidos pelo próprio tungstenite. Erro = motivo da
+// queda. `next` é seguro para cancelar (timeout): o que já chegou fica no buffer da conexão.
+async fn next_message(ws: &mut WsConnection) -> Result<Bytes, String> {
+    loop {
+        match ws.next().await {
+            Some(Ok(message @ (Message::Text(_) | Message::Binary(_)))) => {
+                return Ok(message.into_data())
+            }
+            Some(Ok(Message::Close(frame))) => return Err(close_reason(frame)),
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(ws_reason(e)),
+            None => return Err(NO_CLOSE_FRAME.to_string()),
+        }
+    }
+}
+
+// "Close 1001 (shutting down)"; o motivo em texto é opcional no protocolo
+fn close_reason(frame: Option<CloseFrame>) -> String {
+    match frame {
+        None => "Close sem código".to_string(),
+        Some(f) if f.reason.is_empty() => format!("Close {}", u16::from(f.code)),
+        Some(f) => format!("Close {} ({})", u16::from(f.code), f.reason),
+    }
+}
+
+fn ws_reason(e: WsError) -> String {
+    match e {
+        WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+        | WsError::ConnectionClosed
+        | WsError::AlreadyClosed => NO_CLOSE_FRAME.to_string(),
+        WsError::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            NO_CLOSE_FRAME.to_string()
+        }
+        WsError::Capacity(CapacityError::MessageTooLong { .. }) => {
+            format!("Mensagem maior que {} bytes", MAX_MESSAGE)
+        }
+        WsError::Protocol(e) => format!("Protocolo: {}", e),
+        e => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, LoadTest};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+
+    // Servidor de eco; "bye" fecha com 1001, "drop" derruba o TCP sem close e "push" manda um
+    // ping e uma mensagem extra antes do eco
+    async fn echo_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let Ok(mut ws) = tokio_tungstenite::accept_async(socket).await else {
+                        return;
+                    };
+                    while let Some(Ok(msg)) = ws.next().await {
+                        if !msg.is_text() && !msg.is_binary() {
+                            continue;
+                        }
+                        match &msg.clone().into_data()[..] {
+                            b"bye" => {
+                                let _ = ws
+                                    .close(Some(CloseFrame {
+                                        code: CloseCode::Away,
+                                        reason: "going away".into(),
+                                    }))
+                                    .await;
+                                break;
+                            }
+                            b"drop" => break,
+                            data if data.starts_with(b"push") => {
+                                let _ = ws.send(Message::Ping(Bytes::from_static(b"hi"))).await;
+                                let _ = ws.send(Message::text("unsolicited")).await;
+                            }
+                            _ => {}
+                        }
+                        let _ = ws.send(msg).await;
+                    }
+                });
+            }
+        });
+        format!("ws://{}/chat", addr)
+    }
+
+    async fn run(url: &str, body: &str, matcher: &str, iterations: u32) -> crate::LoadTestResult {
+        LoadTest::builder()
+            .websocket(url, WsMatch::parse(matcher).unwrap())
+            .body(body)
+            .executor(Executor::iterations(2, iterations))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ws_echo_correlation_and_disconnects() {
+        let url = echo_server().await;
+
+        let echo = run(&url, "push {{uuid}}", "echo", 20).await;
+        assert_eq!(echo.successes, 20);
+        assert_eq!(echo.ws.connections, 2);
+        assert_eq!(echo.ws.replies, 20);
+        assert_eq!(echo.ws.unmatched, 20);
+        assert_eq!(echo.ws.setup.len(), 2);
+
+        let correlated = run(&url, r#"{"id":"{{uuid}}"}"#, r#""id":"([^"]+)""#, 10).await;
+        assert_eq!(correlated.ws.replies, 10);
+
+        // Cada "bye" derruba a conexão; o disparo seguinte do worker reconecta
+        let closed = run(&url, "bye", "echo", 4).await;
+        assert_eq!(closed.failures, 4);
+        assert_eq!(closed.ws.connections, 4);
+        assert_eq!(
+            closed.ws.disconnects.get("Close 1001 (going away)"),
+            Some(&4)
+        );
+
+        let dropped = run(&url, "drop", "echo", 2).await;
+        assert_eq!(dropped.failures, 2);
+        assert_eq!(dropped.ws.disconnects.get(NO_CLOSE_FRAME), Some(&2));
+
+        assert!(WsMatch::parse("id=\\d+").is_err());
+    }
+}
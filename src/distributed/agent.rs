@@ -0,0 +1,144 @@
+// src/distributed/agent.rs
+
+use super::{check_share, recv, send, token_matches, unix_ms, AgentReport, Message};
+use crate::app::plan::TestPlan;
+use crate::client::protocol::ProtocolRegistry;
+use colored::Colorize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+// Cadência das mensagens de progresso para o coordenador
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+// Atende um coordenador por vez, para sempre. Só roda planos que trazem o `token`.
+pub async fn serve(
+    listener: TcpListener,
+    registry: ProtocolRegistry,
+    token: String,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        println!(
+            "🤝 Coordenador conectado: {}",
+            peer.to_string().bright_cyan()
+        );
+        match run_share(stream, &registry, &token).await {
+            Ok(()) => println!("✅ Fatia concluída e entregue ao coordenador"),
+            Err(e) => eprintln!("{} {}", "❌ Erro:".red().bold(), e),
+        }
+    }
+}
+
+async fn run_share(
+    stream: TcpStream,
+    registry: &ProtocolRegistry,
+    token: &str,
+) -> Result<(), String> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+
+    let (sent_token, args) = match recv(&mut lines).await? {
+        Some(Message::Plan { token, args }) => (token, *args),
+        other => return Err(format!("Esperava o plano, recebi {:?}", other)),
+    };
+
+    // Nada do plano é interpretado antes do token conferir
+    let accepted = if token_matches(token, &sent_token) {
+        check_share(&args)
+    } else {
+        Err("Token inválido: confira o --token do coordenador e do agente".to_string())
+    };
+
+    // Erros de validação voltam para o coordenador, que aborta o teste inteiro
+    let planned = accepted
+        .and_then(|()| TestPlan::from_agent_args(&args, registry).map_err(|e| e.to_string()));
+    let prepared = match planned {
+        Ok(plan) => match plan.load_test(&args, registry, None).await {
+            Ok(test) => Ok((plan, test)),
+            Err(e) => Err(e.to_string()),
+        },
+        Err(message) => Err(message),
+    };
+    let (plan, test) = match prepared {
+        Ok(prepared) => prepared,
+        Err(message) => {
+            send(
+                &mut write,
+                &Message::Error {
+                    message: message.clone(),
+                },
+            )
+            .await?;
+            return Err(message);
+        }
+    };
+    send(&mut write, &Message::Ready).await?;
+
+    let at = match recv(&mut lines).await? {
+        Some(Message::Start { at_unix_ms }) => at_unix_ms,
+        other => return Err(format!("Esperava o sinal de partida, recebi {:?}", other)),
+    };
+    tokio::time::sleep(Duration::from_millis(at.saturating_sub(unix_ms()))).await;
+
+    println!(
+        "🚀 Disparando contra {} ({} workers)...",
+        plan.target.bright_cyan(),
+        args.workers.to_string().magenta()
+    );
+    let running = match test.start().await {
+        Ok(running) => running,
+        Err(message) => {
+            send(
+                &mut write,
+                &Message::Error {
+                    message: message.clone(),
+                },
+            )
+            .await?;
+            return Err(message);
+        }
+    };
+
+    // Stop do coordenador ou conexão caída: drena como no Ctrl+C local
+    let (stop_tx, mut stop_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let _ = recv(&mut lines).await;
+        let _ = stop_tx.send(());
+    });
+
+    let mut stopped = false;
+    let mut tick = tokio::time::interval(PROGRESS_INTERVAL);
+    while !running.is_finished() {
+        tokio::select! {
+            _ = tick.tick() => {
+                let m = running.metrics();
+                let _ = send(&mut write, &Message::Progress {
+                    successes: m.successes.load(Ordering::Relaxed),
+                    failures: m.failures.load(Ordering::Relaxed),
+                }).await;
+            }
+            _ = &mut stop_rx => {
+                println!("{}", "⚠️ Parada pedida pelo coordenador, drenando...".yellow());
+                stopped = true;
+                break;
+            }
+        }
+    }
+
+    let result = if stopped {
+        running.drain(plan.drain_timeout).await
+    } else {
+        running.wait().await
+    };
+    let report = AgentReport::from_result(&result)?;
+    send(
+        &mut write,
+        &Message::Done {
+            report: Box::new(report),
+        },
+    )
+    .await
+}
@@ -0,0 +1,336 @@
+// src/distributed/coordinator.rs
+
+use super::{merge, recv, send, split_args, unix_ms, AgentReport, Message};
+use crate::args::parser::Args;
+use crate::engine::worker::SharedMetrics;
+use crate::load_test::{Executor, LoadTestResult};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Folga entre o "Start" e o disparo: dá tempo da mensagem chegar em todos os agentes
+const START_DELAY: Duration = Duration::from_millis(500);
+
+struct AgentLink {
+    address: String,
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    write: OwnedWriteHalf,
+}
+
+// Um teste distribuído em andamento. `metrics` soma o progresso que os agentes reportam.
+pub struct RunningCluster {
+    metrics: Arc<SharedMetrics>,
+    started_at: Instant,
+    stop: Arc<watch::Sender<bool>>,
+    handle: JoinHandle<Result<LoadTestResult, String>>,
+}
+
+// Conecta nos agentes, entrega a fatia de cada um e dispara todos no mesmo instante
+pub async fn start(
+    args: &Args,
+    executor: &Executor,
+    agents: &[String],
+    token: &str,
+) -> Result<RunningCluster, String> {
+    let shares = split_args(args, agents.len())?;
+
+    let mut links = Vec::with_capacity(agents.len());
+    for (address, share) in agents.iter().zip(shares) {
+        let stream = TcpStream::connect(address)
+            .await
+            .map_err(|e| format!("Falha ao conectar no agente {}: {}", address, e))?;
+        let (read, mut write) = stream.into_split();
+        send(
+            &mut write,
+            &Message::Plan {
+                token: token.to_string(),
+                args: Box::new(share),
+            },
+        )
+        .await?;
+        links.push(AgentLink {
+            address: address.clone(),
+            lines: BufReader::new(read).lines(),
+            write,
+        });
+    }
+
+    for link in &mut links {
+        match recv(&mut link.lines).await? {
+            Some(Message::Ready) => {}
+            Some(Message::Error { message }) => {
+                return Err(format!("Agente {}: {}", link.address, message))
+            }
+            other => {
+                return Err(format!(
+                    "Agente {} respondeu fora do protocolo: {:?}",
+                    link.address, other
+                ))
+            }
+        }
+    }
+
+    let at = unix_ms() + START_DELAY.as_millis() as u64;
+    for link in &mut links {
+        send(&mut link.write, &Message::Start { at_unix_ms: at }).await?;
+    }
+    tokio::time::sleep(Duration::from_millis(at.saturating_sub(unix_ms()))).await;
+    let started_at = Instant::now();
+
+    let metrics = Arc::new(SharedMetrics::default());
+    let (stop, stop_rx) = watch::channel(false);
+    let stop = Arc::new(stop);
+    let followers: Vec<_> = links
+        .into_iter()
+        .map(|link| tokio::spawn(follow(link, metrics.clone(), stop.clone(), stop_rx.clone())))
+        .collect();
+
+    let warmup = Duration::from_secs(args.warmup);
+    let planned_duration = executor.planned_duration();
+    let planned_requests = executor.planned_requests();
+    let handle = tokio::spawn(async move {
+        let mut reports = Vec::with_capacity(followers.len());
+        for follower in followers {
+            reports.push(follower.await.map_err(|e| e.to_string())??);
+        }
+        merge(reports, warmup, planned_duration, planned_requests)
+    });
+
+    Ok(RunningCluster {
+        metrics,
+        started_at,
+        stop,
+        handle,
+    })
+}
+
+// Acompanha um agente: progresso vira delta nos atomics do coordenador, Stop é repassado.
+// Se uma regra de parada (--abort-on) disparar num agente, o cluster inteiro para.
+async fn follow(
+    link: AgentLink,
+    metrics: Arc<SharedMetrics>,
+    stop_all: Arc<watch::Sender<bool>>,
+    mut stop: watch::Receiver<bool>,
+) -> Result<AgentReport, String> {
+    let AgentLink {
+        address,
+        mut lines,
+        mut write,
+    } = link;
+    let (mut last_successes, mut last_failures) = (0, 0);
+    let mut stop_sent = false;
+
+    loop {
+        tokio::select! {
+            msg = recv(&mut lines) => match msg? {
+                Some(Message::Progress { successes, failures }) => {
+                    metrics
+                        .successes
+                        .fetch_add(successes.saturating_sub(last_successes), Ordering::Relaxed);
+                    metrics
+                        .failures
+                        .fetch_add(failures.saturating_sub(last_failures), Ordering::Relaxed);
+                    last_successes = last_successes.max(successes);
+                    last_failures = last_failures.max(failures);
+                }
+                Some(Message::Done { report }) => {
+                    if report.abort_reason.is_some() {
+                        let _ = stop_all.send(true);
+                    }
+                    return Ok(*report);
+                }
+                Some(Message::Error { message }) => {
+                    return Err(format!("Agente {}: {}", address, message))
+                }
+                Some(other) => {
+                    return Err(format!(
+                        "Agente {} respondeu fora do protocolo: {:?}",
+                        address, other
+                    ))
+                }
+                None => return Err(format!("Agente {} desconectou antes do fim", address)),
+            },
+            changed = stop.changed(), if !stop_sent => {
+                stop_sent = true;
+                if changed.is_ok() {
+                    let _ = send(&mut write, &Message::Stop).await;
+                }
+            }
+        }
+    }
+}
+
+impl RunningCluster {
+    pub fn metrics(&self) -> &SharedMetrics {
+        &self.metrics
+    }
+
+    pub fn started_at(&self) -> Instant {
+        self.started_at
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // Cada agente drena o que está em voo com o próprio --drain-timeout
+    pub fn stop(&self) {
+        let _ = self.stop.send(true);
+    }
+
+    pub async fn wait(self) -> Result<LoadTestResult, String> {
+        self.handle.await.map_err(|e| e.to_string())?
+    }
+
+    pub async fn drain(self) -> Result<LoadTestResult, String> {
+        self.stop();
+        self.wait().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::protocol::ProtocolRegistry;
+    use clap::Parser;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Servidor TCP mínimo: responde 1 byte de ACK a cada leitura
+    async fn ack_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 || socket.write_all(b"k").await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    const TOKEN: &str = "s3cret";
+
+    async fn agent() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(super::super::agent::serve(
+            listener,
+            ProtocolRegistry::new(),
+            TOKEN.to_string(),
+        ));
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_two_agents_merge_into_one_result() {
+        let target = ack_server().await;
+        let agents = vec![agent().await, agent().await];
+        let args = Args::parse_from([
+            "cannon", "-u", &target, "--mode", "tcp", "-c", "40", "-w", "4", "-b", "ping",
+        ]);
+
+        let cluster = start(&args, &Executor::iterations(4, 40), &agents, TOKEN)
+            .await
+            .unwrap();
+        let result = cluster.wait().await.unwrap();
+
+        assert_eq!(result.successes, 40);
+        assert_eq!(result.failures, 0);
+        assert_eq!(result.histogram.len(), 40);
+        assert_eq!(result.planned_requests, Some(40));
+    }
+
+    #[tokio::test]
+    async fn test_agent_errors_abort_the_cluster() {
+        let agents = vec![agent().await];
+        let args = Args::parse_from([
+            "cannon",
+            "-u",
+            "not-a-url",
+            "coordinate",
+            "--agents",
+            "x",
+            "--token",
+            TOKEN,
+        ]);
+
+        let err = start(&args, &Executor::iterations(1, 1), &agents, TOKEN)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.contains("http://"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_refuses_a_wrong_token() {
+        let target = ack_server().await;
+        let agents = vec![agent().await];
+        let args = Args::parse_from(["cannon", "-u", &target, "--mode", "tcp", "-c", "1"]);
+
+        let err = start(&args, &Executor::iterations(1, 1), &agents, "guess")
+            .await
+            .err()
+            .unwrap();
+        assert!(err.contains("Token inválido"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_agent_refuses_unix_targets() {
+        let agents = vec![agent().await];
+        for mode in ["http", "tcp"] {
+            let args = Args::parse_from([
+                "cannon",
+                "-u",
+                "unix:///var/run/docker.sock",
+                "--mode",
+                mode,
+                "-c",
+                "1",
+            ]);
+            let err = start(&args, &Executor::iterations(1, 1), &agents, TOKEN)
+                .await
+                .err()
+                .unwrap();
+            assert!(err.contains("linha de comando local"), "{}: {}", mode, err);
+        }
+    }
+
+    // Um peer que fala o protocolo à mão, com o token certo mas pedindo o que o coordenador não pede
+    #[tokio::test]
+    async fn test_agent_refuses_fields_split_args_never_sends() {
+        let target = ack_server().await;
+        let address = agent().await;
+        let mut args = Args::parse_from(["cannon", "-u", &target, "--mode", "tcp", "-c", "1"]);
+        args.output = Some("/tmp/cannon-agent.json".to_string());
+
+        let (read, mut write) = TcpStream::connect(&address).await.unwrap().into_split();
+        let mut lines = BufReader::new(read).lines();
+        send(
+            &mut write,
+            &Message::Plan {
+                token: TOKEN.to_string(),
+                args: Box::new(args),
+            },
+        )
+        .await
+        .unwrap();
+        match recv(&mut lines).await.unwrap() {
+            Some(Message::Error { message }) => {
+                assert!(message.contains("--output"), "{}", message)
+            }
+            other => panic!("esperava recusa, veio {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,597 @@
+// src/distributed/mod.rs
+//
+// Modo distribuído: um coordenador divide o teste entre agentes (`cannon agent`), dispara
+// todos no mesmo instante e funde os histogramas que eles devolvem num único relatório.
+//
+// Protocolo de controle: uma mensagem JSON por linha sobre TCP.
+//   coordenador → agente: Plan, Start, Stop
+//   agente → coordenador: Ready, Progress, Done, Error
+//
+// O agente roda o que chegar no Plan: por isso escuta só no loopback por padrão e só aceita um
+// plano com o --token que ele mesmo recebeu (segredo compartilhado, o canal não é cifrado).
+
+pub mod agent;
+pub mod coordinator;
+
+use crate::args::parser::Args;
+use crate::engine::abort::AbortReport;
+use crate::engine::profile::LoadProfile;
+use crate::load_test::LoadTestResult;
+use crate::metrics::phases::PhaseStats;
+use crate::metrics::timeline::TimelinePoint;
+use crate::metrics::udp::UdpStats;
+use crate::metrics::ws::WsStats;
+use crate::scenario::{merge_steps, StepStats};
+use hdrhistogram::serialization::{Deserializer, Serializer, V2DeflateSerializer};
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufRead, AsyncWrite, AsyncWriteExt, Lines};
+
+// Tag externa ({"done": {...}}): com `tag = "..."` o serde bufferiza o conteúdo e perde as
+// chaves numéricas dos mapas de status
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Message {
+    // A fatia do teste que cabe a este agente, com o segredo compartilhado (--token)
+    Plan { token: String, args: Box<Args> },
+    Ready,
+    // Relógio de parede (ms desde a época): todos os agentes começam juntos
+    Start { at_unix_ms: u64 },
+    // Contadores acumulados, para a barra de progresso do coordenador
+    Progress { successes: u64, failures: u64 },
+    Stop,
+    Done { report: Box<AgentReport> },
+    Error { message: String },
+}
+
+pub async fn send<W: AsyncWrite + Unpin>(writer: &mut W, msg: &Message) -> Result<(), String> {
+    let mut line = serde_json::to_vec(msg).map_err(|e| e.to_string())?;
+    line.push(b'\n');
+    writer
+        .write_all(&line)
+        .await
+        .map_err(|e| format!("Falha ao enviar mensagem: {}", e))
+}
+
+// None quando o outro lado fechou a conexão. `next_line` é cancel-safe (pode ir num select!)
+pub async fn recv<R: AsyncBufRead + Unpin>(
+    lines: &mut Lines<R>,
+) -> Result<Option<Message>, String> {
+    match lines.next_line().await {
+        Ok(Some(line)) => serde_json::from_str(&line)
+            .map(Some)
+            .map_err(|e| format!("Mensagem inválida: {}", e)),
+        Ok(None) => Ok(None),
+        Err(e) => Err(format!("Falha ao ler mensagem: {}", e)),
+    }
+}
+
+// Comparação em tempo constante: o tempo da resposta não entrega quantos bytes acertaram
+pub fn token_matches(expected: &str, got: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), got.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// Parte `total` em `n` fatias que somam exatamente `total` (as primeiras levam o resto)
+fn share(total: u32, n: usize, i: usize) -> u32 {
+    let n = n as u32;
+    total / n + u32::from((i as u32) < total % n)
+}
+
+// Divide o teste entre `n` agentes: disparos, workers, taxa, VUs e estágios são repartidos;
+// durações, timeouts e o alvo ficam iguais. Saídas em arquivo ficam só com o coordenador.
+pub fn split_args(args: &Args, n: usize) -> Result<Vec<Args>, String> {
+    if n == 0 {
+        return Err("Informe pelo menos um agente".to_string());
+    }
+    if args.find_capacity || args.adaptive.is_some() {
+        return Err(
+            "A busca de capacidade e a concorrência adaptativa não rodam no modo distribuído"
+                .to_string(),
+        );
+    }
+    if args.csv.is_some() {
+        return Err("O --csv (dados brutos) não está disponível no modo distribuído".to_string());
+    }
+    // O log fica na máquina do coordenador
+    if args.replay.is_some() {
+        return Err("O --replay não está disponível no modo distribuído".to_string());
+    }
+    // Os endereços de origem são das interfaces desta máquina, não das dos agentes
+    if !args.source_ip.is_empty() {
+        return Err("O --source-ip não está disponível no modo distribuído".to_string());
+    }
+    if let Some(rps) = args.rps {
+        if (rps as usize) < n {
+            return Err(format!(
+                "--rps {} não dá para dividir entre {} agentes",
+                rps, n
+            ));
+        }
+    }
+    let profile = args
+        .ramp_up
+        .as_deref()
+        .map(LoadProfile::parse)
+        .transpose()?;
+
+    Ok((0..n)
+        .map(|i| {
+            let mut part = args.clone();
+            part.command = None;
+            part.count = share(args.count, n, i);
+            part.workers = share(args.workers, n, i).max(1);
+            part.rps = args.rps.map(|rps| share(rps, n, i));
+            part.max_vus = args.max_vus.map(|vus| share(vus, n, i).max(1));
+            part.ramp_up = profile.as_ref().map(|p| {
+                p.stages
+                    .iter()
+                    .map(|s| format!("{}ms:{}", s.duration.as_millis(), share(s.target, n, i)))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            });
+            // O YAML já foi fundido no coordenador e os arquivos são escritos só lá
+            part.config = None;
+            part.output = None;
+            part.html = None;
+            part.save_baseline = None;
+            part.compare_baseline = None;
+            part.update = false;
+            part.from_curl = None;
+            part
+        })
+        .collect())
+}
+
+// No agente: recusa um plano com campos que o split_args nunca manda (arquivos locais,
+// subcomando, modos que só rodam no coordenador). Um coordenador legítimo não cai aqui.
+pub fn check_share(args: &Args) -> Result<(), String> {
+    let unexpected = [
+        ("subcomando", args.command.is_some()),
+        ("--config", args.config.is_some()),
+        ("--output", args.output.is_some()),
+        ("--html", args.html.is_some()),
+        ("--csv", args.csv.is_some()),
+        ("--save-baseline", args.save_baseline.is_some()),
+        ("--compare-baseline", args.compare_baseline.is_some()),
+        ("--update", args.update),
+        ("--from-curl", args.from_curl.is_some()),
+        ("--replay", args.replay.is_some()),
+        ("--source-ip", !args.source_ip.is_empty()),
+        ("--find-capacity", args.find_capacity),
+        ("--adaptive", args.adaptive.is_some()),
+    ];
+    match unexpected.iter().find(|(_, present)| *present) {
+        Some((field, _)) => Err(format!(
+            "Plano recusado: {} não é enviado pelo coordenador",
+            field
+        )),
+        None => Ok(()),
+    }
+}
+
+// O resultado de um agente, pronto para atravessar a rede
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AgentReport {
+    pub successes: u64,
+    pub failures: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    // Histogramas no formato V2 + deflate do HdrHistogram
+    pub histogram: Vec<u8>,
+    pub response_histogram: Vec<u8>,
+    pub status_counts: HashMap<u16, u64>,
+    pub error_counts: HashMap<String, u64>,
+    pub assertion_failures: u64,
+    pub measured_successes: u64,
+    pub measured_failures: u64,
+    pub dropped_arrivals: u64,
+    pub late_arrivals: u64,
+    pub steps: Vec<AgentStep>,
+    pub requests: Vec<AgentStep>,
+    pub phases: AgentPhases,
+    pub udp: UdpStats,
+    pub ws: AgentWs,
+    pub timeline: Vec<TimelinePoint>,
+    pub duration_secs: f64,
+    pub interrupted: bool,
+    pub aborted: bool,
+    pub abort_reason: Option<AbortReport>,
+    pub unsent_requests: Option<u64>,
+}
+
+// Métricas de um passo do cenário (ou requisição do mix), com o histograma serializado como os demais
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AgentStep {
+    pub name: String,
+    pub successes: u64,
+    pub failures: u64,
+    pub histogram: Vec<u8>,
+    pub status_counts: HashMap<u16, u64>,
+}
+
+// Fases das requisições HTTP, um histograma serializado por fase
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AgentPhases {
+    pub dns: Vec<u8>,
+    pub connect: Vec<u8>,
+    pub tls: Vec<u8>,
+    pub ttfb: Vec<u8>,
+    pub download: Vec<u8>,
+    pub new_connections: u64,
+    pub reused_connections: u64,
+}
+
+// Contadores do --mode ws, com o histograma do handshake serializado
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AgentWs {
+    pub setup: Vec<u8>,
+    pub connections: u64,
+    pub handshake_failures: u64,
+    pub messages_sent: u64,
+    pub replies: u64,
+    pub unmatched: u64,
+    pub disconnects: HashMap<String, u64>,
+}
+
+fn encode(hist: &Histogram<u64>) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    V2DeflateSerializer::new()
+        .serialize(hist, &mut buf)
+        .map_err(|e| format!("Falha ao serializar o histograma: {:?}", e))?;
+    Ok(buf)
+}
+
+fn decode(bytes: &[u8]) -> Result<Histogram<u64>, String> {
+    Deserializer::new()
+        .deserialize(&mut std::io::Cursor::new(bytes))
+        .map_err(|e| format!("Histograma inválido: {:?}", e))
+}
+
+impl AgentStep {
+    fn from_stats(stats: &StepStats) -> Result<Self, String> {
+        Ok(Self {
+            name: stats.name.clone(),
+            successes: stats.successes,
+            failures: stats.failures,
+            histogram: encode(&stats.histogram)?,
+            status_counts: stats.status_counts.clone(),
+        })
+    }
+
+    fn into_stats(self) -> Result<StepStats, String> {
+        Ok(StepStats {
+            histogram: decode(&self.histogram)?,
+            name: self.name,
+            successes: self.successes,
+            failures: self.failures,
+            status_counts: self.status_counts,
+        })
+    }
+}
+
+impl AgentPhases {
+    fn from_stats(stats: &PhaseStats) -> Result<Self, String> {
+        Ok(Self {
+            dns: encode(&stats.dns)?,
+            connect: encode(&stats.connect)?,
+            tls: encode(&stats.tls)?,
+            ttfb: encode(&stats.ttfb)?,
+            download: encode(&stats.download)?,
+            new_connections: stats.new_connections,
+            reused_connections: stats.reused_connections,
+        })
+    }
+
+    fn into_stats(self) -> Result<PhaseStats, String> {
+        Ok(PhaseStats {
+            dns: decode(&self.dns)?,
+            connect: decode(&self.connect)?,
+            tls: decode(&self.tls)?,
+            ttfb: decode(&self.ttfb)?,
+            download: decode(&self.download)?,
+            new_connections: self.new_connections,
+            reused_connections: self.reused_connections,
+        })
+    }
+}
+
+impl AgentWs {
+    fn from_stats(stats: &WsStats) -> Result<Self, String> {
+        Ok(Self {
+            setup: encode(&stats.setup)?,
+            connections: stats.connections,
+            handshake_failures: stats.handshake_failures,
+            messages_sent: stats.messages_sent,
+            replies: stats.replies,
+            unmatched: stats.unmatched,
+            disconnects: stats.disconnects.clone(),
+        })
+    }
+
+    fn into_stats(self) -> Result<WsStats, String> {
+        Ok(WsStats {
+            setup: decode(&self.setup)?,
+            connections: self.connections,
+            handshake_failures: self.handshake_failures,
+            messages_sent: self.messages_sent,
+            replies: self.replies,
+            unmatched: self.unmatched,
+            disconnects: self.disconnects,
+        })
+    }
+}
+
+impl AgentReport {
+    pub fn from_result(result: &LoadTestResult) -> Result<Self, String> {
+        Ok(Self {
+            successes: result.successes,
+            failures: result.failures,
+            bytes_sent: result.bytes_sent,
+            bytes_received: result.bytes_received,
+            histogram: encode(&result.histogram)?,
+            response_histogram: encode(&result.response_histogram)?,
+            status_counts: result.status_counts.clone(),
+            error_counts: result.error_counts.clone(),
+            assertion_failures: result.assertion_failures,
+            measured_successes: result.measured_successes,
+            measured_failures: result.measured_failures,
+            dropped_arrivals: result.dropped_arrivals,
+            late_arrivals: result.late_arrivals,
+            steps: result
+                .steps
+                .iter()
+                .map(AgentStep::from_stats)
+                .collect::<Result<_, String>>()?,
+            requests: result
+                .requests
+                .iter()
+                .map(AgentStep::from_stats)
+                .collect::<Result<_, String>>()?,
+            phases: AgentPhases::from_stats(&result.phases)?,
+            udp: result.udp,
+            ws: AgentWs::from_stats(&result.ws)?,
+            timeline: result.timeline.clone(),
+            duration_secs: result.duration.as_secs_f64(),
+            interrupted: result.interrupted,
+            aborted: result.aborted,
+            abort_reason: result.abort_reason.clone(),
+            unsent_requests: result.unsent_requests,
+        })
+    }
+}
+
+// Funde os relatórios dos agentes num resultado só. Os histogramas são somados (percentis
+// exatos do cluster); na timeline os percentis de cada balde são o pior entre os agentes.
+pub fn merge(
+    reports: Vec<AgentReport>,
+    warmup: Duration,
+    planned_duration: Option<Duration>,
+    planned_requests: Option<u64>,
+) -> Result<LoadTestResult, String> {
+    let mut result = LoadTestResult {
+        successes: 0,
+        failures: 0,
+        bytes_sent: 0,
+        bytes_received: 0,
+        histogram: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+        response_histogram: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+        status_counts: HashMap::new(),
+        error_counts: HashMap::new(),
+        assertion_failures: 0,
+        measured_successes: 0,
+        measured_failures: 0,
+        steps: Vec::new(),
+        requests: Vec::new(),
+        phases: PhaseStats::default(),
+        udp: UdpStats::default(),
+        ws: WsStats::default(),
+        dropped_arrivals: 0,
+        late_arrivals: 0,
+        timeline: Vec::new(),
+        capacity: None,
+        adaptive: None,
+        warmup,
+        planned_duration,
+        planned_requests,
+        duration: Duration::ZERO,
+        interrupted: false,
+        aborted: false,
+        abort_reason: None,
+        unsent_requests: None,
+    };
+    let mut buckets: BTreeMap<u64, TimelinePoint> = BTreeMap::new();
+
+    for r in reports {
+        result.successes += r.successes;
+        result.failures += r.failures;
+        result.bytes_sent += r.bytes_sent;
+        result.bytes_received += r.bytes_received;
+        let _ = result.histogram.add(decode(&r.histogram)?);
+        let _ = result
+            .response_histogram
+            .add(decode(&r.response_histogram)?);
+        for (k, v) in r.status_counts {
+            *result.status_counts.entry(k).or_insert(0) += v;
+        }
+        for (k, v) in r.error_counts {
+            *result.error_counts.entry(k).or_insert(0) += v;
+        }
+        result.assertion_failures += r.assertion_failures;
+        result.measured_successes += r.measured_successes;
+        result.measured_failures += r.measured_failures;
+        result.dropped_arrivals += r.dropped_arrivals;
+        result.late_arrivals += r.late_arrivals;
+        let steps = r
+            .steps
+            .into_iter()
+            .map(AgentStep::into_stats)
+            .collect::<Result<_, String>>()?;
+        merge_steps(&mut result.steps, steps);
+        let requests = r
+            .requests
+            .into_iter()
+            .map(AgentStep::into_stats)
+            .collect::<Result<_, String>>()?;
+        merge_steps(&mut result.requests, requests);
+        result.phases.merge(r.phases.into_stats()?);
+        result.udp.merge(r.udp);
+        result.ws.merge(r.ws.into_stats()?);
+        result.duration = result
+            .duration
+            .max(Duration::from_secs_f64(r.duration_secs));
+        result.interrupted |= r.interrupted;
+        result.aborted |= r.aborted;
+        // A primeira regra de parada a disparar em algum agente explica o teste todo
+        if result.abort_reason.is_none() {
+            result.abort_reason = r.abort_reason;
+        }
+        if let Some(n) = r.unsent_requests {
+            result.unsent_requests = Some(result.unsent_requests.unwrap_or(0) + n);
+        }
+
+        for point in r.timeline {
+            let key = (point.t_secs * 1000.0).round() as u64;
+            match buckets.get_mut(&key) {
+                Some(b) => {
+                    b.requests += point.requests;
+                    b.successes += point.successes;
+                    b.failures += point.failures;
+                    b.rps += point.rps;
+                    b.new_connections += point.new_connections;
+                    for (k, v) in point.status_codes {
+                        *b.status_codes.entry(k).or_insert(0) += v;
+                    }
+                    b.p50_ms = b.p50_ms.max(point.p50_ms);
+                    b.p95_ms = b.p95_ms.max(point.p95_ms);
+                    b.p99_ms = b.p99_ms.max(point.p99_ms);
+                    b.max_ms = b.max_ms.max(point.max_ms);
+                }
+                None => {
+                    buckets.insert(key, point);
+                }
+            }
+        }
+    }
+    result.timeline = buckets.into_values().collect();
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::timing::PhaseTimings;
+    use clap::Parser;
+
+    #[test]
+    fn test_split_args_preserves_totals() {
+        let args = Args::parse_from([
+            "cannon",
+            "-u",
+            "http://alvo",
+            "-c",
+            "10",
+            "-w",
+            "5",
+            "--rps",
+            "7",
+            "--ramp-up",
+            "30s:100,1m:5",
+        ]);
+        let parts = split_args(&args, 3).unwrap();
+
+        assert_eq!(parts.iter().map(|a| a.count).sum::<u32>(), 10);
+        assert_eq!(parts.iter().map(|a| a.workers).sum::<u32>(), 5);
+        assert_eq!(parts.iter().map(|a| a.rps.unwrap()).sum::<u32>(), 7);
+        assert_eq!(parts[0].ramp_up.as_deref(), Some("30000ms:34,60000ms:2"));
+        assert_eq!(parts[2].ramp_up.as_deref(), Some("30000ms:33,60000ms:1"));
+
+        assert!(split_args(&args, 8).is_err());
+        // O que o coordenador manda passa na checagem do agente
+        assert!(parts.iter().all(|part| check_share(part).is_ok()));
+    }
+
+    #[test]
+    fn test_merge_adds_histograms_across_agents() {
+        let report = |values: &[u64]| {
+            let mut hist = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap();
+            for v in values {
+                hist.record(*v).unwrap();
+            }
+            let empty = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap();
+            // Cada agente abriu uma conexão e reaproveitou nas demais
+            let mut phases = PhaseStats::default();
+            for (i, v) in values.iter().enumerate() {
+                phases.record(&PhaseTimings {
+                    connect: (i == 0).then(|| Duration::from_micros(500)),
+                    ttfb: Duration::from_micros(*v),
+                    ..Default::default()
+                });
+            }
+            AgentReport {
+                successes: values.len() as u64,
+                failures: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                histogram: encode(&hist).unwrap(),
+                response_histogram: encode(&empty).unwrap(),
+                status_counts: HashMap::from([(200, values.len() as u64)]),
+                error_counts: HashMap::new(),
+                assertion_failures: 0,
+                measured_successes: values.len() as u64,
+                measured_failures: 0,
+                dropped_arrivals: 0,
+                late_arrivals: 0,
+                steps: Vec::new(),
+                requests: Vec::new(),
+                phases: AgentPhases::from_stats(&phases).unwrap(),
+                udp: UdpStats::default(),
+                ws: AgentWs::from_stats(&WsStats::default()).unwrap(),
+                timeline: Vec::new(),
+                duration_secs: 1.0,
+                interrupted: false,
+                aborted: false,
+                abort_reason: None,
+                unsent_requests: None,
+            }
+        };
+
+        // Passa pelo fio como no modo distribuído de verdade
+        let wire = |r: AgentReport| {
+            let line = serde_json::to_string(&Message::Done {
+                report: Box::new(r),
+            })
+            .unwrap();
+            match serde_json::from_str(&line).unwrap() {
+                Message::Done { report } => *report,
+                other => panic!("mensagem inesperada: {:?}", other),
+            }
+        };
+
+        let merged = merge(
+            vec![wire(report(&[1_000, 2_000])), wire(report(&[100_000]))],
+            Duration::ZERO,
+            None,
+            Some(3),
+        )
+        .unwrap();
+
+        assert_eq!(merged.successes, 3);
+        assert_eq!(merged.histogram.len(), 3);
+        assert!(merged.histogram.max() >= 100_000);
+        assert_eq!(merged.status_counts[&200], 3);
+        assert_eq!(merged.phases.new_connections, 2);
+        assert_eq!(merged.phases.reused_connections, 1);
+        assert_eq!(merged.phases.ttfb.len(), 3);
+    }
+}
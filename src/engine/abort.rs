@@ -0,0 +1,273 @@
+// src/engine/abort.rs
+//
+// Regras de parada (--abort-on): avaliadas DURANTE o teste, sobre cada intervalo fechado da
+// timeline e sobre os atomics. A primeira que disparar encerra o despacho (o que está em voo
+// tem `drain_timeout` para terminar) e vira o motivo no relatório.
+//   "error_rate > 20% for 30s", "p99 > 2s for 1m", "connection_failures > 50"
+
+use super::worker::SharedMetrics;
+use super::Shutdown;
+use crate::metrics::timeline::IntervalSummary;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+// Cadência da checagem dos atomics (falhas de conexão seguidas)
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    // Fração de falhas do intervalo acima de `above`, por `sustained` seguidos
+    ErrorRate { above: f64, sustained: Duration },
+    // p99 (sucessos) do intervalo acima de `above_ms`, por `sustained` seguidos
+    P99 { above_ms: f64, sustained: Duration },
+    ConnectionFailures { above: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct AbortRule {
+    pub spec: String,
+    condition: Condition,
+}
+
+// Qual regra parou o teste, por quê e quando (segundos desde o início)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AbortReport {
+    pub rule: String,
+    pub reason: String,
+    pub at_secs: f64,
+}
+
+impl AbortRule {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = |why: &str| format!("Regra de parada inválida '{}': {}", spec, why);
+
+        let (condition, sustained) = match spec.split_once(" for ") {
+            Some((c, d)) => (
+                c,
+                Some(crate::utils::parse_duration(d.trim()).map_err(|e| invalid(&e))?),
+            ),
+            None => (spec, None),
+        };
+        let (metric, value) = condition
+            .split_once('>')
+            .ok_or_else(|| invalid("use 'métrica > valor' (ex: error_rate > 20% for 30s)"))?;
+        let value = value.trim();
+        let sustained = sustained.unwrap_or(Duration::ZERO);
+
+        let condition = match metric.trim().to_lowercase().as_str() {
+            "error_rate" => Condition::ErrorRate {
+                above: match value.strip_suffix('%') {
+                    Some(v) => v.trim().parse::<f64>().map(|v| v / 100.0),
+                    None => value.parse::<f64>(),
+                }
+                .map_err(|_| invalid("taxa (ex: 20% ou 0.2)"))?,
+                sustained,
+            },
+            "p99" => Condition::P99 {
+                // Sem unidade = ms, como nos thresholds
+                above_ms: match value.strip_suffix("ms") {
+                    Some(v) => v.trim().parse::<f64>().ok(),
+                    None => value.parse::<f64>().ok().or_else(|| {
+                        crate::utils::parse_duration(value)
+                            .ok()
+                            .map(|d| d.as_secs_f64() * 1000.0)
+                    }),
+                }
+                .ok_or_else(|| invalid("latência (ex: 800ms ou 2s)"))?,
+                sustained,
+            },
+            "connection_failures" => {
+                if !sustained.is_zero() {
+                    return Err(invalid(
+                        "connection_failures já é uma sequência; tire o 'for'",
+                    ));
+                }
+                Condition::ConnectionFailures {
+                    above: value
+                        .parse()
+                        .map_err(|_| invalid("quantidade de falhas seguidas"))?,
+                }
+            }
+            other => {
+                return Err(invalid(&format!(
+                    "métrica '{}' (use error_rate, p99 ou connection_failures)",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            spec: spec.trim().to_string(),
+            condition,
+        })
+    }
+}
+
+// Estado de uma regra: quantos intervalos seguidos ela já viu acima do limite
+struct Tracker<'a> {
+    rule: &'a AbortRule,
+    streak: u32,
+}
+
+impl Tracker<'_> {
+    // Intervalos sem dado (nenhuma resposta / nenhum sucesso) não contam nem zeram a sequência
+    fn observe(&mut self, s: &IntervalSummary, interval: Duration) -> Option<String> {
+        let (breached, sustained, detail) = match &self.rule.condition {
+            Condition::ErrorRate { above, sustained } => {
+                let total = s.successes + s.failures;
+                if total == 0 {
+                    return None;
+                }
+                let rate = s.failures as f64 / total as f64;
+                (
+                    rate > *above,
+                    *sustained,
+                    format!("taxa de erro de {:.1}%", rate * 100.0),
+                )
+            }
+            Condition::P99 {
+                above_ms,
+                sustained,
+            } => {
+                let p99 = s.p99_ms?;
+                (p99 > *above_ms, *sustained, format!("p99 de {:.2}ms", p99))
+            }
+            Condition::ConnectionFailures { .. } => return None,
+        };
+        if !breached {
+            self.streak = 0;
+            return None;
+        }
+        self.streak += 1;
+        let needed = (sustained.as_secs_f64() / interval.as_secs_f64())
+            .ceil()
+            .max(1.0) as u32;
+        (self.streak >= needed).then(|| {
+            format!(
+                "{} em {} intervalo(s) seguido(s) de {:?} (até {:.0}s)",
+                detail,
+                self.streak,
+                interval,
+                s.t_secs + interval.as_secs_f64()
+            )
+        })
+    }
+}
+
+// Vigia o teste até uma regra disparar (Some) ou o teste acabar por outro motivo (None)
+pub(crate) async fn watch(
+    rules: Vec<AbortRule>,
+    interval: Duration,
+    mut closed: mpsc::UnboundedReceiver<IntervalSummary>,
+    metrics: Arc<SharedMetrics>,
+    shutdown: Shutdown,
+    started_at: Instant,
+    drain_timeout: Duration,
+) -> Option<AbortReport> {
+    let mut trackers: Vec<Tracker> = rules
+        .iter()
+        .map(|rule| Tracker { rule, streak: 0 })
+        .collect();
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+    let (rule, reason) = loop {
+        tokio::select! {
+            summary = closed.recv() => {
+                let summary = summary?;
+                // Todas observam o intervalo (as sequências andam juntas); vale a primeira que disparar
+                let mut tripped = None;
+                for t in &mut trackers {
+                    if let Some(reason) = t.observe(&summary, interval) {
+                        tripped = tripped.or(Some((t.rule, reason)));
+                    }
+                }
+                if let Some(tripped) = tripped {
+                    break tripped;
+                }
+            }
+            _ = poll.tick() => {
+                let seq = metrics.consecutive_no_response.load(Ordering::Relaxed);
+                let tripped = rules.iter().find(|r| {
+                    matches!(r.condition, Condition::ConnectionFailures { above } if seq > above)
+                });
+                if let Some(rule) = tripped {
+                    break (rule, format!("{} requisições seguidas sem resposta do alvo", seq));
+                }
+            }
+            _ = shutdown.stopped() => return None,
+        }
+    };
+
+    shutdown.stop();
+    let hard_stop = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(drain_timeout).await;
+        hard_stop.abort();
+    });
+
+    Some(AbortReport {
+        rule: rule.spec.clone(),
+        reason,
+        at_secs: started_at.elapsed().as_secs_f64(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(successes: u64, failures: u64, p99_ms: Option<f64>) -> IntervalSummary {
+        IntervalSummary {
+            t_secs: 0.0,
+            successes,
+            failures,
+            p99_ms,
+        }
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rule = AbortRule::parse("error_rate > 20% for 30s").unwrap();
+        assert_eq!(
+            rule.condition,
+            Condition::ErrorRate {
+                above: 0.2,
+                sustained: Duration::from_secs(30)
+            }
+        );
+        let rule = AbortRule::parse("p99 > 1.5s").unwrap();
+        assert_eq!(
+            rule.condition,
+            Condition::P99 {
+                above_ms: 1500.0,
+                sustained: Duration::ZERO
+            }
+        );
+        assert!(AbortRule::parse("connection_failures > 50").is_ok());
+        assert!(AbortRule::parse("connection_failures > 50 for 10s").is_err());
+        assert!(AbortRule::parse("p95 > 1s").is_err());
+        assert!(AbortRule::parse("error_rate 20%").is_err());
+    }
+
+    #[test]
+    fn test_rule_needs_a_sustained_breach() {
+        let rule = AbortRule::parse("error_rate > 50% for 3s").unwrap();
+        let mut tracker = Tracker {
+            rule: &rule,
+            streak: 0,
+        };
+        let interval = Duration::from_secs(1);
+
+        assert!(tracker.observe(&summary(1, 9, None), interval).is_none());
+        assert!(tracker.observe(&summary(1, 9, None), interval).is_none());
+        // Uma janela saudável zera a sequência; uma sem dados não
+        assert!(tracker.observe(&summary(9, 1, None), interval).is_none());
+        assert!(tracker.observe(&summary(1, 9, None), interval).is_none());
+        assert!(tracker.observe(&summary(0, 0, None), interval).is_none());
+        assert!(tracker.observe(&summary(1, 9, None), interval).is_none());
+        assert!(tracker.observe(&summary(1, 9, None), interval).is_some());
+    }
+}
@@ -0,0 +1,343 @@
+// src/engine/adaptive.rs
+
+use crate::client::target::{Session, Target};
+use crate::engine::worker::{send_csv, CsvRecord, SharedMetrics, WorkerResult, WorkerState};
+use crate::engine::Shutdown;
+use crate::payload::generator::PayloadTemplate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+
+// De quanto em quanto tempo o controlador reavalia o limite
+const ADJUST_WINDOW: Duration = Duration::from_secs(1);
+// Corte multiplicativo do AIMD quando a latência estoura ou aparecem erros
+const AIMD_BACKOFF: f64 = 0.9;
+// Suavização do limitador por gradiente (peso da nova estimativa)
+const GRADIENT_SMOOTHING: f64 = 0.2;
+// Vazão dentro desta fração do pico ainda conta como "no platô"
+const KNEE_TOLERANCE: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitAlgorithm {
+    // Soma 1 por janela; corta 10% se a latência média passar do alvo ou houver erros
+    Aimd { latency_target_ms: f64 },
+    // Estilo Netflix concurrency-limits: limite * (latência mínima / atual) + √limite
+    Gradient,
+}
+
+impl LimitAlgorithm {
+    pub fn parse(s: &str, latency_target_ms: f64) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "aimd" => Ok(Self::Aimd { latency_target_ms }),
+            "gradient" => Ok(Self::Gradient),
+            other => Err(format!(
+                "Algoritmo adaptativo desconhecido '{}' (use aimd ou gradient)",
+                other
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Aimd { .. } => "aimd",
+            Self::Gradient => "gradient",
+        }
+    }
+}
+
+pub struct ConcurrencyLimiter {
+    algorithm: LimitAlgorithm,
+    limit: f64,
+    max: f64,
+    min_latency_ms: f64,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(algorithm: LimitAlgorithm, max: u32) -> Self {
+        Self {
+            algorithm,
+            limit: 1.0,
+            max: max.max(1) as f64,
+            min_latency_ms: f64::MAX,
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit as u32
+    }
+
+    // Recalcula o limite a partir da latência média e da taxa de erro da última janela
+    pub fn update(&mut self, avg_latency_ms: f64, error_rate: f64) -> u32 {
+        if avg_latency_ms <= 0.0 && error_rate == 0.0 {
+            // Janela sem respostas: nada a aprender
+            return self.limit();
+        }
+
+        let next = match self.algorithm {
+            LimitAlgorithm::Aimd { latency_target_ms } => {
+                if error_rate > 0.0 || avg_latency_ms > latency_target_ms {
+                    self.limit * AIMD_BACKOFF
+                } else {
+                    self.limit + 1.0
+                }
+            }
+            LimitAlgorithm::Gradient => {
+                if avg_latency_ms > 0.0 {
+                    self.min_latency_ms = self.min_latency_ms.min(avg_latency_ms);
+                }
+                let gradient = if error_rate > 0.0 {
+                    0.5
+                } else {
+                    (self.min_latency_ms / avg_latency_ms).clamp(0.5, 1.0)
+                };
+                let estimate = self.limit * gradient + self.limit.sqrt();
+                self.limit * (1.0 - GRADIENT_SMOOTHING) + estimate * GRADIENT_SMOOTHING
+            }
+        };
+
+        self.limit = next.clamp(1.0, self.max);
+        self.limit()
+    }
+}
+
+// Um ponto da trajetória: o limite vigente durante a janela e o que ela produziu
+#[derive(Serialize, Debug, Clone)]
+pub struct ConcurrencySample {
+    pub elapsed_secs: f64,
+    pub concurrency: u32,
+    pub rps: f64,
+    pub avg_latency_ms: f64,
+    pub error_rate: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AdaptiveReport {
+    pub algorithm: String,
+    // Menor concorrência cuja vazão já está no platô (o "joelho")
+    pub knee_concurrency: Option<u32>,
+    pub max_throughput_rps: f64,
+    pub trajectory: Vec<ConcurrencySample>,
+}
+
+// Agrupa a trajetória por concorrência e devolve a menor que entrega ~o pico de vazão
+pub fn find_knee(trajectory: &[ConcurrencySample]) -> Option<(u32, f64)> {
+    let mut by_limit: BTreeMap<u32, (f64, u32)> = BTreeMap::new();
+    for s in trajectory {
+        let entry = by_limit.entry(s.concurrency).or_insert((0.0, 0));
+        entry.0 += s.rps;
+        entry.1 += 1;
+    }
+    let averages: Vec<(u32, f64)> = by_limit
+        .into_iter()
+        .map(|(limit, (sum, n))| (limit, sum / n as f64))
+        .collect();
+
+    let peak = averages.iter().map(|(_, rps)| *rps).fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return None;
+    }
+    averages
+        .into_iter()
+        .find(|(_, rps)| *rps >= peak * KNEE_TOLERANCE)
+        .map(|(limit, _)| (limit, peak))
+}
+
+// Executor adaptativo: `max_workers` workers existem, mas só os `limit` primeiros disparam.
+// O controlador lê os atomics do SharedMetrics a cada janela e move o limite.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_adaptive(
+    count: u32,
+    duration: Option<Duration>,
+    max_workers: u32,
+    algorithm: LimitAlgorithm,
+    template: Option<Arc<PayloadTemplate>>,
+    target: Arc<Target>,
+    shared_metrics: Arc<SharedMetrics>,
+    csv_tx: Option<mpsc::Sender<CsvRecord>>,
+    start_time: Instant,
+    warmup_end: Instant,
+    shutdown: Shutdown,
+) -> (Vec<WorkerResult>, AdaptiveReport) {
+    let mut limiter = ConcurrencyLimiter::new(algorithm, max_workers);
+    let (limit_tx, limit_rx) = watch::channel(limiter.limit());
+    let stopped = Arc::new(AtomicBool::new(false));
+    let issued = Arc::new(AtomicU64::new(0));
+    let deadline = duration.map(|d| start_time + d);
+
+    let mut handles = Vec::new();
+    for id in 0..max_workers {
+        let mut gate = limit_rx.clone();
+        let stopped = stopped.clone();
+        let issued = issued.clone();
+        let template = template.clone();
+        let target = target.clone();
+        let shared = shared_metrics.clone();
+        let csv_tx = csv_tx.clone();
+        let shutdown = shutdown.clone();
+
+        handles.push(tokio::spawn(async move {
+            let mut payload_buffer = Vec::with_capacity(1024);
+            let mut state = WorkerState::new(&shared);
+            let mut session = Session::default();
+
+            // Espera a vez (id < limite); o sender cai quando o teste acaba
+            while gate.wait_for(|limit| id < *limit).await.is_ok() {
+                if stopped.load(Ordering::Relaxed) || shutdown.is_stopped() {
+                    break;
+                }
+                if deadline.is_none() && issued.fetch_add(1, Ordering::Relaxed) >= count as u64 {
+                    break;
+                }
+
+                if let Some(tpl) = &template {
+                    tpl.render(&mut payload_buffer);
+                }
+                let res = target
+                    .fire(&mut session, &payload_buffer, shutdown.abort_token())
+                    .await;
+                state.record(&res, None, &shared, warmup_end);
+                send_csv(&csv_tx, res, start_time).await;
+            }
+            state.finish()
+        }));
+    }
+
+    // Controlador: uma janela por vez até o prazo (ou até a contagem esgotar)
+    let mut trajectory = Vec::new();
+    let mut last_succ = 0;
+    let mut last_fail = 0;
+    let mut last_latency = 0;
+    let mut window = tokio::time::interval(ADJUST_WINDOW);
+    window.tick().await;
+
+    loop {
+        let done = match deadline {
+            Some(d) => Instant::now() >= d,
+            None => issued.load(Ordering::Relaxed) >= count as u64,
+        };
+        if done {
+            break;
+        }
+        tokio::select! {
+            _ = shutdown.stopped() => break,
+            _ = window.tick() => {}
+        }
+
+        let succ = shared_metrics.successes.load(Ordering::Relaxed);
+        let fail = shared_metrics.failures.load(Ordering::Relaxed);
+        let latency = shared_metrics.latency_sum_us.load(Ordering::Relaxed);
+        let (d_succ, d_fail, d_latency) =
+            (succ - last_succ, fail - last_fail, latency - last_latency);
+        (last_succ, last_fail, last_latency) = (succ, fail, latency);
+
+        let completed = d_succ + d_fail;
+        let avg_latency_ms = if d_succ > 0 {
+            d_latency as f64 / d_succ as f64 / 1000.0
+        } else {
+            0.0
+        };
+        let error_rate = if completed > 0 {
+            d_fail as f64 / completed as f64
+        } else {
+            0.0
+        };
+
+        trajectory.push(ConcurrencySample {
+            elapsed_secs: start_time.elapsed().as_secs_f64(),
+            concurrency: limiter.limit(),
+            rps: completed as f64 / ADJUST_WINDOW.as_secs_f64(),
+            avg_latency_ms,
+            error_rate,
+        });
+
+        let _ = limit_tx.send(limiter.update(avg_latency_ms, error_rate));
+    }
+
+    stopped.store(true, Ordering::Relaxed);
+    drop(limit_tx);
+
+    let mut results = Vec::new();
+    for handle in handles {
+        if let Ok(res) = handle.await {
+            results.push(res);
+        }
+    }
+
+    let knee = find_knee(&trajectory);
+    let report = AdaptiveReport {
+        algorithm: algorithm.name().to_string(),
+        knee_concurrency: knee.map(|(limit, _)| limit),
+        max_throughput_rps: knee.map(|(_, rps)| rps).unwrap_or(0.0),
+        trajectory,
+    };
+    (results, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aimd_increases_then_backs_off() {
+        let mut limiter = ConcurrencyLimiter::new(
+            LimitAlgorithm::Aimd {
+                latency_target_ms: 100.0,
+            },
+            50,
+        );
+        for _ in 0..9 {
+            limiter.update(20.0, 0.0);
+        }
+        assert_eq!(limiter.limit(), 10);
+        assert_eq!(limiter.update(250.0, 0.0), 9);
+        assert_eq!(limiter.update(20.0, 0.1), 8);
+    }
+
+    #[test]
+    fn test_limit_never_exceeds_max_workers() {
+        let mut limiter = ConcurrencyLimiter::new(LimitAlgorithm::Gradient, 8);
+        for _ in 0..200 {
+            limiter.update(5.0, 0.0);
+        }
+        assert_eq!(limiter.limit(), 8);
+    }
+
+    #[test]
+    fn test_gradient_shrinks_when_latency_grows() {
+        let mut limiter = ConcurrencyLimiter::new(LimitAlgorithm::Gradient, 1000);
+        for _ in 0..50 {
+            limiter.update(10.0, 0.0);
+        }
+        let grown = limiter.limit();
+        for _ in 0..10 {
+            limiter.update(40.0, 0.0);
+        }
+        assert!(limiter.limit() < grown);
+    }
+
+    #[test]
+    fn test_knee_is_first_limit_on_the_plateau() {
+        let sample = |concurrency, rps| ConcurrencySample {
+            elapsed_secs: 0.0,
+            concurrency,
+            rps,
+            avg_latency_ms: 1.0,
+            error_rate: 0.0,
+        };
+        let trajectory = vec![
+            sample(1, 100.0),
+            sample(2, 200.0),
+            sample(3, 290.0),
+            sample(4, 300.0),
+            sample(5, 301.0),
+            sample(6, 299.0),
+        ];
+        let (knee, peak) = find_knee(&trajectory).unwrap();
+        assert_eq!(knee, 3);
+        assert_eq!(peak, 301.0);
+        assert!(find_knee(&[]).is_none());
+    }
+}
@@ -0,0 +1,320 @@
+// src/engine/capacity.rs
+
+use crate::client::target::Target;
+use crate::engine::worker::{run_workers, SharedMetrics, WorkerResult};
+use crate::engine::Shutdown;
+use crate::payload::generator::PayloadTemplate;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Abaixo disso o degrau não sustentou a taxa pedida (o canhão ou o alvo saturou)
+const MIN_ACHIEVED_RATIO: f64 = 0.95;
+
+// O que conta como "aguentou" num degrau
+#[derive(Debug, Clone, Copy)]
+pub struct Slo {
+    pub p99_ms: f64,
+    // Fração (0.01 = 1%)
+    pub max_error_rate: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CapacitySearch {
+    pub start_rps: u32,
+    pub step_rps: u32,
+    pub max_rps: u32,
+    pub step_duration: Duration,
+    pub slo: Slo,
+}
+
+// Medição bruta de um degrau
+#[derive(Debug, Clone, Copy)]
+pub struct StepMeasurement {
+    pub achieved_rps: f64,
+    pub p99_ms: f64,
+    pub error_rate: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StepResult {
+    pub phase: &'static str,
+    pub target_rps: u32,
+    pub achieved_rps: f64,
+    pub p99_ms: f64,
+    pub error_rate: f64,
+    pub passed: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CapacityReport {
+    pub slo_p99_ms: f64,
+    pub slo_error_rate: f64,
+    pub max_sustainable_rps: Option<u32>,
+    pub steps: Vec<StepResult>,
+}
+
+impl CapacitySearch {
+    // A busca binária para quando o intervalo fica menor que 10% do degrau
+    fn resolution(&self) -> u32 {
+        (self.step_rps / 10).max(1)
+    }
+
+    fn evaluate(&self, phase: &'static str, target_rps: u32, m: StepMeasurement) -> StepResult {
+        let passed = m.p99_ms <= self.slo.p99_ms
+            && m.error_rate <= self.slo.max_error_rate
+            && m.achieved_rps >= target_rps as f64 * MIN_ACHIEVED_RATIO;
+        StepResult {
+            phase,
+            target_rps,
+            achieved_rps: m.achieved_rps,
+            p99_ms: m.p99_ms,
+            error_rate: m.error_rate,
+            passed,
+        }
+    }
+
+    // Sobe em degraus até a primeira violação do SLO e depois faz busca binária entre o
+    // último degrau aprovado e o reprovado. `measure` roda um degrau na taxa pedida e
+    // devolve None se o teste foi interrompido (a busca para onde estiver).
+    pub async fn run<F, Fut>(&self, mut measure: F) -> CapacityReport
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Option<StepMeasurement>>,
+    {
+        let mut steps = Vec::new();
+        let mut last_good: Option<u32> = None;
+        let mut first_bad: Option<u32> = None;
+
+        let mut rps = self.start_rps.max(1);
+        while rps <= self.max_rps {
+            let Some(m) = measure(rps).await else {
+                return self.report(last_good, steps);
+            };
+            let step = self.evaluate("ramp", rps, m);
+            let passed = step.passed;
+            steps.push(step);
+            if !passed {
+                first_bad = Some(rps);
+                break;
+            }
+            last_good = Some(rps);
+            rps = rps.saturating_add(self.step_rps.max(1));
+        }
+
+        if let Some(mut hi) = first_bad {
+            let mut lo = last_good.unwrap_or(0);
+            while hi - lo > self.resolution() {
+                let mid = lo + (hi - lo) / 2;
+                let Some(m) = measure(mid).await else {
+                    break;
+                };
+                let step = self.evaluate("search", mid, m);
+                if step.passed {
+                    lo = mid;
+                    last_good = Some(mid);
+                } else {
+                    hi = mid;
+                }
+                steps.push(step);
+            }
+        }
+
+        self.report(last_good, steps)
+    }
+
+    fn report(&self, max_sustainable_rps: Option<u32>, steps: Vec<StepResult>) -> CapacityReport {
+        CapacityReport {
+            slo_p99_ms: self.slo.p99_ms,
+            slo_error_rate: self.slo.max_error_rate,
+            max_sustainable_rps,
+            steps,
+        }
+    }
+}
+
+// Roda a busca com o motor de workers fixos, um teste por degrau. Devolve os balanços de
+// todos os degraus (para o relatório agregado) e a tabela da busca.
+pub async fn run_capacity_search(
+    search: CapacitySearch,
+    workers: u32,
+    template: Option<Arc<PayloadTemplate>>,
+    target: Arc<Target>,
+    shared_metrics: Arc<SharedMetrics>,
+    shutdown: Shutdown,
+) -> (Vec<WorkerResult>, CapacityReport) {
+    let all_results = Mutex::new(Vec::new());
+
+    let report = search
+        .run(|rps| {
+            let template = template.clone();
+            let target = target.clone();
+            let shared = shared_metrics.clone();
+            let all_results = &all_results;
+            let step_duration = search.step_duration;
+            let shutdown = shutdown.clone();
+            async move {
+                if shutdown.is_stopped() {
+                    return None;
+                }
+
+                // Os degraus são sequenciais: o delta dos atomics é o placar do degrau
+                let succ_before = shared.successes.load(Ordering::Relaxed);
+                let fail_before = shared.failures.load(Ordering::Relaxed);
+
+                let step_start = Instant::now();
+                let results = run_workers(
+                    u32::MAX,
+                    workers,
+                    template,
+                    Some(rps),
+                    Some(step_duration),
+                    None,
+                    None,
+                    target,
+                    shared.clone(),
+                    None,
+                    step_start,
+                    step_start,
+                    shutdown.clone(),
+                )
+                .await;
+                let elapsed = step_start.elapsed().as_secs_f64();
+
+                let succ = shared.successes.load(Ordering::Relaxed) - succ_before;
+                let fail = shared.failures.load(Ordering::Relaxed) - fail_before;
+                let merged = WorkerResult::merge(results);
+                let total = succ + fail;
+
+                let measurement = StepMeasurement {
+                    achieved_rps: total as f64 / elapsed,
+                    // Tempo de resposta (desde o instante agendado): passado o ponto de
+                    // saturação a fila cresce e é ela que estoura o SLO, não o tempo de serviço
+                    p99_ms: merged.response_histogram.value_at_quantile(0.99) as f64 / 1000.0,
+                    error_rate: if total > 0 {
+                        fail as f64 / total as f64
+                    } else {
+                        1.0
+                    },
+                };
+                all_results.lock().unwrap().push(merged);
+                // Degrau cortado pelo Ctrl+C não vale como medição
+                (!shutdown.is_stopped()).then_some(measurement)
+            }
+        })
+        .await;
+
+    (all_results.into_inner().unwrap(), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search() -> CapacitySearch {
+        CapacitySearch {
+            start_rps: 100,
+            step_rps: 100,
+            max_rps: 10_000,
+            step_duration: Duration::from_secs(1),
+            slo: Slo {
+                p99_ms: 200.0,
+                max_error_rate: 0.01,
+            },
+        }
+    }
+
+    // Alvo simulado: p99 explode acima de 740 RPS
+    fn fake_service(rps: u32) -> StepMeasurement {
+        StepMeasurement {
+            achieved_rps: rps as f64,
+            p99_ms: if rps <= 740 { 50.0 } else { 900.0 },
+            error_rate: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_converges_on_the_knee() {
+        let report = search()
+            .run(|rps| async move { Some(fake_service(rps)) })
+            .await;
+        let found = report.max_sustainable_rps.unwrap();
+        assert!(
+            (730..=740).contains(&found),
+            "capacidade {} fora do joelho",
+            found
+        );
+        assert_eq!(report.steps.iter().filter(|s| s.phase == "ramp").count(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_search_reports_none_when_first_step_fails() {
+        let report = search()
+            .run(|rps| async move {
+                Some(StepMeasurement {
+                    error_rate: 0.5,
+                    ..fake_service(rps)
+                })
+            })
+            .await;
+        assert_eq!(report.max_sustainable_rps, None);
+    }
+
+    #[tokio::test]
+    async fn test_unsustained_rate_fails_the_step() {
+        let report = search()
+            .run(|rps| async move {
+                Some(StepMeasurement {
+                    achieved_rps: rps.min(300) as f64,
+                    ..fake_service(rps)
+                })
+            })
+            .await;
+        let found = report.max_sustainable_rps.unwrap();
+        assert!((300..=315).contains(&found));
+    }
+
+    // Servidor que atende um pedido por vez e sempre diz ter levado 5ms: quanto mais fila,
+    // mais o disparo demora, sem que o tempo de serviço mude
+    struct SingleLane(tokio::sync::Mutex<()>);
+
+    #[async_trait::async_trait]
+    impl crate::client::protocol::Protocol for SingleLane {
+        async fn fire(&self, _payload: &[u8]) -> crate::client::target::TargetResult {
+            let _lane = self.0.lock().await;
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            crate::client::target::TargetResult::success(Duration::from_millis(5), 0, 0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_steps_past_saturation_see_the_queue() {
+        let search = CapacitySearch {
+            start_rps: 50,
+            step_rps: 300,
+            max_rps: 350,
+            step_duration: Duration::from_millis(300),
+            slo: Slo {
+                p99_ms: 100.0,
+                max_error_rate: 0.01,
+            },
+        };
+        let result = crate::LoadTest::builder()
+            .protocol(Arc::new(SingleLane(Default::default())))
+            .executor(crate::Executor::Capacity { workers: 8, search })
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        let report = result.capacity.unwrap();
+        let step = |rps| report.steps.iter().find(|s| s.target_rps == rps).unwrap();
+        assert!(step(50).passed && step(50).p99_ms < 100.0);
+        // Acima dos ~200 RPS que a fila única aguenta o p99 é a espera, não os 5ms do serviço
+        assert!(!step(350).passed);
+        assert!(step(350).p99_ms > 100.0, "p99 {}ms", step(350).p99_ms);
+    }
+}
@@ -1,4 +1,79 @@
+pub mod abort;
+pub mod adaptive;
+pub mod capacity;
+pub mod open_model;
+pub mod profile;
 pub mod worker;
 
+use adaptive::AdaptiveReport;
+use capacity::CapacityReport;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+use worker::WorkerResult;
+
 // Re-exporta a função principal para não quebrar o main.rs
 pub use worker::run_workers;
+
+// O que qualquer executor devolve: os balanços dos workers e, conforme o modo, o relatório extra
+#[derive(Default)]
+pub struct EngineRun {
+    pub results: Vec<WorkerResult>,
+    pub capacity: Option<CapacityReport>,
+    pub adaptive: Option<AdaptiveReport>,
+}
+
+// Sinais de parada do teste: `stop` encerra o despacho e deixa o que está em voo drenar,
+// `abort` cancela também as requisições em voo. Abortar implica parar.
+#[derive(Clone)]
+pub struct Shutdown {
+    abort: CancellationToken,
+    stop: CancellationToken,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        let abort = CancellationToken::new();
+        let stop = abort.child_token();
+        Self { abort, stop }
+    }
+}
+
+impl Shutdown {
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+
+    pub fn abort(&self) {
+        self.abort.cancel();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.is_cancelled()
+    }
+
+    pub fn stopped(&self) -> WaitForCancellationFuture<'_> {
+        self.stop.cancelled()
+    }
+
+    // Token repassado ao Target::fire
+    pub fn abort_token(&self) -> &CancellationToken {
+        &self.abort
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abort_implies_stop_but_not_the_reverse() {
+        let drain = Shutdown::default();
+        drain.stop();
+        assert!(drain.is_stopped());
+        assert!(!drain.abort_token().is_cancelled());
+
+        let hard = Shutdown::default();
+        hard.abort();
+        assert!(hard.is_stopped());
+        assert!(hard.abort_token().is_cancelled());
+    }
+}
@@ -0,0 +1,215 @@
+// src/engine/open_model.rs
+
+use crate::client::target::{Session, Target, TargetResult};
+use crate::engine::profile::LoadProfile;
+use crate::engine::worker::{send_csv, CsvRecord, SharedMetrics, WorkerResult, WorkerState};
+use crate::engine::Shutdown;
+use crate::payload::generator::PayloadTemplate;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Semaphore};
+
+// Atraso a partir do qual uma chegada conta como "atrasada" (o gerador não acompanhou a agenda)
+const LATE_ARRIVAL_TOLERANCE: Duration = Duration::from_millis(5);
+
+// Como os intervalos entre chegadas são sorteados no modelo aberto
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrivalDistribution {
+    // Intervalos idênticos (1/RPS)
+    Constant,
+    // Processo de Poisson: intervalos exponenciais com média 1/RPS
+    Poisson,
+    // Intervalo 1/RPS com jitter uniforme de ±fração (0.0 a 1.0)
+    Uniform(f64),
+}
+
+impl ArrivalDistribution {
+    // Aceita "constant", "poisson", "uniform" (jitter de 50%) ou "uniform:0.2"
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "constant" => Ok(Self::Constant),
+            "poisson" => Ok(Self::Poisson),
+            "uniform" => Ok(Self::Uniform(0.5)),
+            _ => {
+                let jitter = s
+                    .strip_prefix("uniform:")
+                    .and_then(|j| j.parse::<f64>().ok())
+                    .filter(|j| (0.0..=1.0).contains(j))
+                    .ok_or_else(|| {
+                        format!(
+                            "Distribuição de chegada inválida '{}' (use constant, poisson ou uniform:0.0-1.0)",
+                            s
+                        )
+                    })?;
+                Ok(Self::Uniform(jitter))
+            }
+        }
+    }
+
+    // Sorteia o próximo intervalo entre chegadas para a taxa `rate` (req/s)
+    pub fn next_gap(&self, rate: f64) -> Duration {
+        let mean = 1.0 / rate;
+        let secs = match self {
+            Self::Constant => mean,
+            // 1 - U evita ln(0)
+            Self::Poisson => -(1.0 - fastrand::f64()).ln() * mean,
+            Self::Uniform(jitter) => mean * (1.0 + jitter * (2.0 * fastrand::f64() - 1.0)),
+        };
+        Duration::from_secs_f64(secs.max(0.0))
+    }
+}
+
+// Uma resposta do modelo aberto a caminho do agregador
+struct Sample {
+    res: TargetResult,
+    intended: Instant,
+}
+
+// Executor de modelo aberto: as chegadas seguem a agenda independente de quantas
+// requisições ainda estão em voo. O limite é `max_vus` (se houver); chegadas sem vaga
+// são descartadas e contadas, em vez de frear o gerador como no modelo fechado.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_open_model(
+    count: u32,
+    rps: Option<u32>,
+    duration: Option<Duration>,
+    profile: Option<Arc<LoadProfile>>,
+    distribution: ArrivalDistribution,
+    max_vus: Option<u32>,
+    template: Option<Arc<PayloadTemplate>>,
+    target: Arc<Target>,
+    shared_metrics: Arc<SharedMetrics>,
+    csv_tx: Option<mpsc::Sender<CsvRecord>>,
+    start_time: Instant,
+    warmup_end: Instant,
+    shutdown: Shutdown,
+) -> Vec<WorkerResult> {
+    let (sample_tx, mut sample_rx) = mpsc::unbounded_channel::<Sample>();
+
+    // Um único agregador mantém o estado local (as tarefas de disparo são efêmeras)
+    let shared = shared_metrics.clone();
+    let aggregator = tokio::spawn(async move {
+        let mut state = WorkerState::new(&shared);
+        while let Some(sample) = sample_rx.recv().await {
+            state.record(&sample.res, Some(sample.intended), &shared, warmup_end);
+            send_csv(&csv_tx, sample.res, start_time).await;
+        }
+        state.finish()
+    });
+
+    let slots = max_vus.map(|n| Arc::new(Semaphore::new(n as usize)));
+    let schedule_start = Instant::now();
+    let stop_after = profile.as_ref().map(|p| p.total_duration()).or(duration);
+    let mut next_arrival = schedule_start;
+    let mut arrivals: u32 = 0;
+    // Com perfil, as chegadas são sorteadas em "disparos devidos" (taxa 1) e levadas ao relógio
+    // por `due_at`, o inverso da área sob a rampa: a taxa acompanha a rampa mesmo partindo de 0
+    // e estágios em 0 RPS ficam vazios
+    let mut due = 0.0;
+
+    loop {
+        if let Some(p) = &profile {
+            match p.due_at(due) {
+                Some(at) => next_arrival = schedule_start + at,
+                None => break,
+            }
+        }
+        let elapsed = next_arrival.saturating_duration_since(schedule_start);
+        match stop_after {
+            Some(limit) if elapsed >= limit => break,
+            None if arrivals >= count => break,
+            _ => {}
+        }
+
+        tokio::select! {
+            _ = shutdown.stopped() => break,
+            _ = tokio::time::sleep_until(next_arrival.into()) => {}
+        }
+        let intended = next_arrival;
+        match &profile {
+            Some(_) => due += distribution.next_gap(1.0).as_secs_f64(),
+            // Sem perfil o validate() do executor já garantiu rps > 0
+            None => next_arrival += distribution.next_gap(rps.unwrap_or(1).max(1) as f64),
+        }
+        arrivals = arrivals.saturating_add(1);
+
+        if intended.elapsed() > LATE_ARRIVAL_TOLERANCE {
+            shared_metrics.late_arrivals.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let permit = match &slots {
+            Some(sem) => match sem.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    shared_metrics
+                        .dropped_arrivals
+                        .fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let template = template.clone();
+        let target = target.clone();
+        let tx = sample_tx.clone();
+        let abort = shutdown.abort_token().clone();
+        tokio::spawn(async move {
+            let mut payload = Vec::new();
+            if let Some(tpl) = &template {
+                tpl.render(&mut payload);
+            }
+            // Sem worker fixo: cada chegada usa o pool compartilhado
+            let res = target.fire(&mut Session::default(), &payload, &abort).await;
+            drop(permit);
+            let _ = tx.send(Sample { res, intended });
+        });
+    }
+
+    // O agregador termina quando a última tarefa em voo soltar o seu sender
+    drop(sample_tx);
+    aggregator.await.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_distributions() {
+        assert_eq!(
+            ArrivalDistribution::parse("constant").unwrap(),
+            ArrivalDistribution::Constant
+        );
+        assert_eq!(
+            ArrivalDistribution::parse("Poisson").unwrap(),
+            ArrivalDistribution::Poisson
+        );
+        assert_eq!(
+            ArrivalDistribution::parse("uniform:0.2").unwrap(),
+            ArrivalDistribution::Uniform(0.2)
+        );
+        assert!(ArrivalDistribution::parse("uniform:2").is_err());
+        assert!(ArrivalDistribution::parse("gaussian").is_err());
+    }
+
+    #[test]
+    fn test_poisson_gaps_average_to_the_rate() {
+        let dist = ArrivalDistribution::Poisson;
+        let n = 20_000;
+        let total: f64 = (0..n).map(|_| dist.next_gap(100.0).as_secs_f64()).sum();
+        let mean = total / n as f64;
+        assert!((mean - 0.01).abs() < 0.001, "média {} longe de 10ms", mean);
+    }
+
+    #[test]
+    fn test_uniform_gaps_stay_within_jitter() {
+        let dist = ArrivalDistribution::Uniform(0.5);
+        for _ in 0..1_000 {
+            let gap = dist.next_gap(10.0).as_secs_f64();
+            assert!((0.05..=0.15).contains(&gap));
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// src/engine/profile.rs
+
+use crate::utils::parse_duration;
+use std::time::Duration;
+
+// Um estágio leva o RPS alvo linearmente do valor anterior até `target` em `duration`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub duration: Duration,
+    pub target: u32,
+}
+
+// Perfil de carga escalonado (ex: "30s:100,2m:500,30s:0"). O primeiro estágio parte de 0 RPS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadProfile {
+    pub stages: Vec<Stage>,
+}
+
+impl LoadProfile {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut stages = Vec::new();
+
+        for raw in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (dur, target) = raw
+                .split_once(':')
+                .ok_or_else(|| format!("Estágio inválido '{}' (esperado DURAÇÃO:RPS)", raw))?;
+            let target = target
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("RPS inválido no estágio '{}'", raw))?;
+            stages.push(Stage {
+                duration: parse_duration(dur)?,
+                target,
+            });
+        }
+
+        if stages.is_empty() {
+            return Err("O perfil de ramp-up precisa de pelo menos um estágio".to_string());
+        }
+
+        Ok(Self { stages })
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.stages.iter().map(|s| s.duration).sum()
+    }
+
+    // RPS alvo instantâneo no tempo `elapsed` (interpolação linear dentro do estágio)
+    pub fn rate_at(&self, elapsed: Duration) -> f64 {
+        let mut from = 0.0;
+        let mut t = elapsed.as_secs_f64();
+
+        for stage in &self.stages {
+            let d = stage.duration.as_secs_f64();
+            let to = stage.target as f64;
+            if t < d {
+                return from + (to - from) * (t / d);
+            }
+            t -= d;
+            from = to;
+        }
+        from
+    }
+
+    // Quantos disparos já deveriam ter saído até `elapsed` (integral do RPS no tempo)
+    pub fn requests_due(&self, elapsed: Duration) -> f64 {
+        let mut from = 0.0;
+        let mut t = elapsed.as_secs_f64();
+        let mut total = 0.0;
+
+        for stage in &self.stages {
+            let d = stage.duration.as_secs_f64();
+            let to = stage.target as f64;
+            if t < d {
+                let current = from + (to - from) * (t / d);
+                return total + (from + current) / 2.0 * t;
+            }
+            total += (from + to) / 2.0 * d;
+            t -= d;
+            from = to;
+        }
+        total
+    }
+
+    // O inverso de `requests_due`: o instante em que o disparo número `n` (1, 2, ...) fica devido.
+    // None quando o perfil inteiro não chega a `n` disparos.
+    pub fn due_at(&self, n: f64) -> Option<Duration> {
+        let mut from = 0.0;
+        let mut start = 0.0;
+        let mut total = 0.0;
+
+        for stage in &self.stages {
+            let d = stage.duration.as_secs_f64();
+            let to = stage.target as f64;
+            let area = (from + to) / 2.0 * d;
+            if area > 0.0 && total + area >= n {
+                // Dentro do estágio: from·t + (to - from)/(2d)·t² = resto (forma que não divide
+                // por zero quando o RPS é constante)
+                let rest = n - total;
+                let a = (to - from) / (2.0 * d);
+                let t = 2.0 * rest / (from + (from * from + 4.0 * a * rest).max(0.0).sqrt());
+                return Some(Duration::from_secs_f64(start + t.min(d)));
+            }
+            total += area;
+            start += d;
+            from = to;
+        }
+        None
+    }
+
+    // Estimativa do total de disparos do perfil inteiro (usado na barra de progresso)
+    pub fn estimated_requests(&self) -> u64 {
+        self.requests_due(self.total_duration()).round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stages() {
+        let p = LoadProfile::parse("30s:100, 2m:500,30s:0").unwrap();
+        assert_eq!(p.stages.len(), 3);
+        assert_eq!(p.stages[1].duration, Duration::from_secs(120));
+        assert_eq!(p.stages[1].target, 500);
+        assert_eq!(p.total_duration(), Duration::from_secs(180));
+    }
+
+    #[test]
+    fn test_parse_invalid_stage() {
+        assert!(LoadProfile::parse("30s").is_err());
+        assert!(LoadProfile::parse("30s:abc").is_err());
+        assert!(LoadProfile::parse("").is_err());
+    }
+
+    #[test]
+    fn test_linear_interpolation() {
+        let p = LoadProfile::parse("10s:100,10s:100,10s:0").unwrap();
+        assert_eq!(p.rate_at(Duration::from_secs(5)), 50.0);
+        assert_eq!(p.rate_at(Duration::from_secs(15)), 100.0);
+        assert_eq!(p.rate_at(Duration::from_secs(25)), 50.0);
+        assert_eq!(p.rate_at(Duration::from_secs(40)), 0.0);
+    }
+
+    #[test]
+    fn test_requests_due_is_the_area_under_the_ramp() {
+        let p = LoadProfile::parse("10s:100,10s:100,10s:0").unwrap();
+        // Rampa até 100 (500) + platô (1000) + rampa até 0 (500)
+        assert_eq!(p.requests_due(Duration::from_secs(10)), 500.0);
+        assert_eq!(p.estimated_requests(), 2000);
+    }
+
+    #[test]
+    fn test_due_at_inverts_requests_due() {
+        let p = LoadProfile::parse("10s:100,10s:100,10s:0").unwrap();
+        for n in [1.0, 42.0, 500.0, 1234.0, 1999.0, 2000.0] {
+            let t = p.due_at(n).unwrap();
+            assert!((p.requests_due(t) - n).abs() < 1e-6, "n = {}", n);
+        }
+        assert_eq!(p.due_at(500.0), Some(Duration::from_secs(10)));
+        assert_eq!(p.due_at(2001.0), None);
+    }
+}
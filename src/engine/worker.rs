@@ -1,7 +1,14 @@
 // src/engine/worker.rs
 
-use crate::client::target::Target;
+use crate::client::target::{Session, Target, TargetResult};
+use crate::engine::profile::LoadProfile;
+use crate::engine::Shutdown;
+use crate::metrics::phases::PhaseStats;
+use crate::metrics::timeline::{TimelineRecorder, TimelineSink};
+use crate::metrics::udp::UdpStats;
+use crate::metrics::ws::WsStats;
 use crate::payload::generator::PayloadTemplate;
+use crate::scenario::{merge_steps, StepStats};
 use hdrhistogram::Histogram;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -16,14 +23,82 @@ pub struct SharedMetrics {
     pub failures: AtomicU64,
     pub bytes_sent: AtomicU64,
     pub bytes_received: AtomicU64,
+    // Soma das latências de sucesso (µs): média por janela para o modo adaptativo
+    pub latency_sum_us: AtomicU64,
+    // Só no modelo aberto: chegadas descartadas por falta de VU e chegadas fora da agenda
+    pub dropped_arrivals: AtomicU64,
+    pub late_arrivals: AtomicU64,
+    // Falhas seguidas sem resposta nenhuma do alvo (recusa, reset, timeout); zera na primeira resposta
+    pub consecutive_no_response: AtomicU64,
+    // Série temporal por intervalo (cada worker manda os baldes fechados por aqui)
+    pub timeline: Option<TimelineSink>,
+}
+
+// Um disparo na fila. Em modos com ritmo (--rps / --ramp-up) carrega o instante em que
+// DEVERIA ter saído: a latência medida a partir dele não esconde o tempo preso na fila
+// quando o alvo trava (coordinated omission).
+#[derive(Clone, Copy)]
+pub struct Job {
+    pub intended: Option<Instant>,
 }
 
 // O que cada worker devolve no fim da sua vida
 pub struct WorkerResult {
+    // Tempo de serviço: do momento em que o worker pega o job até a resposta
     pub histogram: Histogram<u64>,
+    // Tempo de resposta: do instante agendado até a resposta (só em modos com ritmo)
+    pub response_histogram: Histogram<u64>,
     pub status_counts: HashMap<u16, u64>,
     pub error_counts: HashMap<String, u64>,
     pub assertion_failures: u64,
+    // Sucessos e falhas fora do warm-up, a mesma janela dos status e erros acima
+    pub measured_successes: u64,
+    pub measured_failures: u64,
+    // Só em cenários: métricas por passo, na ordem da jornada
+    pub steps: Vec<StepStats>,
+    // Alvos HTTP: DNS / TCP / TLS / TTFB / download e reuso de conexões
+    pub phases: PhaseStats,
+    // --mode udp: perda, duplicatas e atrasadas
+    pub udp: UdpStats,
+    // --mode ws: handshakes, mensagens e desconexões
+    pub ws: WsStats,
+}
+
+impl WorkerResult {
+    // Funde os balanços locais num só (o Merge final, sem locks durante o teste)
+    pub fn merge(results: Vec<WorkerResult>) -> WorkerResult {
+        let mut merged = WorkerResult {
+            histogram: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            response_histogram: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            status_counts: HashMap::new(),
+            error_counts: HashMap::new(),
+            assertion_failures: 0,
+            measured_successes: 0,
+            measured_failures: 0,
+            steps: Vec::new(),
+            phases: PhaseStats::default(),
+            udp: UdpStats::default(),
+            ws: WsStats::default(),
+        };
+        for w in results {
+            let _ = merged.histogram.add(w.histogram);
+            let _ = merged.response_histogram.add(w.response_histogram);
+            for (k, v) in w.status_counts {
+                *merged.status_counts.entry(k).or_insert(0) += v;
+            }
+            for (k, v) in w.error_counts {
+                *merged.error_counts.entry(k).or_insert(0) += v;
+            }
+            merged.assertion_failures += w.assertion_failures;
+            merged.measured_successes += w.measured_successes;
+            merged.measured_failures += w.measured_failures;
+            merge_steps(&mut merged.steps, w.steps);
+            merged.phases.merge(w.phases);
+            merged.udp.merge(w.udp);
+            merged.ws.merge(w.ws);
+        }
+        merged
+    }
 }
 
 pub struct CsvRecord {
@@ -33,19 +108,177 @@ pub struct CsvRecord {
     pub error: String,
 }
 
+// Acumuladores locais de um worker (ou do agregador do modelo aberto)
+pub(crate) struct WorkerState {
+    hist: Histogram<u64>,
+    response_hist: Histogram<u64>,
+    status: HashMap<u16, u64>,
+    errors: HashMap<String, u64>,
+    assert_failures: u64,
+    successes: u64,
+    failures: u64,
+    steps: Vec<StepStats>,
+    phases: PhaseStats,
+    udp: UdpStats,
+    ws: WsStats,
+    timeline: Option<TimelineRecorder>,
+}
+
+impl WorkerState {
+    pub(crate) fn new(shared: &SharedMetrics) -> Self {
+        Self {
+            hist: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            response_hist: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            status: HashMap::new(),
+            errors: HashMap::new(),
+            assert_failures: 0,
+            successes: 0,
+            failures: 0,
+            steps: Vec::new(),
+            phases: PhaseStats::default(),
+            udp: UdpStats::default(),
+            ws: WsStats::default(),
+            timeline: shared.timeline.as_ref().map(|t| t.recorder()),
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        res: &TargetResult,
+        intended: Option<Instant>,
+        shared: &SharedMetrics,
+        warmup_end: Instant,
+    ) {
+        let now = Instant::now();
+        let is_warmup = now < warmup_end;
+
+        // 1. Atualiza Atomics Globais (Rápido, vai direto pra L1 Cache)
+        if res.success {
+            shared.successes.fetch_add(1, Ordering::Relaxed);
+            shared
+                .latency_sum_us
+                .fetch_add(res.duration.as_micros() as u64, Ordering::Relaxed);
+            if !is_warmup {
+                let _ = self.hist.record(res.duration.as_micros() as u64);
+                if let Some(intended) = intended {
+                    let response_time = now.saturating_duration_since(intended);
+                    let _ = self.response_hist.record(response_time.as_micros() as u64);
+                }
+            }
+        } else {
+            shared.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        // Só escreve quando precisa: no caminho feliz a linha de cache fica compartilhada
+        if !res.success && res.status_code.is_none() {
+            shared
+                .consecutive_no_response
+                .fetch_add(1, Ordering::Relaxed);
+        } else if shared.consecutive_no_response.load(Ordering::Relaxed) != 0 {
+            shared.consecutive_no_response.store(0, Ordering::Relaxed);
+        }
+        shared
+            .bytes_sent
+            .fetch_add(res.bytes_sent, Ordering::Relaxed);
+        shared
+            .bytes_received
+            .fetch_add(res.bytes_received, Ordering::Relaxed);
+
+        // A série temporal cobre o teste inteiro, warm-up incluso
+        if let Some(timeline) = &mut self.timeline {
+            timeline.record(now, res);
+        }
+
+        // As conexões WebSocket abrem no começo, quase sempre dentro do warm-up: entram sempre
+        if let Some(ws) = &res.ws {
+            self.ws.record(ws);
+        }
+
+        // 2. Atualiza HashMaps Locais (fora do warm-up)
+        if !is_warmup {
+            if res.success {
+                self.successes += 1;
+            } else {
+                self.failures += 1;
+            }
+            if let Some(code) = res.status_code {
+                *self.status.entry(code).or_insert(0) += 1;
+            }
+            if let Some(err) = &res.error {
+                *self.errors.entry(err.clone()).or_insert(0) += 1;
+            }
+            if !res.assertion_success {
+                self.assert_failures += 1;
+            }
+            // Cenários e mix: cada passo tem o seu acumulador (o nome vem no fim do teste)
+            for step in &res.steps {
+                while self.steps.len() <= step.index {
+                    self.steps.push(StepStats::new(""));
+                }
+                self.steps[step.index].record(step);
+            }
+            for phases in res.phases() {
+                self.phases.record(phases);
+            }
+            if let Some(datagram) = &res.datagram {
+                self.udp.record(datagram);
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> WorkerResult {
+        WorkerResult {
+            histogram: self.hist,
+            response_histogram: self.response_hist,
+            status_counts: self.status,
+            error_counts: self.errors,
+            assertion_failures: self.assert_failures,
+            measured_successes: self.successes,
+            measured_failures: self.failures,
+            steps: self.steps,
+            phases: self.phases,
+            udp: self.udp,
+            ws: self.ws,
+        }
+    }
+}
+
+// 3. I/O Assíncrono de CSV (Se ativado)
+pub(crate) async fn send_csv(
+    csv_tx: &Option<mpsc::Sender<CsvRecord>>,
+    res: TargetResult,
+    start_time: Instant,
+) {
+    if let Some(tx) = csv_tx {
+        let rec = CsvRecord {
+            relative_ms: start_time.elapsed().as_millis().to_string(),
+            status: res
+                .status_code
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+            latency_ms: res.duration.as_millis().to_string(),
+            error: res.error.unwrap_or_default(),
+        };
+        let _ = tx.send(rec).await;
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn run_workers(
     count: u32,
     workers: u32,
     template: Option<Arc<PayloadTemplate>>,
     rps: Option<u32>,
+    duration: Option<std::time::Duration>,
+    profile: Option<Arc<LoadProfile>>,
+    schedule: Option<Arc<[std::time::Duration]>>,
     target: Arc<Target>,
     shared_metrics: Arc<SharedMetrics>,
     csv_tx: Option<mpsc::Sender<CsvRecord>>,
     start_time: Instant,
     warmup_end: Instant,
+    shutdown: Shutdown,
 ) -> Vec<WorkerResult> {
-    let (job_tx, async_job_rx) = async_channel::bounded::<()>(workers as usize);
+    let (job_tx, async_job_rx) = async_channel::bounded::<Job>(workers as usize);
     let mut handles = Vec::new();
 
     for _ in 0..workers {
@@ -54,17 +287,20 @@ pub async fn run_workers(
         let target = target.clone();
         let shared = shared_metrics.clone();
         let csv_tx = csv_tx.clone();
+        let shutdown = shutdown.clone();
 
         let handle = tokio::spawn(async move {
             let mut payload_buffer = Vec::with_capacity(1024);
 
             // Estado LOCAL do worker (Sem Lock!)
-            let mut local_hist = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap();
-            let mut local_status = HashMap::new();
-            let mut local_errors = HashMap::new();
-            let mut local_assert_failures = 0;
+            let mut state = WorkerState::new(&shared);
+            let mut session = Session::default();
 
-            while rx.recv().await.is_ok() {
+            while let Ok(job) = rx.recv().await {
+                // Depois do Ctrl+C os jobs ainda na fila nunca saem
+                if shutdown.is_stopped() {
+                    break;
+                }
                 if let Some(tpl) = &template {
                     tpl.render(&mut payload_buffer);
                 }
@@ -74,86 +310,100 @@ pub async fn run_workers(
                 } else {
                     &[]
                 };
-                let res = target.fire(payload_ref).await;
-
-                let is_warmup = Instant::now() < warmup_end;
+                let res = target
+                    .fire(&mut session, payload_ref, shutdown.abort_token())
+                    .await;
 
-                // 1. Atualiza Atomics Globais (Rápido, vai direto pra L1 Cache)
-                if res.success {
-                    shared.successes.fetch_add(1, Ordering::Relaxed);
-                    if !is_warmup {
-                        let _ = local_hist.record(res.duration.as_micros() as u64);
-                    }
-                } else {
-                    shared.failures.fetch_add(1, Ordering::Relaxed);
-                }
-                shared
-                    .bytes_sent
-                    .fetch_add(res.bytes_sent, Ordering::Relaxed);
-                shared
-                    .bytes_received
-                    .fetch_add(res.bytes_received, Ordering::Relaxed);
-
-                if !is_warmup {
-                    if let Some(code) = res.status_code {
-                        *local_status.entry(code).or_insert(0) += 1;
-                    }
-                    if let Some(err) = &res.error {
-                        *local_errors.entry(err.clone()).or_insert(0) += 1;
-                    }
-                    if !res.assertion_success {
-                        local_assert_failures += 1;
-                    }
-                }
-                // 2. Atualiza HashMaps Locais
-                if let Some(code) = res.status_code {
-                    *local_status.entry(code).or_insert(0) += 1;
-                }
-                if let Some(err) = &res.error {
-                    *local_errors.entry(err.clone()).or_insert(0) += 1;
-                }
-                if !res.assertion_success {
-                    local_assert_failures += 1;
-                }
-
-                // 3. I/O Assíncrono de CSV (Se ativado)
-                if let Some(tx) = &csv_tx {
-                    let rec = CsvRecord {
-                        relative_ms: start_time.elapsed().as_millis().to_string(),
-                        status: res
-                            .status_code
-                            .map(|c| c.to_string())
-                            .unwrap_or_else(|| "N/A".to_string()),
-                        latency_ms: res.duration.as_millis().to_string(),
-                        error: res.error.unwrap_or_default(),
-                    };
-                    let _ = tx.send(rec).await;
-                }
+                state.record(&res, job.intended, &shared, warmup_end);
+                send_csv(&csv_tx, res, start_time).await;
             }
 
             // Devolve o balanço do worker quando o teste acabar
-            WorkerResult {
-                histogram: local_hist,
-                status_counts: local_status,
-                error_counts: local_errors,
-                assertion_failures: local_assert_failures,
-            }
+            state.finish()
         });
         handles.push(handle);
     }
 
-    // Cronômetro do RPS constante
-    if let Some(r) = rps {
-        let mut interval =
-            tokio::time::interval(std::time::Duration::from_secs_f64(1.0 / r as f64));
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Burst);
-        for _ in 0..count {
-            interval.tick().await;
-            let _ = job_tx.send(()).await;
+    // Agenda explícita (replay): cada job sai no seu instante, contado do início do teste
+    if let Some(schedule) = schedule {
+        for offset in schedule.iter() {
+            let intended = start_time + *offset;
+            tokio::select! {
+                biased;
+                _ = shutdown.stopped() => break,
+                _ = tokio::time::sleep_until(intended.into()) => {}
+            }
+            tokio::select! {
+                _ = shutdown.stopped() => break,
+                _ = job_tx.send(Job { intended: Some(intended) }) => {}
+            }
+        }
+    } else if let Some(profile) = profile {
+        // Perfil escalonado: despacha o que a integral do RPS manda até o fim do último estágio
+        let ramp_start = Instant::now();
+        let total = profile.total_duration();
+        let mut sent: u64 = 0;
+        'ramp: loop {
+            let elapsed = ramp_start.elapsed().min(total);
+            let due = profile.requests_due(elapsed) as u64;
+            while sent < due {
+                // Cada job leva o seu instante na agenda, não o desta volta: o que atrasou com
+                // os workers ocupados conta o tempo de fila
+                let offset = profile.due_at((sent + 1) as f64).unwrap_or(total);
+                let intended = Some(ramp_start + offset.min(elapsed));
+                tokio::select! {
+                    _ = shutdown.stopped() => break 'ramp,
+                    _ = job_tx.send(Job { intended }) => {}
+                }
+                sent += 1;
+            }
+            if elapsed >= total {
+                break;
+            }
+            tokio::select! {
+                _ = shutdown.stopped() => break,
+                _ = tokio::time::sleep(std::time::Duration::from_millis(1)) => {}
+            }
         }
     } else {
-        for _ in 0..count {
-            let _ = job_tx.send(()).await;
+        // Cronômetro do RPS constante (opcional)
+        let mut interval = rps.map(|r| {
+            let mut it = tokio::time::interval(std::time::Duration::from_secs_f64(1.0 / r as f64));
+            it.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Burst);
+            it
+        });
+
+        // Com --duration o critério de parada é o relógio, não a contagem
+        let deadline = duration.map(|d| tokio::time::Instant::from_std(start_time + d));
+        let mut sent: u32 = 0;
+
+        loop {
+            if deadline.is_none() && sent >= count {
+                break;
+            }
+
+            let dispatch = async {
+                // O tick devolve o instante AGENDADO, mesmo quando chega atrasado (Burst)
+                let intended = match interval.as_mut() {
+                    Some(it) => Some(it.tick().await.into_std()),
+                    None => None,
+                };
+                let _ = job_tx.send(Job { intended }).await;
+            };
+            let deadline_reached = async {
+                match deadline {
+                    Some(d) => tokio::time::sleep_until(d).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                biased;
+                _ = shutdown.stopped() => break,
+                _ = deadline_reached => break,
+                _ = dispatch => {}
+            }
+            sent = sent.saturating_add(1);
         }
     }
 
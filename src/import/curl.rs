@@ -0,0 +1,481 @@
+// src/import/curl.rs
+//
+// Um comando `curl` (o "Copy as cURL" do DevTools) → os campos de requisição do Args/FileConfig.
+// Flag que muda a requisição e não tem equivalente no Cannon não some calada: vira aviso.
+
+use super::ImportedConfig;
+use crate::args::parser::Args;
+
+// Flags que só mexem na saída do curl: não mudam o que vai para o servidor
+const OUTPUT_ONLY: [&str; 19] = [
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-L",
+    "--location",
+    "-f",
+    "--fail",
+    "--fail-with-body",
+    "--no-progress-meter",
+    "-#",
+    "--progress-bar",
+    "--http1.1",
+    "--http2",
+    "--no-buffer",
+];
+
+// Sem equivalente, mas levam um valor: o aviso mostra o valor e ele não vira URL por engano
+const UNSUPPORTED_WITH_VALUE: [&str; 28] = [
+    "-x",
+    "--proxy",
+    "-E",
+    "--cert",
+    "--key",
+    "--cacert",
+    "-F",
+    "--form",
+    "--resolve",
+    "--connect-to",
+    "-T",
+    "--upload-file",
+    "-w",
+    "--write-out",
+    "-o",
+    "--output",
+    "--retry",
+    "-r",
+    "--range",
+    "--limit-rate",
+    "-c",
+    "--cookie-jar",
+    "--tls-max",
+    "--ciphers",
+    "--interface",
+    "--max-redirs",
+    "-K",
+    "--config",
+];
+
+// Aceitam o valor colado na flag (-XPOST, -H'a: b')
+const SHORT_WITH_VALUE: [&str; 8] = ["-X", "-H", "-d", "-u", "-A", "-e", "-b", "-m"];
+
+#[derive(Debug, Default)]
+pub struct CurlRequest {
+    pub url: String,
+    pub method: String,
+    pub headers: Vec<String>,
+    pub body: Option<String>,
+    pub insecure: bool,
+    pub http2: bool,
+    pub timeout_ms: Option<u64>,
+    pub connect_timeout_ms: Option<u64>,
+    // O que ficou de fora (flags sem equivalente)
+    pub warnings: Vec<String>,
+}
+
+pub fn parse(command: &str) -> Result<CurlRequest, String> {
+    parse_tokens(tokenize(command)?)
+}
+
+// Já separado pelo shell (ex: cannon import curl -- curl -X POST ...)
+pub fn parse_tokens(tokens: Vec<String>) -> Result<CurlRequest, String> {
+    let mut tokens = tokens.into_iter().peekable();
+    if tokens.peek().map(String::as_str) == Some("curl") {
+        tokens.next();
+    }
+
+    let mut req = CurlRequest::default();
+    let mut method = None;
+    let mut data: Vec<String> = Vec::new();
+    let mut get = false;
+    let mut head = false;
+    let mut json = false;
+
+    while let Some(token) = tokens.next() {
+        // --flag=valor e -XPOST viram flag + valor
+        let (flag, inline) = match token.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ if token.len() > 2 && SHORT_WITH_VALUE.iter().any(|f| token.starts_with(f)) => {
+                (token[..2].to_string(), Some(token[2..].to_string()))
+            }
+            _ => (token.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| tokens.next())
+                .ok_or_else(|| format!("{} precisa de um valor", name))
+        };
+
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value(&flag)?.to_uppercase()),
+            "-H" | "--header" => {
+                let header = value(&flag)?;
+                // "Nome;" no curl = header vazio; "Nome:" = remover o header padrão
+                match header.split_once(':') {
+                    Some((_, v)) if v.trim().is_empty() => req
+                        .warnings
+                        .push(format!("header sem valor ignorado: '{}'", header)),
+                    Some(_) => req.headers.push(header),
+                    None => req.warnings.push(format!("header inválido: '{}'", header)),
+                }
+            }
+            "-d" | "--data" | "--data-ascii" | "--data-binary" => {
+                let v = value(&flag)?;
+                data.push(match v.strip_prefix('@') {
+                    Some(path) => std::fs::read_to_string(path).map_err(|e| {
+                        format!("{} @{}: não foi possível ler o arquivo: {}", flag, path, e)
+                    })?,
+                    None => v,
+                });
+            }
+            "--data-raw" => data.push(value(&flag)?),
+            "--data-urlencode" => {
+                let v = value(&flag)?;
+                data.push(match v.split_once('=') {
+                    Some((name, content)) => format!("{}={}", name, urlencode(content)),
+                    None => urlencode(&v),
+                });
+            }
+            "--json" => {
+                data.push(value(&flag)?);
+                json = true;
+            }
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => head = true,
+            "-u" | "--user" => {
+                let credentials = value(&flag)?;
+                req.headers.push(format!(
+                    "Authorization: Basic {}",
+                    base64(credentials.as_bytes())
+                ));
+            }
+            "--oauth2-bearer" => req
+                .headers
+                .push(format!("Authorization: Bearer {}", value(&flag)?)),
+            "-A" | "--user-agent" => req.headers.push(format!("User-Agent: {}", value(&flag)?)),
+            "-e" | "--referer" => req.headers.push(format!("Referer: {}", value(&flag)?)),
+            "-b" | "--cookie" => {
+                let cookie = value(&flag)?;
+                if cookie.contains('=') {
+                    req.headers.push(format!("Cookie: {}", cookie));
+                } else {
+                    req.warnings.push(format!(
+                        "{} {}: arquivo de cookies não é suportado",
+                        flag, cookie
+                    ));
+                }
+            }
+            // Sem descompressão no cliente, um Accept-Encoding faria o --expect e a contagem de
+            // bytes olharem para o corpo comprimido
+            "--compressed" => req.warnings.push(
+                "--compressed ignorado: o Cannon não descomprime respostas, então não pede compressão"
+                    .to_string(),
+            ),
+            "-k" | "--insecure" => req.insecure = true,
+            "--http2-prior-knowledge" => req.http2 = true,
+            "-m" | "--max-time" => req.timeout_ms = Some(seconds_to_ms(&flag, &value(&flag)?)?),
+            "--connect-timeout" => {
+                req.connect_timeout_ms = Some(seconds_to_ms(&flag, &value(&flag)?)?)
+            }
+            "--url" => req.url = value(&flag)?,
+            f if OUTPUT_ONLY.contains(&f) => {}
+            f if UNSUPPORTED_WITH_VALUE.contains(&f) => {
+                let v = value(f)?;
+                req.warnings
+                    .push(format!("{} {} não é suportado e foi ignorado", f, v));
+            }
+            f if f.starts_with('-') && f.len() > 1 => req
+                .warnings
+                .push(format!("{} não é suportado e foi ignorado", f)),
+            _ if req.url.is_empty() => req.url = token,
+            _ => req
+                .warnings
+                .push(format!("argumento extra '{}' ignorado", token)),
+        }
+    }
+
+    if req.url.is_empty() {
+        return Err("O comando curl não tem URL".to_string());
+    }
+    if !req.url.contains("://") {
+        req.url = format!("http://{}", req.url);
+    }
+
+    let has_header = |req: &CurlRequest, name: &str| {
+        req.headers
+            .iter()
+            .any(|h| h.to_lowercase().starts_with(&format!("{}:", name)))
+    };
+    if get && !data.is_empty() {
+        // -G manda os dados na query string
+        let sep = if req.url.contains('?') { '&' } else { '?' };
+        req.url = format!("{}{}{}", req.url, sep, data.join("&"));
+    } else if !data.is_empty() {
+        req.body = Some(data.join("&"));
+        if json {
+            if !has_header(&req, "content-type") {
+                req.headers
+                    .push("Content-Type: application/json".to_string());
+            }
+            if !has_header(&req, "accept") {
+                req.headers.push("Accept: application/json".to_string());
+            }
+        } else if !has_header(&req, "content-type") {
+            req.headers
+                .push("Content-Type: application/x-www-form-urlencoded".to_string());
+        }
+    }
+    req.method = match (method, head, &req.body) {
+        (Some(m), _, _) => m,
+        (None, true, _) => "HEAD".to_string(),
+        (None, false, Some(_)) => "POST".to_string(),
+        (None, false, None) => "GET".to_string(),
+    };
+
+    Ok(req)
+}
+
+impl CurlRequest {
+    // --from-curl: a requisição do curl entra no lugar de -u / -X / -b; os -H somam
+    pub fn apply(self, args: &mut Args) {
+        args.url = Some(self.url);
+        args.method = self.method;
+        if self.body.is_some() {
+            args.body = self.body;
+        }
+        let mut headers = self.headers;
+        headers.append(&mut args.headers);
+        args.headers = headers;
+        args.insecure |= self.insecure;
+        args.http2 |= self.http2;
+        if let Some(t) = self.timeout_ms {
+            args.timeout = t;
+        }
+        if let Some(t) = self.connect_timeout_ms {
+            args.connect_timeout = t;
+        }
+    }
+
+    pub fn into_config(self) -> ImportedConfig {
+        ImportedConfig {
+            url: Some(self.url),
+            method: Some(self.method),
+            headers: (!self.headers.is_empty()).then_some(self.headers),
+            body: self.body,
+            insecure: self.insecure.then_some(true),
+            http2: self.http2.then_some(true),
+            timeout: self.timeout_ms,
+            connect_timeout: self.connect_timeout_ms,
+            ..Default::default()
+        }
+    }
+}
+
+fn seconds_to_ms(flag: &str, value: &str) -> Result<u64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|s| *s > 0.0)
+        .map(|s| (s * 1000.0) as u64)
+        .ok_or_else(|| format!("{} {}: use segundos (ex: 2.5)", flag, value))
+}
+
+// Separa como o shell: aspas simples e duplas, $'...' (o DevTools usa para corpos com
+// caracteres especiais) e a quebra de linha com barra invertida
+fn tokenize(command: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Aspas simples sem fechar".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some('x') => {
+                                let hex: String = chars.by_ref().take(2).collect();
+                                let byte = u8::from_str_radix(&hex, 16)
+                                    .map_err(|_| format!("Escape inválido \\x{}", hex))?;
+                                current.push(byte as char);
+                            }
+                            Some('u') => {
+                                let hex: String = chars.by_ref().take(4).collect();
+                                let c = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or_else(|| format!("Escape inválido \\u{}", hex))?;
+                                current.push(c);
+                            }
+                            Some(c) => current.push(c),
+                            None => return Err("Aspas $'...' sem fechar".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Aspas $'...' sem fechar".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Aspas duplas sem fechar".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Aspas duplas sem fechar".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // Continuação de linha
+                Some('\n') | Some('\r') => {}
+                Some(c) => {
+                    in_token = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn urlencode(input: &str) -> String {
+    input
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devtools_command() {
+        let req = parse(
+            r#"curl 'https://api.example.com/orders?x=1' \
+  -H 'accept: application/json' \
+  -H 'content-type: application/json' \
+  -b 'session=abc' \
+  --data-raw $'{"note":"it\'s ok"}' \
+  --compressed -k"#,
+        )
+        .unwrap();
+        assert_eq!(req.url, "https://api.example.com/orders?x=1");
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.body.as_deref(), Some(r#"{"note":"it's ok"}"#));
+        assert_eq!(
+            req.headers,
+            vec![
+                "accept: application/json",
+                "content-type: application/json",
+                "Cookie: session=abc",
+            ]
+        );
+        assert!(req.insecure);
+        assert_eq!(req.warnings.len(), 1);
+        assert!(req.warnings[0].starts_with("--compressed ignorado"));
+    }
+
+    #[test]
+    fn test_flags_and_warnings() {
+        let req = parse(
+            "curl -X put -u admin:s3cret -d a=1 --data b=2 -m 2.5 --proxy http://p:3128 --tlsv1.2 localhost:8080/x",
+        )
+        .unwrap();
+        assert_eq!(req.url, "http://localhost:8080/x");
+        assert_eq!(req.method, "PUT");
+        assert_eq!(req.body.as_deref(), Some("a=1&b=2"));
+        assert_eq!(req.headers[0], "Authorization: Basic YWRtaW46czNjcmV0");
+        assert_eq!(
+            req.headers[1],
+            "Content-Type: application/x-www-form-urlencoded"
+        );
+        assert_eq!(req.timeout_ms, Some(2500));
+        assert_eq!(req.warnings.len(), 2);
+        assert!(req.warnings[0].contains("--proxy http://p:3128"));
+
+        let get =
+            parse("curl -G -d q=rust --data-urlencode 'tag=a b' --url=http://h/search?lang=pt")
+                .unwrap();
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "http://h/search?lang=pt&q=rust&tag=a%20b");
+        assert_eq!(parse("curl -XDELETE http://h/1").unwrap().method, "DELETE");
+        assert!(get.body.is_none());
+
+        assert!(parse("curl -H 'x: y'").is_err());
+        assert!(parse("curl 'http://h").is_err());
+        assert_eq!(base64(b"ab"), "YWI=");
+    }
+}
@@ -0,0 +1,348 @@
+// src/import/har.rs
+//
+// HAR (HTTP Archive, o "Save all as HAR" do DevTools) → passos de um `scenario:`.
+// A origem mais frequente vira o `url:` e os passos dela ficam relativos; o intervalo ocioso
+// entre o fim de uma resposta e a requisição seguinte vira o `think_time` do passo.
+
+use crate::args::parser::StepConfig;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// O cliente recalcula estes headers; os que começam com ':' são pseudo-headers do HTTP/2
+const SKIPPED_HEADERS: [&str; 5] = [
+    "host",
+    "content-length",
+    "connection",
+    "accept-encoding",
+    "transfer-encoding",
+];
+
+const STATIC_EXTENSIONS: [&str; 16] = [
+    "js", "mjs", "css", "map", "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "avif", "woff",
+    "woff2", "ttf", "otf",
+];
+
+#[derive(Deserialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Deserialize)]
+struct HarLog {
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    started_date_time: String,
+    // Duração total da entrada (ms)
+    #[serde(default)]
+    time: f64,
+    request: HarRequest,
+    #[serde(default)]
+    response: Option<HarResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Vec<HarHeader>,
+    #[serde(default)]
+    post_data: Option<HarPostData>,
+}
+
+#[derive(Deserialize)]
+struct HarHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HarResponse {
+    #[serde(default)]
+    content: Option<HarContent>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    #[serde(default)]
+    mime_type: String,
+}
+
+pub struct HarFilter {
+    // Vazio = todos os domínios
+    pub domains: Vec<String>,
+    pub exclude_domains: Vec<String>,
+    pub include_static: bool,
+    // Pausas menores são o navegador disparando em paralelo, não o usuário pensando
+    pub min_think_time: Duration,
+    pub max_think_time: Duration,
+}
+
+pub struct HarImport {
+    pub base_url: Option<String>,
+    pub steps: Vec<StepConfig>,
+    pub skipped_static: usize,
+    pub skipped_domain: usize,
+}
+
+// Uma entrada que passou pelos filtros
+struct Kept {
+    origin: String,
+    path: String,
+    start: f64,
+    end: f64,
+    entry: HarEntry,
+}
+
+pub fn convert(text: &str, filter: &HarFilter) -> Result<HarImport, String> {
+    let har: Har = serde_json::from_str(text).map_err(|e| format!("HAR inválido: {}", e))?;
+    let mut kept = Vec::new();
+    let (mut skipped_static, mut skipped_domain) = (0, 0);
+
+    for (i, entry) in har.log.entries.into_iter().enumerate() {
+        // data:, blob:, chrome-extension:... não são requisições que dê para repetir
+        let Some((origin, host, path)) = split_url(&entry.request.url) else {
+            skipped_domain += 1;
+            continue;
+        };
+        if !filter.accepts(&host) {
+            skipped_domain += 1;
+            continue;
+        }
+        if !filter.include_static && is_static(path, &entry) {
+            skipped_static += 1;
+            continue;
+        }
+        let start = crate::utils::parse_rfc3339(&entry.started_date_time).ok_or_else(|| {
+            format!(
+                "Entrada {}: startedDateTime inválido '{}'",
+                i + 1,
+                entry.started_date_time
+            )
+        })?;
+        kept.push(Kept {
+            origin: origin.to_string(),
+            path: path.to_string(),
+            start,
+            end: start + entry.time.max(0.0) / 1000.0,
+            entry,
+        });
+    }
+    if kept.is_empty() {
+        return Err("Nenhuma requisição sobrou depois dos filtros".to_string());
+    }
+    kept.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    // A origem com mais requisições vira o --url
+    let mut origins: HashMap<&str, usize> = HashMap::new();
+    for k in &kept {
+        *origins.entry(k.origin.as_str()).or_insert(0) += 1;
+    }
+    let base = origins
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(origin, _)| origin.to_string())
+        .unwrap_or_default();
+
+    let mut steps = Vec::with_capacity(kept.len());
+    let mut busy_until: Option<f64> = None;
+    for k in &kept {
+        let think_time = busy_until
+            .map(|end| Duration::from_secs_f64((k.start - end).max(0.0)))
+            .filter(|gap| *gap >= filter.min_think_time)
+            .map(|gap| format!("{}ms", gap.min(filter.max_think_time).as_millis()));
+        busy_until = Some(busy_until.map_or(k.end, |end| end.max(k.end)));
+        steps.push(step(k, &base, think_time));
+    }
+
+    Ok(HarImport {
+        base_url: Some(base),
+        steps,
+        skipped_static,
+        skipped_domain,
+    })
+}
+
+impl HarFilter {
+    fn accepts(&self, host: &str) -> bool {
+        let matches = |domain: &String| {
+            let domain = domain.trim().trim_start_matches('.').to_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        };
+        (self.domains.is_empty() || self.domains.iter().any(matches))
+            && !self.exclude_domains.iter().any(matches)
+    }
+}
+
+fn step(kept: &Kept, base: &str, think_time: Option<String>) -> StepConfig {
+    let request = &kept.entry.request;
+    let mut headers: Vec<String> = request
+        .headers
+        .iter()
+        .filter(|h| {
+            !h.name.starts_with(':') && !SKIPPED_HEADERS.contains(&h.name.to_lowercase().as_str())
+        })
+        .map(|h| format!("{}: {}", h.name, h.value))
+        .collect();
+
+    let post = request.post_data.as_ref();
+    let body = post.and_then(|p| p.text.clone()).filter(|t| !t.is_empty());
+    // Alguns exportadores só guardam o tipo do corpo no postData
+    if let Some(mime) = post.map(|p| p.mime_type.as_str()).filter(|m| !m.is_empty()) {
+        let has_type = headers
+            .iter()
+            .any(|h| h.to_lowercase().starts_with("content-type:"));
+        if body.is_some() && !has_type {
+            headers.push(format!("Content-Type: {}", mime));
+        }
+    }
+
+    StepConfig {
+        name: None,
+        method: request.method.to_uppercase(),
+        url: if kept.origin == base {
+            kept.path.clone()
+        } else {
+            format!("{}{}", kept.origin, kept.path)
+        },
+        headers,
+        body,
+        expect: None,
+        extract: Default::default(),
+        think_time,
+    }
+}
+
+// "https://api.x.com:8443/a?b=1" → ("https://api.x.com:8443", "api.x.com", "/a?b=1")
+fn split_url(url: &str) -> Option<(&str, String, &str)> {
+    let scheme_end = url.find("://")?;
+    if !matches!(&url[..scheme_end], "http" | "https") {
+        return None;
+    }
+    let rest = &url[scheme_end + 3..];
+    let origin_len = scheme_end + 3 + rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (origin, path) = url.split_at(origin_len);
+    let authority = &origin[scheme_end + 3..];
+    let host = authority
+        .rsplit('@')
+        .next()
+        .unwrap_or(authority)
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    // O fragmento nunca vai para o servidor
+    let path = path.split('#').next().unwrap_or_default();
+    Some((origin, host, if path.is_empty() { "/" } else { path }))
+}
+
+fn is_static(path: &str, entry: &HarEntry) -> bool {
+    let file = path.split('?').next().unwrap_or_default();
+    let by_extension = file
+        .rsplit_once('.')
+        .filter(|(_, ext)| !ext.contains('/'))
+        .is_some_and(|(_, ext)| STATIC_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    let mime = entry
+        .response
+        .as_ref()
+        .and_then(|r| r.content.as_ref())
+        .map(|c| c.mime_type.to_lowercase())
+        .unwrap_or_default();
+    let by_mime = [
+        "image/",
+        "font/",
+        "text/css",
+        "javascript",
+        "video/",
+        "audio/",
+    ]
+    .iter()
+    .any(|m| mime.contains(m));
+    by_extension || by_mime
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAR: &str = r#"{"log":{"entries":[
+      {"startedDateTime":"2024-05-01T12:00:00.000Z","time":120,
+       "request":{"method":"GET","url":"https://shop.example.com/","headers":[{"name":":authority","value":"shop.example.com"},{"name":"Accept","value":"text/html"}]},
+       "response":{"content":{"mimeType":"text/html"}}},
+      {"startedDateTime":"2024-05-01T12:00:00.130Z","time":20,
+       "request":{"method":"GET","url":"https://shop.example.com/app.js?v=3","headers":[]},
+       "response":{"content":{"mimeType":"application/javascript"}}},
+      {"startedDateTime":"2024-05-01T12:00:00.140Z","time":30,
+       "request":{"method":"GET","url":"https://www.google-analytics.com/collect","headers":[]}},
+      {"startedDateTime":"2024-05-01T12:00:02.620Z","time":80,
+       "request":{"method":"post","url":"https://api.shop.example.com/cart#top","headers":[{"name":"Content-Length","value":"9"}],
+                  "postData":{"mimeType":"application/json","text":"{\"sku\":1}"}}},
+      {"startedDateTime":"2024-05-01T12:01:00.000Z","time":50,
+       "request":{"method":"GET","url":"https://shop.example.com/checkout","headers":[]}}
+    ]}}"#;
+
+    fn filter() -> HarFilter {
+        HarFilter {
+            domains: vec!["shop.example.com".to_string()],
+            exclude_domains: Vec::new(),
+            include_static: false,
+            min_think_time: Duration::from_millis(100),
+            max_think_time: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn test_har_becomes_a_scenario() {
+        let import = convert(HAR, &filter()).unwrap();
+        assert_eq!(import.base_url.as_deref(), Some("https://shop.example.com"));
+        assert_eq!(import.skipped_static, 1);
+        assert_eq!(import.skipped_domain, 1);
+
+        let urls: Vec<&str> = import.steps.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["/", "https://api.shop.example.com/cart", "/checkout"]
+        );
+        assert_eq!(import.steps[0].headers, vec!["Accept: text/html"]);
+
+        let cart = &import.steps[1];
+        assert_eq!(cart.method, "POST");
+        assert_eq!(cart.body.as_deref(), Some(r#"{"sku":1}"#));
+        assert_eq!(cart.headers, vec!["Content-Type: application/json"]);
+        // 2.62s - (0s + 120ms) de pausa; a seguinte passa do teto
+        assert_eq!(cart.think_time.as_deref(), Some("2500ms"));
+        assert_eq!(import.steps[2].think_time.as_deref(), Some("10000ms"));
+        assert_eq!(import.steps[0].think_time, None);
+    }
+
+    #[test]
+    fn test_har_filters() {
+        let mut all = filter();
+        all.domains.clear();
+        all.include_static = true;
+        assert_eq!(convert(HAR, &all).unwrap().steps.len(), 5);
+
+        all.exclude_domains = vec!["example.com".to_string()];
+        assert_eq!(convert(HAR, &all).unwrap().steps.len(), 1);
+
+        all.exclude_domains.push("google-analytics.com".to_string());
+        assert!(convert(HAR, &all).is_err());
+        assert!(convert("{}", &filter()).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+// src/import/mod.rs
+//
+// `cannon import ...`: converte tráfego gravado em outras ferramentas num YAML que o -f entende.
+// Cada formato só monta o `ImportedConfig`; a escrita (arquivo ou saída padrão) é comum.
+
+pub mod curl;
+pub mod har;
+pub mod openapi;
+
+use crate::args::parser::{ImportSource, RequestConfig, StepConfig};
+use colored::Colorize;
+use serde::Serialize;
+
+// O subconjunto do FileConfig que um import consegue preencher
+#[derive(Serialize, Debug, Default)]
+pub struct ImportedConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insecure: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http2: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<Vec<StepConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests: Option<Vec<RequestConfig>>,
+}
+
+impl ImportedConfig {
+    // YAML pronto para o -f, com um comentário dizendo de onde veio
+    pub fn to_yaml(&self, origin: &str) -> Result<String, String> {
+        let body = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+        Ok(format!("# Gerado por `cannon import {}`\n{}", origin, body))
+    }
+}
+
+pub fn run(source: &ImportSource) -> Result<(), Box<dyn std::error::Error>> {
+    match source {
+        ImportSource::Har {
+            file,
+            output,
+            domains,
+            exclude_domains,
+            include_static,
+            min_think_time,
+            max_think_time,
+        } => {
+            let filter = har::HarFilter {
+                domains: domains.clone(),
+                exclude_domains: exclude_domains.clone(),
+                include_static: *include_static,
+                min_think_time: crate::utils::parse_duration(min_think_time)
+                    .unwrap_or_else(|e| exit_with("--min-think-time", e)),
+                max_think_time: crate::utils::parse_duration(max_think_time)
+                    .unwrap_or_else(|e| exit_with("--max-think-time", e)),
+            };
+            let text = std::fs::read_to_string(file)
+                .unwrap_or_else(|e| exit_with("import har", format!("'{}': {}", file, e)));
+            let import =
+                har::convert(&text, &filter).unwrap_or_else(|e| exit_with("import har", e));
+
+            let imported = import.steps.len();
+            let config = ImportedConfig {
+                url: import.base_url,
+                scenario: Some(import.steps),
+                ..Default::default()
+            };
+            write(&config, &format!("har {}", file), output.as_deref())?;
+            eprintln!(
+                "{} {} passo(s) importado(s); ignorada(s): {} estática(s), {} de outro domínio",
+                "✅".green(),
+                imported.to_string().cyan(),
+                import.skipped_static,
+                import.skipped_domain
+            );
+        }
+        ImportSource::Openapi {
+            file,
+            output,
+            tags,
+            include_deprecated,
+            read_weight,
+        } => {
+            let filter = openapi::OpenApiFilter {
+                tags: tags.clone(),
+                include_deprecated: *include_deprecated,
+                read_weight: *read_weight,
+            };
+            let text = std::fs::read_to_string(file)
+                .unwrap_or_else(|e| exit_with("import openapi", format!("'{}': {}", file, e)));
+            let import =
+                openapi::convert(&text, &filter).unwrap_or_else(|e| exit_with("import openapi", e));
+            for w in &import.warnings {
+                eprintln!("{} {}", "⚠️ openapi:".yellow().bold(), w);
+            }
+
+            let imported = import.requests.len();
+            let config = ImportedConfig {
+                url: import.base_url,
+                requests: Some(import.requests),
+                ..Default::default()
+            };
+            write(&config, &format!("openapi {}", file), output.as_deref())?;
+            eprintln!(
+                "{} {} operação(ões) importada(s); ignorada(s): {} deprecated, {} fora das tags",
+                "✅".green(),
+                imported.to_string().cyan(),
+                import.skipped_deprecated,
+                import.skipped_tag
+            );
+        }
+        ImportSource::Curl { command, output } => {
+            let req = parse_curl(command).unwrap_or_else(|e| exit_with("import curl", e));
+            warn_curl(&req.warnings);
+            write(&req.into_config(), "curl", output.as_deref())?;
+        }
+    }
+    Ok(())
+}
+
+// Um argumento = o comando inteiro entre aspas; vários = já separados pelo shell
+pub fn parse_curl(command: &[String]) -> Result<curl::CurlRequest, String> {
+    match command {
+        [single] => curl::parse(single),
+        tokens => curl::parse_tokens(tokens.to_vec()),
+    }
+}
+
+pub fn warn_curl(warnings: &[String]) {
+    for w in warnings {
+        eprintln!("{} {}", "⚠️ curl:".yellow().bold(), w);
+    }
+}
+
+fn write(
+    config: &ImportedConfig,
+    origin: &str,
+    output: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let yaml = config.to_yaml(origin)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, yaml)?;
+            eprintln!("💾 Configuração salva em {}", path.yellow());
+        }
+        None => print!("{}", yaml),
+    }
+    Ok(())
+}
+
+fn exit_with(flag: &str, message: impl std::fmt::Display) -> ! {
+    eprintln!(
+        "{} {}",
+        format!("❌ Erro no {}:", flag).red().bold(),
+        message
+    );
+    std::process::exit(1);
+}
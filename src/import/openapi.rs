@@ -0,0 +1,565 @@
+// src/import/openapi.rs
+//
+// Spec OpenAPI 3 (ou Swagger 2), em YAML ou JSON → um mix `requests:` com uma requisição por
+// operação. Parâmetros e corpos viram tags do PayloadTemplate conforme o tipo do schema:
+// string → {{username}}, format uuid → {{uuid}}, format email → {{email}}, inteiro/número → {{number}}.
+
+use crate::args::parser::RequestConfig;
+use serde_json::Value;
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+// Schemas recursivos (árvore, lista ligada) param aqui com null
+const MAX_DEPTH: usize = 8;
+
+pub struct OpenApiFilter {
+    // Vazio = todas as tags
+    pub tags: Vec<String>,
+    pub include_deprecated: bool,
+    // Peso de GET/HEAD no mix (as escritas pesam 1); x-cannon-weight na operação tem prioridade
+    pub read_weight: u32,
+}
+
+pub struct OpenApiImport {
+    pub base_url: Option<String>,
+    pub requests: Vec<RequestConfig>,
+    pub skipped_deprecated: usize,
+    pub skipped_tag: usize,
+    pub warnings: Vec<String>,
+}
+
+pub fn convert(text: &str, filter: &OpenApiFilter) -> Result<OpenApiImport, String> {
+    // O serde_yaml também lê JSON
+    let spec: Value = serde_yaml::from_str(text).map_err(|e| format!("Spec inválida: {}", e))?;
+    let swagger2 = spec.get("swagger").is_some();
+    if !swagger2 && spec.get("openapi").is_none() {
+        return Err("Não parece uma spec OpenAPI: falta a chave openapi (ou swagger)".to_string());
+    }
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or("A spec não tem paths")?;
+
+    let (base_url, prefix) = server(&spec, swagger2);
+    let mut import = OpenApiImport {
+        base_url,
+        requests: Vec::new(),
+        skipped_deprecated: 0,
+        skipped_tag: 0,
+        warnings: Vec::new(),
+    };
+    let mut secured = false;
+
+    for (path, item) in paths {
+        let item = resolve(&spec, item);
+        for method in METHODS {
+            let Some(op) = item.get(method) else {
+                continue;
+            };
+            if op.get("deprecated").and_then(Value::as_bool) == Some(true)
+                && !filter.include_deprecated
+            {
+                import.skipped_deprecated += 1;
+                continue;
+            }
+            if !filter.tags.is_empty() {
+                let tags = op.get("tags").and_then(Value::as_array);
+                let tagged = tags.is_some_and(|tags| {
+                    tags.iter()
+                        .filter_map(Value::as_str)
+                        .any(|t| filter.tags.iter().any(|f| f.eq_ignore_ascii_case(t)))
+                });
+                if !tagged {
+                    import.skipped_tag += 1;
+                    continue;
+                }
+            }
+            let security = op.get("security").or_else(|| spec.get("security"));
+            secured |= security.and_then(Value::as_array).is_some_and(|s| {
+                s.iter()
+                    .any(|req| req.as_object().is_some_and(|o| !o.is_empty()))
+            });
+
+            let (request, warning) =
+                operation(&spec, swagger2, &prefix, path, method, item, op, filter);
+            import.warnings.extend(warning);
+            import.requests.push(request);
+        }
+    }
+
+    if import.requests.is_empty() {
+        return Err("Nenhuma operação sobrou depois dos filtros".to_string());
+    }
+    if import.base_url.is_none() {
+        import
+            .warnings
+            .push("a spec não tem um servidor absoluto: informe o alvo com -u".to_string());
+    }
+    if secured {
+        import.warnings.push(
+            "a API exige autenticação: adicione as credenciais com -H (ex: 'Authorization: Bearer ...')"
+                .to_string(),
+        );
+    }
+    Ok(import)
+}
+
+// URL absoluta do servidor (vira o `url:`) ou, se relativa, o prefixo dos caminhos
+fn server(spec: &Value, swagger2: bool) -> (Option<String>, String) {
+    let url = if swagger2 {
+        spec.get("host").and_then(Value::as_str).map(|host| {
+            let scheme = spec
+                .pointer("/schemes/0")
+                .and_then(Value::as_str)
+                .unwrap_or("https");
+            let base_path = spec.get("basePath").and_then(Value::as_str).unwrap_or("");
+            format!("{}://{}{}", scheme, host, base_path)
+        })
+    } else {
+        spec.pointer("/servers/0").map(|server| {
+            let mut url = server
+                .get("url")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string();
+            // {variável} do servidor → o default dela
+            for (name, var) in server
+                .get("variables")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(default) = var.get("default").and_then(Value::as_str) {
+                    url = url.replace(&format!("{{{}}}", name), default);
+                }
+            }
+            url
+        })
+    };
+    match url {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            (Some(url.trim_end_matches('/').to_string()), String::new())
+        }
+        Some(relative) => (None, relative.trim_end_matches('/').to_string()),
+        None => (None, String::new()),
+    }
+}
+
+// A requisição e, se algo ficou de fora, o aviso
+#[allow(clippy::too_many_arguments)]
+fn operation(
+    spec: &Value,
+    swagger2: bool,
+    prefix: &str,
+    path: &str,
+    method: &str,
+    item: &Value,
+    op: &Value,
+    filter: &OpenApiFilter,
+) -> (RequestConfig, Option<String>) {
+    let label = format!("{} {}", method.to_uppercase(), path);
+
+    // Parâmetros do caminho valem para todas as operações; os da operação têm prioridade
+    let mut params: Vec<&Value> = Vec::new();
+    for p in [op, item]
+        .iter()
+        .filter_map(|v| v.get("parameters").and_then(Value::as_array))
+        .flatten()
+        .map(|p| resolve(spec, p))
+    {
+        let key = |p: &Value| (p.get("name").cloned(), p.get("in").cloned());
+        if !params.iter().any(|q| key(q) == key(p)) {
+            params.push(p);
+        }
+    }
+    let param_in = |location: &'static str| {
+        params
+            .iter()
+            .copied()
+            .filter(move |p| p.get("in").and_then(Value::as_str) == Some(location))
+    };
+    // Swagger 2 descreve o tipo no próprio parâmetro; OpenAPI 3, no `schema`
+    let schema_of = |p: &Value| p.get("schema").cloned().unwrap_or_else(|| p.clone());
+
+    // {id} → a tag do tipo declarado (sem declaração, texto)
+    let mut url = String::from(prefix);
+    let mut rest = path;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|c| open + c) else {
+            break;
+        };
+        let name = &rest[open + 1..close];
+        url.push_str(&rest[..open]);
+        url.push_str(
+            &param_in("path")
+                .find(|p| p.get("name").and_then(Value::as_str) == Some(name))
+                .map(|p| placeholder(spec, &schema_of(p)))
+                .unwrap_or_else(|| "{{username}}".to_string()),
+        );
+        rest = &rest[close + 1..];
+    }
+    url.push_str(rest);
+
+    // Só os obrigatórios: a cobertura é de endpoints, não de combinações de filtros
+    let required = |p: &&Value| p.get("required").and_then(Value::as_bool) == Some(true);
+    let query: Vec<String> = param_in("query")
+        .filter(required)
+        .filter_map(|p| {
+            let name = p.get("name")?.as_str()?;
+            Some(format!("{}={}", name, placeholder(spec, &schema_of(p))))
+        })
+        .collect();
+    if !query.is_empty() {
+        url = format!("{}?{}", url, query.join("&"));
+    }
+    let mut headers: Vec<String> = param_in("header")
+        .filter(required)
+        .filter_map(|p| {
+            let name = p.get("name")?.as_str()?;
+            Some(format!("{}: {}", name, placeholder(spec, &schema_of(p))))
+        })
+        .collect();
+
+    let mut warning = None;
+    let body = if swagger2 {
+        if let Some(p) = param_in("body").next() {
+            headers.push("Content-Type: application/json".to_string());
+            Some(json(spec, &schema_of(p), 0))
+        } else {
+            let form: Vec<String> = param_in("formData")
+                .filter_map(|p| {
+                    let name = p.get("name")?.as_str()?;
+                    Some(format!("{}={}", name, placeholder(spec, p)))
+                })
+                .collect();
+            (!form.is_empty()).then(|| {
+                headers.push("Content-Type: application/x-www-form-urlencoded".to_string());
+                form.join("&")
+            })
+        }
+    } else {
+        match op.get("requestBody").map(|b| resolve(spec, b)) {
+            Some(body) => {
+                let content = body.get("content").and_then(Value::as_object);
+                let json_type = content.and_then(|c| c.keys().find(|k| k.contains("json")));
+                let form_type = content.and_then(|c| {
+                    c.keys()
+                        .find(|k| k.as_str() == "application/x-www-form-urlencoded")
+                });
+                match (json_type, form_type) {
+                    (Some(mime), _) => {
+                        headers.push(format!("Content-Type: {}", mime));
+                        Some(json(spec, &content.unwrap()[mime]["schema"], 0))
+                    }
+                    (None, Some(mime)) => {
+                        headers.push(format!("Content-Type: {}", mime));
+                        Some(form(spec, &content.unwrap()[mime]["schema"]))
+                    }
+                    (None, None) => {
+                        let types: Vec<&String> =
+                            content.into_iter().flatten().map(|(k, _)| k).collect();
+                        warning = Some(format!(
+                            "{}: corpo {:?} não suportado (só JSON e formulário); a requisição vai sem corpo",
+                            label, types
+                        ));
+                        None
+                    }
+                }
+            }
+            None => None,
+        }
+    };
+
+    let weight = op
+        .get("x-cannon-weight")
+        .and_then(Value::as_u64)
+        .map(|w| w as u32)
+        .unwrap_or(match method {
+            "get" | "head" => filter.read_weight,
+            _ => 1,
+        });
+
+    let request = RequestConfig {
+        name: Some(
+            op.get("operationId")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or(label),
+        ),
+        method: method.to_uppercase(),
+        url,
+        headers,
+        body,
+        expect: None,
+        weight,
+    };
+    (request, warning)
+}
+
+// {"$ref": "#/components/schemas/User"} → o alvo (refs externas ficam como estão)
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    // Limite contra ref apontando para si mesma
+    for _ in 0..MAX_DEPTH {
+        match current
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| spec.pointer(pointer))
+        {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+// O tipo do schema, aceitando o `type: [string, null]` do OpenAPI 3.1
+fn type_of(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(t) => Some(t),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null"),
+        _ => None,
+    }
+}
+
+// Valor de parâmetro (caminho, query, header, formulário)
+fn placeholder(spec: &Value, schema: &Value) -> String {
+    let schema = resolve(spec, schema);
+    if let Some(first) = schema.pointer("/enum/0") {
+        return match first {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+    }
+    match (
+        type_of(schema),
+        schema.get("format").and_then(Value::as_str),
+    ) {
+        (_, Some("uuid")) => "{{uuid}}".to_string(),
+        (_, Some("email")) => "{{email}}".to_string(),
+        (Some("integer" | "number"), _) => "{{number}}".to_string(),
+        (Some("boolean"), _) => "true".to_string(),
+        _ => "{{username}}".to_string(),
+    }
+}
+
+// Corpo JSON com as tags no lugar dos valores (os números ficam sem aspas)
+fn json(spec: &Value, schema: &Value, depth: usize) -> String {
+    let schema = resolve(spec, schema);
+    if depth > MAX_DEPTH {
+        return "null".to_string();
+    }
+    if let Some(first) = schema.pointer("/enum/0") {
+        return first.to_string();
+    }
+    if let Some(first) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|k| schema.get(*k).and_then(|v| v.get(0)))
+    {
+        return json(spec, first, depth + 1);
+    }
+    let all_of = schema.get("allOf").and_then(Value::as_array);
+    if schema.get("properties").is_some() || all_of.is_some() || type_of(schema) == Some("object") {
+        let fields: Vec<String> = properties(spec, schema)
+            .into_iter()
+            .map(|(name, prop)| {
+                format!(
+                    "{}: {}",
+                    Value::String(name.to_string()),
+                    json(spec, prop, depth + 1)
+                )
+            })
+            .collect();
+        return format!("{{{}}}", fields.join(", "));
+    }
+
+    match (
+        type_of(schema),
+        schema.get("format").and_then(Value::as_str),
+    ) {
+        (Some("array"), _) => match schema.get("items") {
+            Some(items) => format!("[{}]", json(spec, items, depth + 1)),
+            None => "[]".to_string(),
+        },
+        (Some("integer" | "number"), _) => "{{number}}".to_string(),
+        (Some("boolean"), _) => "true".to_string(),
+        (Some("string"), Some("date-time")) => "\"2024-01-01T00:00:00Z\"".to_string(),
+        (Some("string"), Some("date")) => "\"2024-01-01\"".to_string(),
+        (Some("string"), Some("uuid")) => "\"{{uuid}}\"".to_string(),
+        (Some("string"), Some("email")) => "\"{{email}}\"".to_string(),
+        (Some("string"), _) => "\"{{username}}\"".to_string(),
+        _ => "null".to_string(),
+    }
+}
+
+// Propriedades do objeto (com as do allOf), sem as readOnly que o servidor preenche
+fn properties<'a>(spec: &'a Value, schema: &'a Value) -> Vec<(&'a str, &'a Value)> {
+    let mut out: Vec<(&str, &Value)> = Vec::new();
+    for part in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|s| resolve(spec, s))
+        .chain([schema])
+    {
+        for (name, prop) in part
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let readonly = resolve(spec, prop).get("readOnly").and_then(Value::as_bool);
+            if readonly != Some(true) && !out.iter().any(|(n, _)| *n == name) {
+                out.push((name, prop));
+            }
+        }
+    }
+    out
+}
+
+fn form(spec: &Value, schema: &Value) -> String {
+    properties(spec, resolve(spec, schema))
+        .into_iter()
+        .map(|(name, prop)| format!("{}={}", name, placeholder(spec, prop)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.3
+servers:
+  - url: https://{env}.example.com/v1
+    variables:
+      env: { default: staging }
+security:
+  - bearerAuth: []
+paths:
+  /users/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: integer } }
+    get:
+      operationId: getUser
+      tags: [users]
+      responses:
+        200: { description: ok }
+    delete:
+      deprecated: true
+      responses:
+        204: { description: gone }
+  /users:
+    post:
+      tags: [users]
+      x-cannon-weight: 5
+      parameters:
+        - { name: X-Request-Id, in: header, required: true, schema: { type: string, format: uuid } }
+        - { name: dry_run, in: query, schema: { type: boolean } }
+      requestBody:
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/NewUser' }
+      responses:
+        201: { description: created }
+  /orders:
+    get:
+      tags: [orders]
+      parameters:
+        - { name: status, in: query, required: true, schema: { type: string, enum: [open, closed] } }
+      responses:
+        200: { description: ok }
+components:
+  schemas:
+    NewUser:
+      allOf:
+        - $ref: '#/components/schemas/Base'
+        - type: object
+          properties:
+            email: { type: string, format: email }
+            age: { type: integer }
+            roles: { type: array, items: { type: string } }
+    Base:
+      type: object
+      properties:
+        id: { type: string, format: uuid, readOnly: true }
+        name: { type: string }
+"#;
+
+    fn filter() -> OpenApiFilter {
+        OpenApiFilter {
+            tags: Vec::new(),
+            include_deprecated: false,
+            read_weight: 3,
+        }
+    }
+
+    #[test]
+    fn test_operations_become_a_weighted_mix() {
+        let import = convert(SPEC, &filter()).unwrap();
+        assert_eq!(
+            import.base_url.as_deref(),
+            Some("https://staging.example.com/v1")
+        );
+        assert_eq!(import.skipped_deprecated, 1);
+        // Avisa da autenticação exigida pela spec
+        assert_eq!(import.warnings.len(), 1);
+
+        let by_name = |name: &str| {
+            import
+                .requests
+                .iter()
+                .find(|r| r.name.as_deref() == Some(name))
+                .unwrap()
+        };
+        let get = by_name("getUser");
+        assert_eq!(get.url, "/users/{{number}}");
+        assert_eq!(get.weight, 3);
+
+        let post = by_name("POST /users");
+        assert_eq!(post.url, "/users");
+        assert_eq!(post.weight, 5);
+        assert_eq!(
+            post.headers,
+            vec!["X-Request-Id: {{uuid}}", "Content-Type: application/json"]
+        );
+        assert_eq!(
+            post.body.as_deref(),
+            Some(
+                r#"{"name": "{{username}}", "age": {{number}}, "email": "{{email}}", "roles": ["{{username}}"]}"#
+            )
+        );
+
+        assert_eq!(by_name("GET /orders").url, "/orders?status=open");
+    }
+
+    #[test]
+    fn test_filters_and_swagger2() {
+        let mut only_orders = filter();
+        only_orders.tags = vec!["orders".to_string()];
+        let import = convert(SPEC, &only_orders).unwrap();
+        assert_eq!(import.requests.len(), 1);
+        assert_eq!(import.skipped_tag, 2);
+
+        let swagger = r##"{"swagger":"2.0","host":"api.local:8080","basePath":"/api","schemes":["http"],
+          "paths":{"/pets":{"post":{"parameters":[{"in":"body","name":"pet","schema":{"$ref":"#/definitions/Pet"}}]}}},
+          "definitions":{"Pet":{"type":"object","properties":{"name":{"type":"string"}}}}}"##;
+        let import = convert(swagger, &filter()).unwrap();
+        assert_eq!(
+            import.base_url.as_deref(),
+            Some("http://api.local:8080/api")
+        );
+        assert_eq!(
+            import.requests[0].body.as_deref(),
+            Some(r#"{"name": "{{username}}"}"#)
+        );
+
+        assert!(convert("paths: {}", &filter()).is_err());
+    }
+}
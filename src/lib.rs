@@ -1,6 +1,16 @@
+pub mod app;
 pub mod args;
 pub mod client;
+pub mod distributed;
 pub mod engine;
+pub mod import;
+pub mod load_test;
+pub mod metrics;
 pub mod payload;
+pub mod replay;
 pub mod report;
+pub mod scenario;
 pub mod security;
+pub mod utils;
+
+pub use load_test::{Executor, LoadTest, LoadTestResult};
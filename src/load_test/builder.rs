@@ -0,0 +1,937 @@
+// src/load_test/builder.rs
+
+use super::{Executor, LoadTestResult};
+use crate::client::http::{ConnectionMode, HttpClients, HttpOptions};
+use crate::client::protocol::Protocol;
+use crate::client::source::SourceIps;
+use crate::client::target::Target;
+use crate::client::udp::{UdpReply, UdpTarget};
+use crate::client::ws::{WsMatch, WsTarget};
+use crate::engine::abort::{self, AbortReport, AbortRule};
+use crate::engine::worker::{CsvRecord, SharedMetrics, WorkerResult};
+use crate::engine::{EngineRun, Shutdown};
+use crate::metrics::timeline::{timeline_channel, TimelinePoint};
+use crate::payload::generator::PayloadTemplate;
+use crate::replay::ReplayLog;
+use crate::scenario::Scenario;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+#[derive(Clone)]
+enum Endpoint {
+    Http(String),
+    Tcp {
+        address: String,
+        connections: u32,
+    },
+    Udp {
+        address: String,
+        reply: Option<UdpReply>,
+    },
+    Ws {
+        url: String,
+        matcher: WsMatch,
+    },
+    Custom(Arc<dyn Protocol>),
+    Scenario(Arc<Scenario>),
+    Replay(Arc<ReplayLog>),
+}
+
+pub struct LoadTestBuilder {
+    endpoint: Option<Endpoint>,
+    method: reqwest::Method,
+    headers: Vec<String>,
+    http_options: HttpOptions,
+    source_ips: SourceIps,
+    executor: Option<Executor>,
+    body: Option<String>,
+    expect_body: Option<String>,
+    warmup: Duration,
+    timeline_interval: Duration,
+    csv: Option<mpsc::Sender<CsvRecord>>,
+    abort_rules: Vec<AbortRule>,
+    drain_timeout: Duration,
+}
+
+impl Default for LoadTestBuilder {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            method: reqwest::Method::GET,
+            headers: Vec::new(),
+            http_options: HttpOptions::default(),
+            source_ips: SourceIps::default(),
+            executor: None,
+            body: None,
+            expect_body: None,
+            warmup: Duration::ZERO,
+            timeline_interval: Duration::from_secs(1),
+            csv: None,
+            abort_rules: Vec::new(),
+            drain_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl LoadTestBuilder {
+    pub fn http(mut self, url: impl Into<String>) -> Self {
+        self.endpoint = Some(Endpoint::Http(url.into()));
+        self
+    }
+
+    // Alvo TCP cru: `connections` sockets abertos antes do teste e reciclados entre os workers
+    pub fn tcp(mut self, address: impl Into<String>, connections: u32) -> Self {
+        self.endpoint = Some(Endpoint::Tcp {
+            address: address.into(),
+            connections,
+        });
+        self
+    }
+
+    // Alvo UDP: cada disparo é um datagrama; com `reply`, espera a resposta (latência = RTT)
+    pub fn udp(mut self, address: impl Into<String>, reply: Option<UdpReply>) -> Self {
+        self.endpoint = Some(Endpoint::Udp {
+            address: address.into(),
+            reply,
+        });
+        self
+    }
+
+    // Alvo WebSocket (ws:// ou wss://): uma conexão por worker; cada disparo manda o corpo como
+    // mensagem e espera a resposta que `matcher` reconhece. Os headers vão no handshake.
+    pub fn websocket(mut self, url: impl Into<String>, matcher: WsMatch) -> Self {
+        self.endpoint = Some(Endpoint::Ws {
+            url: url.into(),
+            matcher,
+        });
+        self
+    }
+
+    // Protocolo de terceiros já conectado (ver client::protocol::ProtocolRegistry)
+    pub fn protocol(mut self, protocol: Arc<dyn Protocol>) -> Self {
+        self.endpoint = Some(Endpoint::Custom(protocol));
+        self
+    }
+
+    // Jornada em passos (HTTP): cada disparo percorre o cenário inteiro. Os headers do builder
+    // valem para todos os passos; método, corpo e asserção vêm de cada passo.
+    pub fn scenario(mut self, scenario: impl Into<Arc<Scenario>>) -> Self {
+        self.endpoint = Some(Endpoint::Scenario(scenario.into()));
+        self
+    }
+
+    // Replay de um log JSONL (HTTP): método, URL, headers e corpo vêm de cada linha; os headers
+    // do builder valem por cima. Combine com Executor::replay para seguir a agenda do log.
+    pub fn replay(mut self, log: impl Into<Arc<ReplayLog>>) -> Self {
+        self.endpoint = Some(Endpoint::Replay(log.into()));
+        self
+    }
+
+    pub fn method(mut self, method: reqwest::Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    // Formato "Nome: valor" (o mesmo do -H)
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.headers.push(header.into());
+        self
+    }
+
+    pub fn headers(mut self, headers: impl IntoIterator<Item = String>) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    pub fn http_options(mut self, options: HttpOptions) -> Self {
+        self.http_options = options;
+        self
+    }
+
+    // Endereços locais de onde saem as conexões (HTTP e TCP), em rodízio
+    pub fn source_ips(mut self, sources: SourceIps) -> Self {
+        self.source_ips = sources;
+        self
+    }
+
+    pub fn executor(mut self, executor: Executor) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    // Template do corpo, com as mesmas tags do --body ({{uuid}}, {{u32:be}}, ...)
+    pub fn body(mut self, template: impl Into<String>) -> Self {
+        self.body = Some(template.into());
+        self
+    }
+
+    // Asserção: a resposta precisa conter este texto para contar como sucesso
+    pub fn expect_body(mut self, expected: impl Into<String>) -> Self {
+        self.expect_body = Some(expected.into());
+        self
+    }
+
+    pub fn warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    pub fn timeline_interval(mut self, interval: Duration) -> Self {
+        self.timeline_interval = interval;
+        self
+    }
+
+    // Cada resposta também sai por este canal (a CLI grava o CSV a partir dele)
+    pub fn csv(mut self, tx: mpsc::Sender<CsvRecord>) -> Self {
+        self.csv = Some(tx);
+        self
+    }
+
+    // Regra de parada avaliada a cada intervalo da timeline (ver engine::abort)
+    pub fn abort_on(mut self, rule: AbortRule) -> Self {
+        self.abort_rules.push(rule);
+        self
+    }
+
+    // Quanto o que está em voo tem para terminar quando uma regra de parada dispara
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    pub fn build(self) -> Result<LoadTest, String> {
+        let endpoint = self.endpoint.ok_or(
+            "Nenhum alvo definido: use .http(url), .tcp(endereço, conexões), .udp(endereço, resposta), .websocket(url, casamento), .protocol(...), .scenario(...) ou .replay(...)",
+        )?;
+        let executor = self
+            .executor
+            .ok_or("Nenhum executor definido: use .executor(...)")?;
+        executor.validate()?;
+
+        if let Some(d) = executor.planned_duration() {
+            if !self.warmup.is_zero() && self.warmup >= d {
+                return Err(format!(
+                    "O warm-up ({:?}) precisa ser menor que a duração do teste ({:?})",
+                    self.warmup, d
+                ));
+            }
+        }
+        if self.timeline_interval.is_zero() {
+            return Err("O intervalo da série temporal precisa ser maior que zero".to_string());
+        }
+        self.http_options.validate()?;
+        if self.http_options.connection_mode == ConnectionMode::PerWorker
+            && matches!(executor, Executor::Open { .. })
+        {
+            return Err(
+                "--connection-mode per-worker precisa de workers fixos; o modelo aberto (--arrival) não tem"
+                    .to_string(),
+            );
+        }
+
+        if matches!(endpoint, Endpoint::Ws { .. }) && matches!(executor, Executor::Open { .. }) {
+            return Err(
+                "O modo ws mantém uma conexão por worker; o modelo aberto (--arrival) não tem workers fixos"
+                    .to_string(),
+            );
+        }
+
+        Ok(LoadTest {
+            endpoint,
+            method: self.method,
+            headers: Arc::new(self.headers),
+            http_options: self.http_options,
+            source_ips: self.source_ips,
+            executor,
+            template: self
+                .body
+                .map(|b| PayloadTemplate::try_parse(&b))
+                .transpose()?,
+            expect_body: self.expect_body.map(Arc::new),
+            warmup: self.warmup,
+            timeline_interval: self.timeline_interval,
+            csv: self.csv,
+            abort_rules: self.abort_rules,
+            drain_timeout: self.drain_timeout,
+        })
+    }
+}
+
+// Um teste validado e pronto para disparar
+pub struct LoadTest {
+    endpoint: Endpoint,
+    method: reqwest::Method,
+    headers: Arc<Vec<String>>,
+    http_options: HttpOptions,
+    source_ips: SourceIps,
+    executor: Executor,
+    template: Option<Arc<PayloadTemplate>>,
+    expect_body: Option<Arc<String>>,
+    warmup: Duration,
+    timeline_interval: Duration,
+    csv: Option<mpsc::Sender<CsvRecord>>,
+    abort_rules: Vec<AbortRule>,
+    drain_timeout: Duration,
+}
+
+impl LoadTest {
+    pub fn builder() -> LoadTestBuilder {
+        LoadTestBuilder::default()
+    }
+
+    pub fn executor(&self) -> &Executor {
+        &self.executor
+    }
+
+    // Roda até o fim e devolve o resultado
+    pub async fn run(self) -> Result<LoadTestResult, String> {
+        Ok(self.start().await?.wait().await)
+    }
+
+    // Conecta no alvo e põe o motor para rodar em segundo plano
+    pub async fn start(self) -> Result<RunningTest, String> {
+        let clients = || {
+            HttpClients::new(&self.http_options, &self.source_ips)
+                .map_err(|e| format!("Falha ao criar o cliente HTTP: {}", e))
+        };
+        let target = match &self.endpoint {
+            Endpoint::Http(url) => Target::new_http(
+                clients()?,
+                url.clone(),
+                self.method.clone(),
+                self.headers.clone(),
+                self.expect_body.clone(),
+            ),
+            Endpoint::Tcp {
+                address,
+                connections,
+            } => Target::new_tcp(address, *connections, self.source_ips.clone()).await?,
+            Endpoint::Udp { address, reply } => {
+                Target::Udp(UdpTarget::new(address, *reply, self.source_ips.clone()).await?)
+            }
+            Endpoint::Ws { url, matcher } => Target::Ws(WsTarget::new(
+                url,
+                self.headers.clone(),
+                matcher.clone(),
+                &self.http_options,
+            )?),
+            Endpoint::Custom(protocol) => Target::custom(protocol.clone()),
+            Endpoint::Scenario(scenario) => Target::Scenario {
+                clients: clients()?,
+                scenario: scenario.clone(),
+                headers: self.headers.clone(),
+            },
+            Endpoint::Replay(log) => Target::Replay {
+                clients: clients()?,
+                log: log.clone(),
+                headers: self.headers.clone(),
+                expected_body: self.expect_body.clone(),
+            },
+        };
+        let (step_names, is_mix) = match &self.endpoint {
+            Endpoint::Scenario(scenario) => (scenario.step_names(), scenario.is_mix()),
+            _ => (Vec::new(), false),
+        };
+        let target = Arc::new(target);
+
+        let started_at = Instant::now();
+        let warmup_end = started_at + self.warmup;
+
+        // Série temporal: cada worker fecha os seus baldes e o coletor funde por intervalo
+        let (timeline_sink, mut timeline_collector) =
+            timeline_channel(started_at, self.timeline_interval);
+        let metrics = Arc::new(SharedMetrics {
+            timeline: Some(timeline_sink),
+            ..Default::default()
+        });
+
+        // Regras de parada: o coletor publica cada intervalo fechado para o vigia
+        let shutdown = Shutdown::default();
+        let mut abort_watch = None;
+        if !self.abort_rules.is_empty() {
+            let (tx, rx) = mpsc::unbounded_channel();
+            timeline_collector = timeline_collector.publish_closed(tx);
+            abort_watch = Some(tokio::spawn(abort::watch(
+                self.abort_rules.clone(),
+                self.timeline_interval,
+                rx,
+                metrics.clone(),
+                shutdown.clone(),
+                started_at,
+                self.drain_timeout,
+            )));
+        }
+
+        let (timeline_done, timeline_done_rx) = oneshot::channel();
+        let timeline = tokio::spawn(timeline_collector.run(timeline_done_rx));
+        let engine = spawn_engine(
+            self.executor.clone(),
+            self.template,
+            target,
+            metrics.clone(),
+            self.csv,
+            started_at,
+            warmup_end,
+            shutdown.clone(),
+        );
+
+        Ok(RunningTest {
+            engine,
+            timeline,
+            timeline_done,
+            metrics,
+            shutdown,
+            started_at,
+            warmup: self.warmup,
+            planned_duration: self.executor.planned_duration(),
+            planned_requests: self.executor.planned_requests(),
+            step_names,
+            is_mix,
+            abort_watch,
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_engine(
+    executor: Executor,
+    template: Option<Arc<PayloadTemplate>>,
+    target: Arc<Target>,
+    metrics: Arc<SharedMetrics>,
+    csv_tx: Option<mpsc::Sender<CsvRecord>>,
+    start_time: Instant,
+    warmup_end: Instant,
+    shutdown: Shutdown,
+) -> JoinHandle<EngineRun> {
+    tokio::spawn(async move {
+        match executor {
+            Executor::Closed {
+                workers,
+                count,
+                rps,
+                duration,
+                profile,
+            } => EngineRun {
+                results: crate::engine::worker::run_workers(
+                    count, workers, template, rps, duration, profile, None, target, metrics,
+                    csv_tx, start_time, warmup_end, shutdown,
+                )
+                .await,
+                ..Default::default()
+            },
+            Executor::Replay {
+                workers,
+                count,
+                schedule,
+            } => EngineRun {
+                results: crate::engine::worker::run_workers(
+                    count, workers, None, None, None, None, schedule, target, metrics, csv_tx,
+                    start_time, warmup_end, shutdown,
+                )
+                .await,
+                ..Default::default()
+            },
+            Executor::Open {
+                count,
+                rps,
+                duration,
+                profile,
+                distribution,
+                max_vus,
+            } => EngineRun {
+                results: crate::engine::open_model::run_open_model(
+                    count,
+                    rps,
+                    duration,
+                    profile,
+                    distribution,
+                    max_vus,
+                    template,
+                    target,
+                    metrics,
+                    csv_tx,
+                    start_time,
+                    warmup_end,
+                    shutdown,
+                )
+                .await,
+                ..Default::default()
+            },
+            Executor::Adaptive {
+                count,
+                duration,
+                max_workers,
+                algorithm,
+            } => {
+                let (results, report) = crate::engine::adaptive::run_adaptive(
+                    count,
+                    duration,
+                    max_workers,
+                    algorithm,
+                    template,
+                    target,
+                    metrics,
+                    csv_tx,
+                    start_time,
+                    warmup_end,
+                    shutdown,
+                )
+                .await;
+                EngineRun {
+                    results,
+                    adaptive: Some(report),
+                    ..Default::default()
+                }
+            }
+            Executor::Capacity { workers, search } => {
+                let (results, report) = crate::engine::capacity::run_capacity_search(
+                    search, workers, template, target, metrics, shutdown,
+                )
+                .await;
+                EngineRun {
+                    results,
+                    capacity: Some(report),
+                    ..Default::default()
+                }
+            }
+        }
+    })
+}
+
+// Um teste em andamento: os atomics ficam expostos para quem quiser mostrar progresso
+pub struct RunningTest {
+    engine: JoinHandle<EngineRun>,
+    timeline: JoinHandle<Vec<TimelinePoint>>,
+    timeline_done: oneshot::Sender<()>,
+    metrics: Arc<SharedMetrics>,
+    shutdown: Shutdown,
+    started_at: Instant,
+    warmup: Duration,
+    planned_duration: Option<Duration>,
+    planned_requests: Option<u64>,
+    step_names: Vec<String>,
+    is_mix: bool,
+    abort_watch: Option<JoinHandle<Option<AbortReport>>>,
+}
+
+impl RunningTest {
+    pub fn metrics(&self) -> &SharedMetrics {
+        &self.metrics
+    }
+
+    pub fn started_at(&self) -> Instant {
+        self.started_at
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.engine.is_finished()
+    }
+
+    // Para de despachar; o que está em voo termina normalmente
+    pub fn stop(&self) {
+        self.shutdown.stop();
+    }
+
+    // Para de despachar e cancela o que está em voo
+    pub fn abort(&self) {
+        self.shutdown.abort();
+    }
+
+    pub async fn wait(mut self) -> LoadTestResult {
+        let run = (&mut self.engine).await.unwrap_or_default();
+        self.finish(run).await
+    }
+
+    // Para o despacho e espera o que está em voo por até `timeout`; depois disso, cancela
+    pub async fn drain(mut self, timeout: Duration) -> LoadTestResult {
+        self.stop();
+        let run = tokio::select! {
+            run = &mut self.engine => run.unwrap_or_default(),
+            _ = tokio::time::sleep(timeout) => {
+                self.abort();
+                (&mut self.engine).await.unwrap_or_default()
+            }
+        };
+        self.finish(run).await
+    }
+
+    async fn finish(self, run: EngineRun) -> LoadTestResult {
+        let duration = self.started_at.elapsed();
+
+        // Os workers já devolveram os balanços: todos os baldes estão no canal
+        let _ = self.timeline_done.send(());
+        let timeline = self.timeline.await.unwrap_or_default();
+        // Com o coletor encerrado o vigia também termina (se não tiver disparado antes)
+        let abort_reason = match self.abort_watch {
+            Some(watch) => watch.await.ok().flatten(),
+            None => None,
+        };
+
+        let WorkerResult {
+            histogram,
+            response_histogram,
+            status_counts,
+            error_counts,
+            assertion_failures,
+            measured_successes,
+            measured_failures,
+            mut steps,
+            phases,
+            udp,
+            ws,
+        } = WorkerResult::merge(run.results);
+        for (stats, name) in steps.iter_mut().zip(&self.step_names) {
+            stats.name = name.clone();
+        }
+        // No mix os acumuladores são por requisição, não por passo de jornada
+        let requests = if self.is_mix {
+            std::mem::take(&mut steps)
+        } else {
+            Vec::new()
+        };
+
+        let m = &self.metrics;
+        let successes = m.successes.load(Ordering::Relaxed);
+        let failures = m.failures.load(Ordering::Relaxed);
+        let interrupted = self.shutdown.is_stopped();
+
+        LoadTestResult {
+            successes,
+            failures,
+            bytes_sent: m.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: m.bytes_received.load(Ordering::Relaxed),
+            dropped_arrivals: m.dropped_arrivals.load(Ordering::Relaxed),
+            late_arrivals: m.late_arrivals.load(Ordering::Relaxed),
+            histogram,
+            response_histogram,
+            status_counts,
+            error_counts,
+            assertion_failures,
+            measured_successes,
+            measured_failures,
+            steps,
+            requests,
+            phases,
+            udp,
+            ws,
+            timeline,
+            capacity: run.capacity,
+            adaptive: run.adaptive,
+            warmup: self.warmup,
+            planned_duration: self.planned_duration,
+            planned_requests: self.planned_requests,
+            duration,
+            interrupted,
+            aborted: self.shutdown.abort_token().is_cancelled(),
+            abort_reason,
+            // Quanto do plano nunca saiu por causa da interrupção
+            unsent_requests: interrupted
+                .then(|| {
+                    self.planned_requests
+                        .map(|total| total.saturating_sub(successes + failures))
+                })
+                .flatten(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::open_model::ArrivalDistribution;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Servidor TCP mínimo: responde 1 byte de ACK a cada leitura
+    async fn ack_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 64];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 || socket.write_all(b"k").await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_iterations_run_to_completion() {
+        let addr = ack_server().await;
+        let result = LoadTest::builder()
+            .tcp(addr, 2)
+            .body("ping")
+            .executor(Executor::iterations(2, 20))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(result.successes, 20);
+        assert_eq!(result.failures, 0);
+        assert_eq!(result.histogram.len(), 20);
+        assert!(!result.interrupted);
+        assert_eq!(result.timeline.iter().map(|p| p.requests).sum::<u64>(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_drain_marks_the_run_as_interrupted() {
+        let addr = ack_server().await;
+        let running = LoadTest::builder()
+            .tcp(addr, 1)
+            .body("ping")
+            .executor(Executor::constant_rate(1, 10, Duration::from_secs(30)))
+            .build()
+            .unwrap()
+            .start()
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let result = running.drain(Duration::from_secs(1)).await;
+        assert!(result.interrupted);
+        assert!(result.successes > 0);
+        assert_eq!(result.unsent_requests, Some(300 - result.total()));
+    }
+
+    struct Constant;
+
+    #[async_trait::async_trait]
+    impl Protocol for Constant {
+        async fn fire(&self, payload: &[u8]) -> crate::client::target::TargetResult {
+            crate::client::target::TargetResult::success(
+                Duration::from_micros(1500),
+                payload.len() as u64,
+                0,
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_protocol_runs_through_the_engine() {
+        let result = LoadTest::builder()
+            .protocol(Arc::new(Constant))
+            .body("xy")
+            .executor(Executor::iterations(3, 9))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(result.successes, 9);
+        assert_eq!(result.bytes_sent, 18);
+        assert_eq!(result.histogram.value_at_quantile(0.5), 1500);
+    }
+
+    // Alvo que nunca responde: toda tentativa falha sem status
+    struct Refused;
+
+    #[async_trait::async_trait]
+    impl Protocol for Refused {
+        async fn fire(&self, _payload: &[u8]) -> crate::client::target::TargetResult {
+            crate::client::target::TargetResult::fail(
+                Duration::from_micros(100),
+                "Connection refused".to_string(),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_abort_rules_stop_a_broken_run() {
+        let run = |rule: &str| {
+            LoadTest::builder()
+                .protocol(Arc::new(Refused))
+                .executor(Executor::constant_rate(2, 200, Duration::from_secs(30)))
+                .timeline_interval(Duration::from_millis(100))
+                .abort_on(AbortRule::parse(rule).unwrap())
+                .build()
+                .unwrap()
+                .run()
+        };
+
+        let result = run("connection_failures > 10").await.unwrap();
+        let abort = result.abort_reason.as_ref().unwrap();
+        assert_eq!(abort.rule, "connection_failures > 10");
+        assert!(result.interrupted);
+        assert!(result.total() < 6000);
+
+        let result = run("error_rate > 50% for 200ms").await.unwrap();
+        assert!(result
+            .abort_reason
+            .as_ref()
+            .unwrap()
+            .reason
+            .contains("100.0%"));
+        assert!(result.total() < 6000);
+    }
+
+    // Cada disparo leva 300ms, três intervalos de timeline
+    struct Slow;
+
+    #[async_trait::async_trait]
+    impl Protocol for Slow {
+        async fn fire(&self, _payload: &[u8]) -> crate::client::target::TargetResult {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            crate::client::target::TargetResult::success(Duration::from_millis(300), 0, 0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_abort_rules_see_targets_slower_than_the_interval() {
+        let result = LoadTest::builder()
+            .protocol(Arc::new(Slow))
+            .executor(Executor::iterations(2, 40))
+            .timeline_interval(Duration::from_millis(100))
+            .abort_on(AbortRule::parse("p99 > 200ms").unwrap())
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert!(result.abort_reason.is_some());
+        assert!(result.total() < 40);
+    }
+
+    // Trava o único worker por 400ms no 20º disparo; os demais respondem na hora
+    struct StallOnce(std::sync::atomic::AtomicU32);
+
+    #[async_trait::async_trait]
+    impl Protocol for StallOnce {
+        async fn fire(&self, _payload: &[u8]) -> crate::client::target::TargetResult {
+            if self.0.fetch_add(1, Ordering::Relaxed) == 20 {
+                tokio::time::sleep(Duration::from_millis(400)).await;
+            }
+            crate::client::target::TargetResult::success(Duration::from_micros(100), 0, 0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ramp_counts_queue_time_after_a_stall() {
+        let profile = crate::engine::profile::LoadProfile::parse("200ms:200,1s:200").unwrap();
+        let result = LoadTest::builder()
+            .protocol(Arc::new(StallOnce(Default::default())))
+            .executor(Executor::ramp(1, profile))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        // ~80 disparos venceram durante a trava: todos esperaram na fila, não só o primeiro
+        let queued = result.response_histogram.count_between(100_000, u64::MAX);
+        assert!(queued >= 40, "só {} disparos com tempo de fila", queued);
+        assert!(result.histogram.value_at_quantile(0.9) < 100_000);
+    }
+
+    #[test]
+    fn test_build_rejects_incomplete_tests() {
+        assert!(LoadTest::builder()
+            .executor(Executor::iterations(1, 1))
+            .build()
+            .is_err());
+        assert!(LoadTest::builder()
+            .http("http://localhost")
+            .build()
+            .is_err());
+        assert!(LoadTest::builder()
+            .http("http://localhost")
+            .executor(Executor::constant_rate(1, 10, Duration::from_secs(5)))
+            .warmup(Duration::from_secs(10))
+            .build()
+            .is_err());
+        // Sem perfil, 0 req/s nunca termina um teste por contagem
+        assert!(LoadTest::builder()
+            .http("http://localhost")
+            .executor(Executor::Open {
+                count: 10,
+                rps: Some(0),
+                duration: None,
+                profile: None,
+                distribution: ArrivalDistribution::Constant,
+                max_vus: None,
+            })
+            .build()
+            .is_err());
+    }
+
+    // Metade responde 200, metade falha
+    struct HalfBroken(std::sync::atomic::AtomicU32);
+
+    #[async_trait::async_trait]
+    impl Protocol for HalfBroken {
+        async fn fire(&self, _payload: &[u8]) -> crate::client::target::TargetResult {
+            if self.0.fetch_add(1, Ordering::Relaxed).is_multiple_of(2) {
+                let mut res =
+                    crate::client::target::TargetResult::success(Duration::from_micros(100), 0, 0);
+                res.status_code = Some(200);
+                res
+            } else {
+                crate::client::target::TargetResult::fail(
+                    Duration::from_micros(100),
+                    "Connection refused".to_string(),
+                )
+            }
+        }
+    }
+
+    // Status, erros e falhas de asserção contam uma vez por disparo, nos dois modelos
+    #[tokio::test]
+    async fn test_status_and_error_counts_match_the_requests() {
+        let open = Executor::Open {
+            count: 20,
+            rps: Some(500),
+            duration: None,
+            profile: None,
+            distribution: ArrivalDistribution::Constant,
+            max_vus: None,
+        };
+        for executor in [Executor::iterations(2, 20), open] {
+            let result = LoadTest::builder()
+                .protocol(Arc::new(HalfBroken(Default::default())))
+                .executor(executor)
+                .build()
+                .unwrap()
+                .run()
+                .await
+                .unwrap();
+            assert_eq!(result.successes, 10);
+            assert_eq!(result.status_counts[&200], 10);
+            assert_eq!(result.error_counts["Connection refused"], 10);
+            assert_eq!(result.assertion_failures, 10);
+        }
+    }
+
+    // A rampa parte de 0: as chegadas seguem a área sob a rampa (50 + 100), sem pular o estágio
+    #[tokio::test]
+    async fn test_open_model_follows_a_ramp_from_zero() {
+        let profile = crate::engine::profile::LoadProfile::parse("1s:100,1s:100").unwrap();
+        let result = LoadTest::builder()
+            .protocol(Arc::new(Constant))
+            .executor(Executor::Open {
+                count: 0,
+                rps: None,
+                duration: None,
+                profile: Some(Arc::new(profile)),
+                distribution: ArrivalDistribution::Constant,
+                max_vus: None,
+            })
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert!((145..=155).contains(&result.total()), "{}", result.total());
+    }
+}
@@ -0,0 +1,186 @@
+// src/load_test/mod.rs
+//
+// API de biblioteca: monta e roda um teste de carga sem imprimir nada e devolve o resultado
+// estruturado. A CLI (main.rs) é só mais um consumidor desta API.
+
+mod builder;
+mod result;
+
+pub use builder::{LoadTest, LoadTestBuilder, RunningTest};
+pub(crate) use result::apdex;
+pub use result::LoadTestResult;
+
+use crate::engine::adaptive::LimitAlgorithm;
+use crate::engine::capacity::CapacitySearch;
+use crate::engine::open_model::ArrivalDistribution;
+use crate::engine::profile::LoadProfile;
+use crate::replay::ReplayLog;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Como a carga é gerada (um executor por teste)
+#[derive(Clone)]
+pub enum Executor {
+    // Modelo fechado: `workers` fixos puxando jobs da fila. Para em `count` disparos ou no
+    // prazo (`duration`); `rps` dá ritmo ao despacho e `profile` troca tudo pelos estágios
+    Closed {
+        workers: u32,
+        count: u32,
+        rps: Option<u32>,
+        duration: Option<Duration>,
+        profile: Option<Arc<LoadProfile>>,
+    },
+    // Modelo aberto: chegadas na agenda da taxa (ou dos estágios), limitadas por `max_vus`
+    Open {
+        count: u32,
+        rps: Option<u32>,
+        duration: Option<Duration>,
+        profile: Option<Arc<LoadProfile>>,
+        distribution: ArrivalDistribution,
+        max_vus: Option<u32>,
+    },
+    // Concorrência adaptativa: até `max_workers` ativos, movidos pelo limitador
+    Adaptive {
+        count: u32,
+        duration: Option<Duration>,
+        max_workers: u32,
+        algorithm: LimitAlgorithm,
+    },
+    // Busca de capacidade: degraus de RPS avaliados contra o SLO
+    Capacity {
+        workers: u32,
+        search: CapacitySearch,
+    },
+    // Replay de um log (ver crate::replay): `count` linhas, cada uma no seu instante da
+    // `schedule`; sem agenda, o mais rápido que `workers` conseguirem
+    Replay {
+        workers: u32,
+        count: u32,
+        schedule: Option<Arc<[Duration]>>,
+    },
+}
+
+impl Executor {
+    // `count` disparos o mais rápido que `workers` conseguirem
+    pub fn iterations(workers: u32, count: u32) -> Self {
+        Self::Closed {
+            workers,
+            count,
+            rps: None,
+            duration: None,
+            profile: None,
+        }
+    }
+
+    // `rps` constante durante `duration`
+    pub fn constant_rate(workers: u32, rps: u32, duration: Duration) -> Self {
+        Self::Closed {
+            workers,
+            count: u32::MAX,
+            rps: Some(rps),
+            duration: Some(duration),
+            profile: None,
+        }
+    }
+
+    // Perfil escalonado (ramp-up) com workers fixos
+    pub fn ramp(workers: u32, profile: LoadProfile) -> Self {
+        Self::Closed {
+            workers,
+            count: u32::MAX,
+            rps: None,
+            duration: None,
+            profile: Some(Arc::new(profile)),
+        }
+    }
+
+    // Replay do log inteiro: `speed` 1.0 = ritmo original, None = sem agenda
+    pub fn replay(workers: u32, log: &ReplayLog, speed: Option<f64>) -> Result<Self, String> {
+        Ok(Self::Replay {
+            workers,
+            count: u32::try_from(log.len()).map_err(|_| "O log de replay é grande demais")?,
+            schedule: match speed {
+                Some(speed) => Some(log.schedule(speed)?.into()),
+                None => None,
+            },
+        })
+    }
+
+    // Duração planejada: explícita ou implícita na soma dos estágios
+    pub fn planned_duration(&self) -> Option<Duration> {
+        match self {
+            Self::Closed {
+                duration, profile, ..
+            }
+            | Self::Open {
+                duration, profile, ..
+            } => duration.or(profile.as_ref().map(|p| p.total_duration())),
+            Self::Adaptive { duration, .. } => *duration,
+            Self::Capacity { .. } => None,
+            Self::Replay { schedule, .. } => schedule.as_ref().and_then(|s| s.last().copied()),
+        }
+    }
+
+    // Quantos disparos o plano prevê (None quando não há como saber de antemão)
+    pub fn planned_requests(&self) -> Option<u64> {
+        match self {
+            Self::Closed {
+                count,
+                rps,
+                duration,
+                profile,
+                ..
+            }
+            | Self::Open {
+                count,
+                rps,
+                duration,
+                profile,
+                ..
+            } => match (profile, duration, rps) {
+                (Some(profile), _, _) => Some(profile.estimated_requests()),
+                (None, Some(d), Some(rps)) => Some((d.as_secs_f64() * *rps as f64) as u64),
+                (None, Some(_), None) => None,
+                (None, None, _) => Some(*count as u64),
+            },
+            Self::Adaptive {
+                count,
+                duration: None,
+                ..
+            } => Some(*count as u64),
+            Self::Adaptive { .. } | Self::Capacity { .. } => None,
+            Self::Replay { count, .. } => Some(*count as u64),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Closed { workers: 0, .. }
+            | Self::Capacity { workers: 0, .. }
+            | Self::Replay { workers: 0, .. }
+            | Self::Adaptive { max_workers: 0, .. } => {
+                Err("O executor precisa de pelo menos 1 worker".to_string())
+            }
+            Self::Open {
+                rps: None,
+                profile: None,
+                ..
+            } => Err(
+                "O modelo aberto precisa de uma taxa: informe rps ou um perfil de estágios"
+                    .to_string(),
+            ),
+            // Sem perfil, 0 req/s nunca dispara nada e um teste por contagem não termina
+            Self::Open {
+                rps: Some(0),
+                profile: None,
+                ..
+            }
+            | Self::Closed {
+                rps: Some(0),
+                profile: None,
+                ..
+            } => Err("A taxa (rps) precisa ser maior que zero".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
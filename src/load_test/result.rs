@@ -0,0 +1,138 @@
+// src/load_test/result.rs
+
+use crate::engine::abort::AbortReport;
+use crate::engine::adaptive::AdaptiveReport;
+use crate::engine::capacity::CapacityReport;
+use crate::metrics::phases::PhaseStats;
+use crate::metrics::timeline::TimelinePoint;
+use crate::metrics::udp::UdpStats;
+use crate::metrics::ws::WsStats;
+use crate::report::cli::{to_ms, FinalReport, LatencyStats, PhaseReport, StepReport, WsReport};
+use crate::scenario::StepStats;
+use hdrhistogram::Histogram;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Tudo o que um teste produziu, já fundido entre os workers
+pub struct LoadTestResult {
+    pub successes: u64,
+    pub failures: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    // Tempo de serviço (µs), sem o warm-up
+    pub histogram: Histogram<u64>,
+    // Tempo de resposta a partir do instante agendado (µs); vazio em modos sem ritmo
+    pub response_histogram: Histogram<u64>,
+    pub status_counts: HashMap<u16, u64>,
+    pub error_counts: HashMap<String, u64>,
+    pub assertion_failures: u64,
+    // Sucessos e falhas fora do warm-up (os contadores do topo incluem o warm-up)
+    pub measured_successes: u64,
+    pub measured_failures: u64,
+    // Cenários: métricas por passo (os contadores acima são por jornada)
+    pub steps: Vec<StepStats>,
+    // Mix ponderado: métricas por requisição (os contadores acima somam todas)
+    pub requests: Vec<StepStats>,
+    // Fases das requisições HTTP (vazio em alvos TCP / protocolos próprios)
+    pub phases: PhaseStats,
+    // --mode udp: datagramas, respostas, perda, duplicatas e atrasadas (vazio nos outros modos)
+    pub udp: UdpStats,
+    // --mode ws: handshakes, mensagens e desconexões (vazio nos outros modos)
+    pub ws: WsStats,
+    pub dropped_arrivals: u64,
+    pub late_arrivals: u64,
+    pub timeline: Vec<TimelinePoint>,
+    pub capacity: Option<CapacityReport>,
+    pub adaptive: Option<AdaptiveReport>,
+    pub warmup: Duration,
+    pub planned_duration: Option<Duration>,
+    pub planned_requests: Option<u64>,
+    // Tempo de parede do teste inteiro (warm-up incluso)
+    pub duration: Duration,
+    // Parado antes do fim (stop/drain); `aborted` se o que estava em voo foi cancelado
+    pub interrupted: bool,
+    pub aborted: bool,
+    // Regra de parada (--abort-on) que encerrou o teste antes do fim
+    pub abort_reason: Option<AbortReport>,
+    pub unsent_requests: Option<u64>,
+}
+
+impl LoadTestResult {
+    pub fn total(&self) -> u64 {
+        self.successes + self.failures
+    }
+
+    // Duração sem o warm-up (base do RPS real)
+    pub fn stable_duration(&self) -> Duration {
+        self.duration
+            .checked_sub(self.warmup)
+            .unwrap_or(self.duration)
+    }
+
+    pub fn actual_rps(&self) -> f64 {
+        self.successes as f64 / self.stable_duration().as_secs_f64()
+    }
+
+    // Apdex com limiar de satisfação `t_ms` (tolerável até 4x)
+    pub fn apdex(&self, t_ms: u64) -> f64 {
+        apdex(&self.histogram, t_ms)
+    }
+
+    // O relatório serializável (JSON / HTML)
+    pub fn to_report(&self, target: &str, concurrency: u32, apdex_t_ms: u64) -> FinalReport {
+        let hist = &self.histogram;
+        FinalReport {
+            target: target.to_string(),
+            // Em testes por tempo o total é o que efetivamente saiu
+            total_requests: match self.planned_duration {
+                Some(_) => self.total(),
+                None => self.planned_requests.unwrap_or(self.total()),
+            } as u32,
+            concurrency,
+            successes: self.successes,
+            failures: self.failures,
+            min_ms: to_ms(hist.min()),
+            avg_ms: to_ms(hist.mean() as u64),
+            p50_ms: to_ms(hist.value_at_quantile(0.5)),
+            p95_ms: to_ms(hist.value_at_quantile(0.95)),
+            p99_ms: to_ms(hist.value_at_quantile(0.99)),
+            max_ms: to_ms(hist.max()),
+            actual_rps: self.actual_rps(),
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            status_codes: self.status_counts.clone(),
+            errors: self.error_counts.clone(),
+            duration_secs: self.stable_duration().as_secs_f64(),
+            planned_duration_secs: self.planned_duration.map(|d| d.as_secs_f64()),
+            actual_duration_secs: self.duration.as_secs_f64(),
+            apdex_score: self.apdex(apdex_t_ms),
+            response_time: (!self.response_histogram.is_empty())
+                .then(|| LatencyStats::from_histogram(&self.response_histogram)),
+            dropped_arrivals: self.dropped_arrivals,
+            late_arrivals: self.late_arrivals,
+            interrupted: self.interrupted,
+            abort_reason: self.abort_reason.clone(),
+            unsent_requests: self.unsent_requests,
+            capacity: self.capacity.clone(),
+            adaptive: self.adaptive.clone(),
+            timeline: self.timeline.clone(),
+            steps: self.steps.iter().map(StepReport::from_stats).collect(),
+            requests: self.requests.iter().map(StepReport::from_stats).collect(),
+            phases: (!self.phases.is_empty()).then(|| PhaseReport::from_stats(&self.phases)),
+            udp: (!self.udp.is_empty()).then_some(self.udp),
+            ws: (!self.ws.is_empty()).then(|| WsReport::from_stats(&self.ws, self.duration)),
+            thresholds: Vec::new(),
+        }
+    }
+}
+
+// Apdex de qualquer histograma de latência (µs): também usado nos thresholds por requisição
+pub(crate) fn apdex(hist: &Histogram<u64>, t_ms: u64) -> f64 {
+    if hist.is_empty() {
+        return 0.0;
+    }
+    let t_us = t_ms * 1000;
+    let satisfied = hist.count_between(0, t_us);
+    let tolerating = hist.count_between(t_us + 1, t_us * 4);
+    (satisfied as f64 + (tolerating as f64 / 2.0)) / hist.len() as f64
+}
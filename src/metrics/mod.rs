@@ -0,0 +1,4 @@
+pub mod phases;
+pub mod timeline;
+pub mod udp;
+pub mod ws;
@@ -0,0 +1,71 @@
+// src/metrics/phases.rs
+//
+// Histogramas por fase da requisição HTTP (ver client::timing). Um por worker, fundidos no fim
+// como o resto das métricas: quando o p99 piora, dizem se foi o servidor (TTFB) ou o
+// estabelecimento de conexão (DNS / TCP / TLS).
+
+use crate::client::timing::PhaseTimings;
+use hdrhistogram::Histogram;
+
+pub struct PhaseStats {
+    // Só conexões novas entram em DNS / TCP / TLS
+    pub dns: Histogram<u64>,
+    pub connect: Histogram<u64>,
+    pub tls: Histogram<u64>,
+    pub ttfb: Histogram<u64>,
+    pub download: Histogram<u64>,
+    pub new_connections: u64,
+    pub reused_connections: u64,
+}
+
+impl Default for PhaseStats {
+    fn default() -> Self {
+        let hist = || Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap();
+        Self {
+            dns: hist(),
+            connect: hist(),
+            tls: hist(),
+            ttfb: hist(),
+            download: hist(),
+            new_connections: 0,
+            reused_connections: 0,
+        }
+    }
+}
+
+impl PhaseStats {
+    pub fn record(&mut self, phases: &PhaseTimings) {
+        let us = |d: std::time::Duration| d.as_micros() as u64;
+        if let Some(dns) = phases.dns {
+            let _ = self.dns.record(us(dns));
+        }
+        if let Some(connect) = phases.connect {
+            let _ = self.connect.record(us(connect));
+        }
+        if let Some(tls) = phases.tls {
+            let _ = self.tls.record(us(tls));
+        }
+        let _ = self.ttfb.record(us(phases.ttfb));
+        let _ = self.download.record(us(phases.download));
+        if phases.new_connection() {
+            self.new_connections += 1;
+        } else {
+            self.reused_connections += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: PhaseStats) {
+        let _ = self.dns.add(other.dns);
+        let _ = self.connect.add(other.connect);
+        let _ = self.tls.add(other.tls);
+        let _ = self.ttfb.add(other.ttfb);
+        let _ = self.download.add(other.download);
+        self.new_connections += other.new_connections;
+        self.reused_connections += other.reused_connections;
+    }
+
+    // Alvos TCP e protocolos próprios não têm fases
+    pub fn is_empty(&self) -> bool {
+        self.ttfb.is_empty()
+    }
+}
@@ -0,0 +1,348 @@
+// src/metrics/timeline.rs
+
+use crate::client::target::TargetResult;
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+
+// Um intervalo de tempo (índice = segundos desde o início / tamanho do intervalo)
+pub struct IntervalBucket {
+    index: u64,
+    successes: u64,
+    failures: u64,
+    status_counts: HashMap<u16, u64>,
+    new_connections: u64,
+    // Auto-resize: só cresce até a maior latência vista no intervalo
+    histogram: Histogram<u64>,
+}
+
+impl IntervalBucket {
+    fn new(index: u64) -> Self {
+        Self {
+            index,
+            successes: 0,
+            failures: 0,
+            status_counts: HashMap::new(),
+            new_connections: 0,
+            histogram: Histogram::new(2).unwrap(),
+        }
+    }
+
+    fn merge(&mut self, other: IntervalBucket) {
+        self.successes += other.successes;
+        self.failures += other.failures;
+        for (k, v) in other.status_counts {
+            *self.status_counts.entry(k).or_insert(0) += v;
+        }
+        self.new_connections += other.new_connections;
+        let _ = self.histogram.add(other.histogram);
+    }
+}
+
+// Ponto da série temporal exportado no relatório
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimelinePoint {
+    pub t_secs: f64,
+    pub requests: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub rps: f64,
+    pub status_codes: HashMap<u16, u64>,
+    // Conexões HTTP abertas no intervalo (churn ao longo do teste)
+    pub new_connections: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+// Resumo de um intervalo já fechado, publicado durante o teste (regras de parada)
+#[derive(Debug, Clone, Copy)]
+pub struct IntervalSummary {
+    pub t_secs: f64,
+    pub successes: u64,
+    pub failures: u64,
+    // None quando o intervalo não teve nenhum sucesso
+    pub p99_ms: Option<f64>,
+}
+
+// Folga depois da virada do intervalo antes de considerá-lo fechado, para os resultados que
+// terminaram bem na virada
+const CLOSE_GRACE: Duration = Duration::from_millis(100);
+
+// O balde aberto de um worker. O worker o trava a cada resultado; o coletor só na virada do
+// intervalo, para recolher o balde de quem ainda está esperando uma resposta lenta.
+type OpenBucket = Mutex<Option<IntervalBucket>>;
+
+// Ponta de envio, clonada para cada worker (via SharedMetrics)
+#[derive(Clone)]
+pub struct TimelineSink {
+    start: Instant,
+    interval: Duration,
+    tx: mpsc::UnboundedSender<IntervalBucket>,
+    open: Arc<Mutex<Vec<Weak<OpenBucket>>>>,
+}
+
+impl TimelineSink {
+    pub fn recorder(&self) -> TimelineRecorder {
+        let current = Arc::new(OpenBucket::default());
+        self.open.lock().unwrap().push(Arc::downgrade(&current));
+        TimelineRecorder {
+            sink: self.clone(),
+            current,
+        }
+    }
+}
+
+// Estado local do worker: só o intervalo corrente fica em memória. Quando o relógio vira,
+// o balde fechado segue pelo canal e o coletor funde. Um worker preso num disparo mais longo
+// que o intervalo não vira o relógio: aí é o coletor que recolhe o balde (ver `sweep`).
+pub struct TimelineRecorder {
+    sink: TimelineSink,
+    current: Arc<OpenBucket>,
+}
+
+impl TimelineRecorder {
+    pub fn record(&mut self, now: Instant, res: &TargetResult) {
+        let elapsed = now.saturating_duration_since(self.sink.start);
+        let index = (elapsed.as_secs_f64() / self.sink.interval.as_secs_f64()) as u64;
+
+        let mut current = self.current.lock().unwrap();
+        if current.as_ref().is_some_and(|b| b.index != index) {
+            if let Some(bucket) = current.take() {
+                let _ = self.sink.tx.send(bucket);
+            }
+        }
+        let bucket = current.get_or_insert_with(|| IntervalBucket::new(index));
+
+        if res.success {
+            bucket.successes += 1;
+            let _ = bucket.histogram.record(res.duration.as_micros() as u64);
+        } else {
+            bucket.failures += 1;
+        }
+        if let Some(code) = res.status_code {
+            *bucket.status_counts.entry(code).or_insert(0) += 1;
+        }
+        bucket.new_connections += res.new_connections();
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(bucket) = self.current.lock().unwrap().take() {
+            let _ = self.sink.tx.send(bucket);
+        }
+    }
+}
+
+impl Drop for TimelineRecorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+pub struct TimelineCollector {
+    start: Instant,
+    interval: Duration,
+    rx: mpsc::UnboundedReceiver<IntervalBucket>,
+    open: Arc<Mutex<Vec<Weak<OpenBucket>>>>,
+    buckets: BTreeMap<u64, IntervalBucket>,
+    closed: Option<mpsc::UnboundedSender<IntervalSummary>>,
+}
+
+pub fn timeline_channel(start: Instant, interval: Duration) -> (TimelineSink, TimelineCollector) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let open = Arc::new(Mutex::new(Vec::new()));
+    (
+        TimelineSink {
+            start,
+            interval,
+            tx,
+            open: open.clone(),
+        },
+        TimelineCollector {
+            start,
+            interval,
+            rx,
+            open,
+            buckets: BTreeMap::new(),
+            closed: None,
+        },
+    )
+}
+
+impl TimelineCollector {
+    fn absorb(&mut self, bucket: IntervalBucket) {
+        match self.buckets.get_mut(&bucket.index) {
+            Some(existing) => existing.merge(bucket),
+            None => {
+                self.buckets.insert(bucket.index, bucket);
+            }
+        }
+    }
+
+    // Recolhe os baldes abertos de intervalos anteriores a `current` (workers esperando uma
+    // resposta) e o que já estava no canal; os workers que terminaram saem da lista
+    fn sweep(&mut self, current: u64) {
+        let mut stale = Vec::new();
+        self.open.lock().unwrap().retain(|slot| {
+            let Some(slot) = slot.upgrade() else {
+                return false;
+            };
+            let mut open = slot.lock().unwrap();
+            if open.as_ref().is_some_and(|b| b.index < current) {
+                stale.extend(open.take());
+            }
+            true
+        });
+        // O worker manda o balde pelo canal com a trava na mão: o que ele entregou antes da
+        // varredura já está aqui
+        while let Ok(bucket) = self.rx.try_recv() {
+            self.absorb(bucket);
+        }
+        for bucket in stale {
+            self.absorb(bucket);
+        }
+    }
+
+    // Publica cada intervalo assim que ele fecha (sem isto, a série só sai no fim)
+    pub fn publish_closed(mut self, tx: mpsc::UnboundedSender<IntervalSummary>) -> Self {
+        self.closed = Some(tx);
+        self
+    }
+
+    fn summary(&self, index: u64) -> IntervalSummary {
+        let bucket = self.buckets.get(&index);
+        IntervalSummary {
+            t_secs: index as f64 * self.interval.as_secs_f64(),
+            successes: bucket.map_or(0, |b| b.successes),
+            failures: bucket.map_or(0, |b| b.failures),
+            p99_ms: bucket
+                .filter(|b| !b.histogram.is_empty())
+                .map(|b| b.histogram.value_at_quantile(0.99) as f64 / 1000.0),
+        }
+    }
+
+    // Funde os baldes enquanto o teste roda; `done` chega quando os workers já terminaram
+    pub async fn run(mut self, mut done: oneshot::Receiver<()>) -> Vec<TimelinePoint> {
+        let mut tick = tokio::time::interval_at(
+            (self.start + self.interval + CLOSE_GRACE).into(),
+            self.interval,
+        );
+        let mut next_closed = 0;
+        loop {
+            tokio::select! {
+                Some(bucket) = self.rx.recv() => self.absorb(bucket),
+                _ = tick.tick(), if self.closed.is_some() => {
+                    let elapsed = self.start.elapsed().saturating_sub(CLOSE_GRACE);
+                    let current = (elapsed.as_secs_f64() / self.interval.as_secs_f64()) as u64;
+                    self.sweep(current);
+                    while next_closed < current {
+                        let summary = self.summary(next_closed);
+                        if let Some(tx) = &self.closed {
+                            let _ = tx.send(summary);
+                        }
+                        next_closed += 1;
+                    }
+                }
+                _ = &mut done => break,
+            }
+        }
+        while let Ok(bucket) = self.rx.try_recv() {
+            self.absorb(bucket);
+        }
+        self.into_points()
+    }
+
+    fn into_points(self) -> Vec<TimelinePoint> {
+        let secs = self.interval.as_secs_f64();
+        self.buckets
+            .into_values()
+            .map(|b| {
+                let h = &b.histogram;
+                let to_ms = |us: u64| us as f64 / 1000.0;
+                TimelinePoint {
+                    t_secs: b.index as f64 * secs,
+                    requests: b.successes + b.failures,
+                    successes: b.successes,
+                    failures: b.failures,
+                    rps: (b.successes + b.failures) as f64 / secs,
+                    p50_ms: to_ms(h.value_at_quantile(0.5)),
+                    p95_ms: to_ms(h.value_at_quantile(0.95)),
+                    p99_ms: to_ms(h.value_at_quantile(0.99)),
+                    max_ms: to_ms(h.max()),
+                    status_codes: b.status_counts,
+                    new_connections: b.new_connections,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(ms: u64, status: u16) -> TargetResult {
+        TargetResult {
+            status_code: Some(status),
+            ..TargetResult::success(Duration::from_millis(ms), 0, 0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buckets_from_many_workers_are_merged_per_interval() {
+        let start = Instant::now();
+        let (sink, collector) = timeline_channel(start, Duration::from_secs(1));
+        let (done_tx, done_rx) = oneshot::channel();
+        let handle = tokio::spawn(collector.run(done_rx));
+
+        let mut a = sink.recorder();
+        let mut b = sink.recorder();
+        a.record(start + Duration::from_millis(100), &ok(10, 200));
+        b.record(start + Duration::from_millis(900), &ok(30, 500));
+        a.record(start + Duration::from_millis(1500), &ok(20, 200));
+        b.record(
+            start + Duration::from_millis(2100),
+            &TargetResult::fail(Duration::from_millis(5), "boom".to_string()),
+        );
+        drop(a);
+        drop(b);
+        done_tx.send(()).unwrap();
+
+        let points = handle.await.unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].requests, 2);
+        assert_eq!(points[0].status_codes[&500], 1);
+        assert_eq!(points[1].t_secs, 1.0);
+        assert_eq!(points[1].successes, 1);
+        assert_eq!(points[2].failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_closed_intervals_include_workers_still_waiting() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let (sink, collector) = timeline_channel(start, interval);
+        let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+        let (done_tx, done_rx) = oneshot::channel();
+        let handle = tokio::spawn(collector.publish_closed(closed_tx).run(done_rx));
+
+        // O worker registra um resultado e fica preso no próximo disparo, sem virar o relógio
+        let mut slow = sink.recorder();
+        slow.record(start + Duration::from_millis(50), &ok(1500, 200));
+
+        let first = closed_rx.recv().await.unwrap();
+        assert_eq!(first.t_secs, 0.0);
+        assert_eq!(first.successes, 1);
+        assert!(first.p99_ms.is_some_and(|p| p > 1000.0));
+
+        drop(slow);
+        done_tx.send(()).unwrap();
+        let points = handle.await.unwrap();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].requests, 1);
+    }
+}
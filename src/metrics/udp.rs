@@ -0,0 +1,57 @@
+// src/metrics/udp.rs
+//
+// Contadores do --mode udp (ver client::udp). O RTT é a própria latência do relatório quando
+// o disparo espera a resposta; aqui ficam a perda e os datagramas fora de hora.
+
+use crate::client::udp::DatagramOutcome;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UdpStats {
+    pub sent: u64,
+    // Só os disparos que esperavam resposta entram em replies / lost
+    pub awaited: u64,
+    pub replies: u64,
+    pub lost: u64,
+    pub duplicates: u64,
+    pub late: u64,
+}
+
+impl UdpStats {
+    pub fn record(&mut self, outcome: &DatagramOutcome) {
+        self.sent += 1;
+        match outcome.replied {
+            Some(true) => {
+                self.awaited += 1;
+                self.replies += 1;
+            }
+            Some(false) => {
+                self.awaited += 1;
+                self.lost += 1;
+            }
+            None => {}
+        }
+        self.duplicates += outcome.duplicates as u64;
+        self.late += outcome.late as u64;
+    }
+
+    pub fn merge(&mut self, other: UdpStats) {
+        self.sent += other.sent;
+        self.awaited += other.awaited;
+        self.replies += other.replies;
+        self.lost += other.lost;
+        self.duplicates += other.duplicates;
+        self.late += other.late;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sent == 0
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        if self.awaited == 0 {
+            return 0.0;
+        }
+        self.lost as f64 / self.awaited as f64
+    }
+}
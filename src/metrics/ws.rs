@@ -0,0 +1,73 @@
+// src/metrics/ws.rs
+//
+// Contadores do --mode ws (ver client::ws): handshake, mensagens e motivos de desconexão.
+// O RTT das mensagens é a própria latência do relatório.
+
+use crate::client::ws::WsOutcome;
+use hdrhistogram::Histogram;
+use std::collections::HashMap;
+
+pub struct WsStats {
+    // Duração do handshake (µs), uma amostra por conexão aberta
+    pub setup: Histogram<u64>,
+    pub connections: u64,
+    pub handshake_failures: u64,
+    pub messages_sent: u64,
+    pub replies: u64,
+    // Mensagens recebidas que não eram a resposta esperada (pushes, respostas atrasadas)
+    pub unmatched: u64,
+    // Motivo → quantas vezes ("Close 1001 (going away)", "Connection reset by peer", ...)
+    pub disconnects: HashMap<String, u64>,
+}
+
+impl Default for WsStats {
+    fn default() -> Self {
+        Self {
+            setup: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            connections: 0,
+            handshake_failures: 0,
+            messages_sent: 0,
+            replies: 0,
+            unmatched: 0,
+            disconnects: HashMap::new(),
+        }
+    }
+}
+
+impl WsStats {
+    pub fn record(&mut self, outcome: &WsOutcome) {
+        if let Some(setup) = outcome.connected {
+            self.connections += 1;
+            let _ = self.setup.record(setup.as_micros() as u64);
+        }
+        if outcome.handshake_failed {
+            self.handshake_failures += 1;
+        }
+        if outcome.sent {
+            self.messages_sent += 1;
+        }
+        if outcome.replied == Some(true) {
+            self.replies += 1;
+        }
+        self.unmatched += outcome.unmatched as u64;
+        if let Some(reason) = &outcome.disconnect {
+            *self.disconnects.entry(reason.clone()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: WsStats) {
+        let _ = self.setup.add(other.setup);
+        self.connections += other.connections;
+        self.handshake_failures += other.handshake_failures;
+        self.messages_sent += other.messages_sent;
+        self.replies += other.replies;
+        self.unmatched += other.unmatched;
+        for (reason, n) in other.disconnects {
+            *self.disconnects.entry(reason).or_insert(0) += n;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.connections == 0 && self.handshake_failures == 0
+    }
+}
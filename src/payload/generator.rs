@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -29,6 +30,9 @@ pub enum Chunk {
     // Geradores Binários (Para TCP)
     BinaryRandomNumber(BinaryType),
     BinaryFixedValue { value: u64, ty: BinaryType },
+
+    // Tag desconhecida: variável de cenário ({{token}}). Sem valor, sai literal como antes.
+    Variable(String),
 }
 
 pub struct PayloadTemplate {
@@ -37,6 +41,12 @@ pub struct PayloadTemplate {
 
 impl PayloadTemplate {
     pub fn parse(template: &str) -> Arc<Self> {
+        Self::try_parse(template).expect("Sintaxe inválida: tag não fechada")
+    }
+
+    // Como `parse`, mas devolve o erro: templates vindos de arquivos (cenários, HAR) não
+    // derrubam o processo
+    pub fn try_parse(template: &str) -> Result<Arc<Self>, String> {
         let mut chunks = Vec::new();
         let mut remaining = template;
 
@@ -48,9 +58,15 @@ impl PayloadTemplate {
                 ));
             }
 
-            let end_idx = remaining
+            let end_idx = remaining[start_idx..]
                 .find("}}")
-                .expect("Sintaxe inválida: tag não fechada");
+                .map(|i| start_idx + i)
+                .ok_or_else(|| {
+                    format!(
+                        "Sintaxe inválida: tag não fechada em '{}'",
+                        &remaining[start_idx..]
+                    )
+                })?;
             let tag = &remaining[start_idx + 2..end_idx];
 
             match tag {
@@ -73,8 +89,8 @@ impl PayloadTemplate {
                     });
                 }
                 _ => {
-                    // Fallback se a tag não for reconhecida, trata como texto estático
-                    chunks.push(Chunk::StaticText(format!("{{{{{}}}}}", tag).into_bytes()));
+                    // Fallback se a tag não for reconhecida: variável (ou texto estático no render)
+                    chunks.push(Chunk::Variable(tag.to_string()));
                 }
             }
 
@@ -85,7 +101,7 @@ impl PayloadTemplate {
             chunks.push(Chunk::StaticText(remaining.as_bytes().to_vec()));
         }
 
-        Arc::new(Self { chunks })
+        Ok(Arc::new(Self { chunks }))
     }
 
     fn parse_binary_type(s: &str) -> BinaryType {
@@ -103,11 +119,29 @@ impl PayloadTemplate {
 
     #[inline(always)]
     pub fn render(&self, buffer: &mut Vec<u8>) {
+        self.render_inner(buffer, None);
+    }
+
+    // Render com as variáveis do usuário virtual (cenários em passos)
+    pub fn render_with(&self, buffer: &mut Vec<u8>, vars: &HashMap<String, String>) {
+        self.render_inner(buffer, Some(vars));
+    }
+
+    #[inline(always)]
+    fn render_inner(&self, buffer: &mut Vec<u8>, vars: Option<&HashMap<String, String>>) {
         buffer.clear();
 
         for chunk in &self.chunks {
             match chunk {
                 Chunk::StaticText(bytes) => buffer.extend_from_slice(bytes),
+                Chunk::Variable(name) => match vars.and_then(|v| v.get(name)) {
+                    Some(value) => buffer.extend_from_slice(value.as_bytes()),
+                    None => {
+                        buffer.extend_from_slice(b"{{");
+                        buffer.extend_from_slice(name.as_bytes());
+                        buffer.extend_from_slice(b"}}");
+                    }
+                },
 
                 // --- INJEÇÕES HTTP / TEXTO ZERO-COPY ---
                 Chunk::TextRandomNumber => {
@@ -0,0 +1,382 @@
+// src/replay/mod.rs
+//
+// Replay de tráfego (--replay): um arquivo JSONL, uma requisição por linha, re-disparado contra
+// o --url. Cada disparo do motor consome a próxima linha; a agenda (`schedule`) reproduz os
+// intervalos originais, acelerados pelo --replay-speed, ou some de vez no modo "max".
+//
+//   {"timestamp":"2024-05-01T12:00:00.250Z","method":"POST","url":"/orders","headers":{"Content-Type":"application/json"},"body":"{}"}
+//
+// URLs absolutas têm o host trocado pelo do --url (a ideia é levar o tráfego de produção para
+// staging); só o caminho e a query são preservados.
+
+use crate::client::target::TargetResult;
+use crate::client::timing;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+// Headers que o cliente recalcula: copiar os da captura só quebraria a requisição
+const SKIPPED_HEADERS: [&str; 4] = ["host", "content-length", "connection", "transfer-encoding"];
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(default, alias = "ts", alias = "time")]
+    timestamp: Option<Timestamp>,
+    #[serde(default = "default_method")]
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Headers,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+// Epoch numérico (segundos ou milissegundos) ou texto RFC 3339
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Timestamp {
+    Epoch(f64),
+    Text(String),
+}
+
+// {"Nome": "valor"} ou ["Nome: valor"] (o formato do -H)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Headers {
+    Map(BTreeMap<String, String>),
+    List(Vec<String>),
+}
+
+impl Default for Headers {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+struct Entry {
+    // Desde a primeira linha do arquivo (None = linha sem timestamp)
+    offset: Option<Duration>,
+    method: reqwest::Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+}
+
+pub struct ReplayLog {
+    entries: Vec<Entry>,
+    // Próxima linha a disparar (compartilhada entre os workers)
+    cursor: AtomicUsize,
+}
+
+impl ReplayLog {
+    pub fn load(path: &str, base_url: &str) -> Result<Self, String> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Não foi possível abrir '{}': {}", path, e))?;
+        Self::parse(std::io::BufReader::new(file), base_url)
+    }
+
+    pub fn parse(reader: impl BufRead, base_url: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        let mut first: Option<f64> = None;
+
+        for (i, line) in reader.lines().enumerate() {
+            let n = i + 1;
+            let line = line.map_err(|e| format!("Erro lendo a linha {}: {}", n, e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: Line =
+                serde_json::from_str(&line).map_err(|e| format!("Linha {} inválida: {}", n, e))?;
+
+            let offset = match &line.timestamp {
+                Some(ts) => {
+                    let secs = ts.as_secs().map_err(|e| format!("Linha {}: {}", n, e))?;
+                    let first = *first.get_or_insert(secs);
+                    if secs < first {
+                        return Err(format!(
+                            "Linha {}: timestamp anterior ao da primeira linha (o log precisa estar em ordem)",
+                            n
+                        ));
+                    }
+                    Some(Duration::from_secs_f64(secs - first))
+                }
+                None => None,
+            };
+            let method = reqwest::Method::from_bytes(line.method.to_uppercase().as_bytes())
+                .map_err(|_| format!("Linha {}: método inválido '{}'", n, line.method))?;
+            let headers = match line.headers {
+                Headers::Map(map) => map.into_iter().collect(),
+                Headers::List(list) => list
+                    .iter()
+                    .filter_map(|h| h.split_once(':'))
+                    .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                    .collect::<Vec<_>>(),
+            }
+            .into_iter()
+            .filter(|(k, _)| !SKIPPED_HEADERS.contains(&k.to_lowercase().as_str()))
+            .collect();
+
+            entries.push(Entry {
+                offset,
+                method,
+                url: retarget(base_url, &line.url),
+                headers,
+                body: line.body.map(String::into_bytes),
+            });
+        }
+
+        if entries.is_empty() {
+            return Err("O log de replay não tem nenhuma requisição".to_string());
+        }
+        Ok(Self {
+            entries,
+            cursor: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Intervalo entre a primeira e a última linha, na velocidade original
+    pub fn span(&self) -> Duration {
+        self.entries
+            .iter()
+            .filter_map(|e| e.offset)
+            .max()
+            .unwrap_or_default()
+    }
+
+    // Quando cada linha deve sair, desde o início do teste. `speed` 2.0 = o dobro da velocidade.
+    // Linha sem timestamp sai junto com a anterior.
+    pub fn schedule(&self, speed: f64) -> Result<Vec<Duration>, String> {
+        if !(speed > 0.0 && speed.is_finite()) {
+            return Err(format!(
+                "Velocidade de replay inválida: {} (use um fator > 0 ou 'max')",
+                speed
+            ));
+        }
+        if self.entries.iter().all(|e| e.offset.is_none()) {
+            return Err(
+                "O log não tem timestamps: use --replay-speed max para disparar sem agenda"
+                    .to_string(),
+            );
+        }
+
+        let mut last = Duration::ZERO;
+        Ok(self
+            .entries
+            .iter()
+            .map(|e| {
+                if let Some(offset) = e.offset {
+                    last = offset.div_f64(speed);
+                }
+                last
+            })
+            .collect())
+    }
+
+    // Dispara a próxima linha do log (volta ao começo se o motor pedir mais que o arquivo tem)
+    pub async fn fire(
+        &self,
+        client: &reqwest::Client,
+        global_headers: &[String],
+        expect: Option<&str>,
+    ) -> TargetResult {
+        let entry = &self.entries[self.cursor.fetch_add(1, Ordering::Relaxed) % self.entries.len()];
+        let start = Instant::now();
+
+        let mut req = client.request(entry.method.clone(), &entry.url);
+        // Os -H da linha de comando substituem o header de mesmo nome da captura
+        let overridden = |name: &str| {
+            global_headers.iter().any(|h| {
+                h.split_once(':')
+                    .is_some_and(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+            })
+        };
+        for (k, v) in entry.headers.iter().filter(|(k, _)| !overridden(k)) {
+            req = req.header(k, v);
+        }
+        for h in global_headers {
+            if let Some((k, v)) = h.split_once(':') {
+                req = req.header(k.trim(), v.trim());
+            }
+        }
+        let bytes_sent = entry.body.as_ref().map_or(0, |b| b.len() as u64);
+        if let Some(body) = &entry.body {
+            req = req.body(body.clone());
+        }
+
+        let (res, mut phases) = timing::send(req).await;
+        let resp = match res {
+            Ok(resp) => resp,
+            Err(e) => return TargetResult::fail(start.elapsed(), format!("Network Error: {}", e)),
+        };
+        let status = resp.status().as_u16();
+        let bytes = match timing::read_body(resp, &mut phases).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return TargetResult {
+                    status_code: Some(status),
+                    ..TargetResult::fail(start.elapsed(), format!("Read Error: {}", e))
+                }
+            }
+        };
+
+        let mut error = None;
+        if let Some(expected) = expect {
+            if !String::from_utf8_lossy(&bytes).contains(expected) {
+                error = Some(format!("Mismatch: missing '{}'", expected));
+            }
+        }
+        let assert_ok = error.is_none();
+        TargetResult {
+            success: (200..300).contains(&status) && assert_ok,
+            duration: start.elapsed(),
+            status_code: Some(status),
+            error,
+            bytes_sent,
+            bytes_received: bytes.len() as u64,
+            assertion_success: assert_ok,
+            steps: Vec::new(),
+            phases: Some(phases),
+            datagram: None,
+            ws: None,
+        }
+    }
+}
+
+// Caminho + query da URL capturada, pendurados no --url
+fn retarget(base_url: &str, url: &str) -> String {
+    let path = match url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+    {
+        Some(rest) => rest.find(['/', '?']).map_or("", |i| &rest[i..]),
+        None => url,
+    };
+    let base = base_url.trim_end_matches('/');
+    if path.is_empty() {
+        base.to_string()
+    } else if path.starts_with('?') {
+        format!("{}{}", base, path)
+    } else {
+        format!("{}/{}", base, path.trim_start_matches('/'))
+    }
+}
+
+impl Timestamp {
+    // Segundos desde a epoch
+    fn as_secs(&self) -> Result<f64, String> {
+        match self {
+            // Acima de ~5000 d.C. em segundos só pode ser milissegundo
+            Self::Epoch(v) if *v > 1e11 => Ok(v / 1000.0),
+            Self::Epoch(v) => Ok(*v),
+            Self::Text(text) => match text.trim().parse::<f64>() {
+                Ok(v) => Self::Epoch(v).as_secs(),
+                Err(_) => crate::utils::parse_rfc3339(text.trim()).ok_or_else(|| {
+                    format!("timestamp inválido '{}' (use RFC 3339 ou epoch)", text)
+                }),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, LoadTest};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const LOG: &str = r#"
+{"timestamp":"2024-05-01T12:00:00Z","method":"GET","url":"https://prod.example.com/items?page=2"}
+{"timestamp":"2024-05-01T12:00:00.500Z","method":"post","url":"/orders","headers":{"Content-Type":"application/json","Host":"prod"},"body":"{\"id\":1}"}
+{"url":"/health"}
+{"timestamp":1714564802,"url":"/items/7","headers":["X-Trace: abc"]}
+"#;
+
+    #[test]
+    fn test_parse_log_and_retarget() {
+        let log = ReplayLog::parse(LOG.as_bytes(), "http://staging:8080/").unwrap();
+        assert_eq!(log.len(), 4);
+        assert_eq!(log.entries[0].url, "http://staging:8080/items?page=2");
+        assert_eq!(log.entries[1].method, reqwest::Method::POST);
+        assert_eq!(log.entries[1].headers.len(), 1);
+        assert_eq!(log.entries[2].offset, None);
+        assert_eq!(log.entries[3].headers[0].0, "X-Trace");
+        assert_eq!(log.span(), Duration::from_secs(2));
+
+        assert!(ReplayLog::parse("".as_bytes(), "http://x").is_err());
+        assert!(ReplayLog::parse(r#"{"method":"GET"}"#.as_bytes(), "http://x").is_err());
+    }
+
+    #[test]
+    fn test_schedule_scales_the_original_gaps() {
+        let log = ReplayLog::parse(LOG.as_bytes(), "http://x").unwrap();
+        let ms = |v: u64| Duration::from_millis(v);
+        assert_eq!(
+            log.schedule(1.0).unwrap(),
+            vec![ms(0), ms(500), ms(500), ms(2000)]
+        );
+        assert_eq!(
+            log.schedule(2.0).unwrap(),
+            vec![ms(0), ms(250), ms(250), ms(1000)]
+        );
+        assert!(log.schedule(0.0).is_err());
+
+        let untimed = ReplayLog::parse(r#"{"url":"/a"}"#.as_bytes(), "http://x").unwrap();
+        assert!(untimed.schedule(1.0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_runs_through_the_engine() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                    let status = if req.starts_with("get /health") {
+                        "503 Service Unavailable"
+                    } else {
+                        "200 OK"
+                    };
+                    let resp = format!(
+                        "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                        status
+                    );
+                    let _ = socket.write_all(resp.as_bytes()).await;
+                });
+            }
+        });
+
+        let log = ReplayLog::parse(LOG.as_bytes(), &format!("http://{}", addr)).unwrap();
+        let executor = Executor::replay(2, &log, Some(20.0)).unwrap();
+        let result = LoadTest::builder()
+            .replay(log)
+            .executor(executor)
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(result.successes, 3);
+        assert_eq!(result.status_counts.get(&503), Some(&1));
+        // A agenda (2s / 20) também vira a duração planejada
+        assert_eq!(result.planned_duration, Some(Duration::from_millis(100)));
+    }
+}
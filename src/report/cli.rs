@@ -1,10 +1,21 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use colored::Colorize;
 use hdrhistogram::Histogram;
 use serde::Serialize;
 use tabled::Tabled;
 
+use crate::engine::abort::AbortReport;
+use crate::engine::adaptive::AdaptiveReport;
+use crate::engine::capacity::CapacityReport;
+use crate::metrics::phases::PhaseStats;
+use crate::metrics::timeline::TimelinePoint;
+use crate::metrics::udp::UdpStats;
+use crate::metrics::ws::WsStats;
+use crate::report::thresholds::ThresholdResult;
+use crate::scenario::StepStats;
+
 #[derive(Serialize, Tabled)]
 pub struct FinalReport {
     #[tabled(rename = "Target URL")]
@@ -32,8 +43,169 @@ pub struct FinalReport {
     #[tabled(skip)]
     pub errors: HashMap<String, u64>,
     pub duration_secs: f64,
+    #[tabled(skip)]
+    pub planned_duration_secs: Option<f64>,
+    pub actual_duration_secs: f64,
 
     pub apdex_score: f64,
+
+    // Latência corrigida para coordinated omission (só em --rps / --ramp-up)
+    #[tabled(skip)]
+    pub response_time: Option<LatencyStats>,
+
+    // Modelo aberto (--arrival): chegadas descartadas (sem VU livre) e atrasadas
+    #[tabled(skip)]
+    pub dropped_arrivals: u64,
+    #[tabled(skip)]
+    pub late_arrivals: u64,
+
+    // Ctrl+C: o relatório é parcial e `unsent_requests` diz quanto do plano não saiu
+    #[tabled(skip)]
+    pub interrupted: bool,
+    #[tabled(skip)]
+    pub unsent_requests: Option<u64>,
+    // Regra de parada (--abort-on) que interrompeu o teste
+    #[tabled(skip)]
+    pub abort_reason: Option<AbortReport>,
+
+    // Busca de capacidade (--find-capacity): degraus e a maior taxa aprovada
+    #[tabled(skip)]
+    pub capacity: Option<CapacityReport>,
+
+    // Concorrência adaptativa (--adaptive): trajetória do limite e o joelho da vazão
+    #[tabled(skip)]
+    pub adaptive: Option<AdaptiveReport>,
+
+    // Série temporal por intervalo (--timeline-interval): vazão, erros e percentis ao longo do teste
+    #[tabled(skip)]
+    pub timeline: Vec<TimelinePoint>,
+
+    // Cenários (`scenario:` no YAML): uma entrada por passo da jornada
+    #[tabled(skip)]
+    pub steps: Vec<StepReport>,
+
+    // Mix ponderado (`requests:` no YAML): uma entrada por requisição
+    #[tabled(skip)]
+    pub requests: Vec<StepReport>,
+
+    // Alvos HTTP: latência por fase (DNS, TCP, TLS, TTFB, download) e reuso de conexões
+    #[tabled(skip)]
+    pub phases: Option<PhaseReport>,
+
+    // --mode udp: perda, duplicatas e atrasadas (a latência acima é o RTT com --udp-wait-reply)
+    #[tabled(skip)]
+    pub udp: Option<UdpStats>,
+
+    // --mode ws: handshakes, mensagens por segundo e motivos de desconexão
+    #[tabled(skip)]
+    pub ws: Option<WsReport>,
+
+    // Limites (`thresholds:` no YAML): preenchido pela CLI depois da avaliação
+    #[tabled(skip)]
+    pub thresholds: Vec<ThresholdResult>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StepReport {
+    pub name: String,
+    pub requests: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub status_codes: HashMap<u16, u64>,
+    pub latency: LatencyStats,
+}
+
+impl StepReport {
+    pub fn from_stats(stats: &StepStats) -> Self {
+        Self {
+            name: stats.name.clone(),
+            requests: stats.total(),
+            successes: stats.successes,
+            failures: stats.failures,
+            status_codes: stats.status_counts.clone(),
+            latency: LatencyStats::from_histogram(&stats.histogram),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PhaseReport {
+    // Ausentes quando nenhuma conexão nova (ou nenhuma em https / por nome) foi aberta
+    pub dns: Option<LatencyStats>,
+    pub connect: Option<LatencyStats>,
+    pub tls: Option<LatencyStats>,
+    pub ttfb: LatencyStats,
+    pub download: LatencyStats,
+    pub new_connections: u64,
+    pub reused_connections: u64,
+}
+
+impl PhaseReport {
+    pub fn from_stats(stats: &PhaseStats) -> Self {
+        let optional =
+            |h: &Histogram<u64>| (!h.is_empty()).then(|| LatencyStats::from_histogram(h));
+        Self {
+            dns: optional(&stats.dns),
+            connect: optional(&stats.connect),
+            tls: optional(&stats.tls),
+            ttfb: LatencyStats::from_histogram(&stats.ttfb),
+            download: LatencyStats::from_histogram(&stats.download),
+            new_connections: stats.new_connections,
+            reused_connections: stats.reused_connections,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WsReport {
+    pub connections: u64,
+    pub handshake_failures: u64,
+    // Ausente quando nenhum handshake completou
+    pub setup: Option<LatencyStats>,
+    pub messages_sent: u64,
+    pub replies: u64,
+    pub unmatched: u64,
+    pub messages_per_sec: f64,
+    pub disconnects: HashMap<String, u64>,
+}
+
+impl WsReport {
+    pub fn from_stats(stats: &WsStats, duration: Duration) -> Self {
+        Self {
+            connections: stats.connections,
+            handshake_failures: stats.handshake_failures,
+            setup: (!stats.setup.is_empty()).then(|| LatencyStats::from_histogram(&stats.setup)),
+            messages_sent: stats.messages_sent,
+            replies: stats.replies,
+            unmatched: stats.unmatched,
+            messages_per_sec: stats.messages_sent as f64 / duration.as_secs_f64().max(1e-9),
+            disconnects: stats.disconnects.clone(),
+        }
+    }
+}
+
+// Resumo de um histograma em ms, para os blocos extras do relatório JSON
+#[derive(Serialize, Clone, Debug)]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    pub fn from_histogram(hist: &Histogram<u64>) -> Self {
+        Self {
+            min_ms: to_ms(hist.min()),
+            avg_ms: to_ms(hist.mean() as u64),
+            p50_ms: to_ms(hist.value_at_quantile(0.5)),
+            p95_ms: to_ms(hist.value_at_quantile(0.95)),
+            p99_ms: to_ms(hist.value_at_quantile(0.99)),
+            max_ms: to_ms(hist.max()),
+        }
+    }
 }
 
 #[derive(Tabled)]
@@ -100,6 +272,7 @@ pub fn print_summary(
     successes: u64,
     failures: u64,
     hist: &Histogram<u64>,
+    response_hist: Option<&Histogram<u64>>,
     total: std::time::Duration,
     target_rps: Option<u32>,
     status_counts: std::collections::HashMap<u16, u64>,
@@ -162,6 +335,40 @@ pub fn print_summary(
         .to_string();
 
     println!("{}", table);
+
+    // Service time (acima) vs Response time (desde o instante agendado)
+    if let Some(resp) = response_hist.filter(|h| !h.is_empty()) {
+        println!(
+            "\n{}",
+            "⏳ TEMPO DE RESPOSTA (corrigido p/ coordinated omission)"
+                .bold()
+                .bright_white()
+        );
+        let to_ms_str = |v| format!("{:.2}ms", to_ms(v));
+        let rows: Vec<LatencyMetrics> = percentiles
+            .iter()
+            .map(|&p| LatencyMetrics {
+                metric: format!("p{}", p * 100.0),
+                value: format!(
+                    "{} (serviço {})",
+                    to_ms_str(resp.value_at_quantile(p)),
+                    to_ms_str(hist.value_at_quantile(p))
+                ),
+            })
+            .chain(std::iter::once(LatencyMetrics {
+                metric: "Máximo".to_string(),
+                value: format!(
+                    "{} (serviço {})",
+                    to_ms_str(resp.max()),
+                    to_ms_str(hist.max())
+                ),
+            }))
+            .collect();
+        println!(
+            "{}",
+            tabled::Table::new(rows).with(tabled::settings::Style::modern())
+        );
+    }
     println!(
         "{} {} | {} {} | {} {:?}",
         "✅ Sucessos:".green().bold(),
@@ -265,6 +472,322 @@ pub fn print_summary(
     println!("Teste finalizado em {}s", total.as_secs());
 }
 
+#[derive(Tabled)]
+struct CapacityRow {
+    #[tabled(rename = "Fase")]
+    phase: String,
+    #[tabled(rename = "RPS Alvo")]
+    target: u32,
+    #[tabled(rename = "RPS Real")]
+    achieved: String,
+    #[tabled(rename = "p99")]
+    p99: String,
+    #[tabled(rename = "Erros")]
+    errors: String,
+    #[tabled(rename = "SLO")]
+    verdict: String,
+}
+
+pub fn print_capacity_report(report: &CapacityReport) {
+    println!("\n{}", "🧗 BUSCA DE CAPACIDADE".bold().bright_white());
+    println!(
+        "SLO: p99 ≤ {:.2}ms e erros ≤ {:.2}%",
+        report.slo_p99_ms,
+        report.slo_error_rate * 100.0
+    );
+
+    let rows: Vec<CapacityRow> = report
+        .steps
+        .iter()
+        .map(|s| CapacityRow {
+            phase: s.phase.to_string(),
+            target: s.target_rps,
+            achieved: format!("{:.1}", s.achieved_rps),
+            p99: format!("{:.2}ms", s.p99_ms),
+            errors: format!("{:.2}%", s.error_rate * 100.0),
+            verdict: if s.passed { "✅" } else { "❌" }.to_string(),
+        })
+        .collect();
+    println!(
+        "{}",
+        tabled::Table::new(rows).with(tabled::settings::Style::modern())
+    );
+
+    match report.max_sustainable_rps {
+        Some(rps) => println!(
+            "🏆 Capacidade máxima dentro do SLO: {} RPS",
+            rps.to_string().green().bold()
+        ),
+        None => println!(
+            "{}",
+            "⚠️ Nenhum degrau atendeu o SLO (nem a taxa inicial)"
+                .yellow()
+                .bold()
+        ),
+    }
+}
+
+#[derive(Tabled)]
+struct StepRow {
+    #[tabled(rename = "Nome")]
+    name: String,
+    #[tabled(rename = "Reqs")]
+    requests: u64,
+    #[tabled(rename = "Falhas")]
+    failures: u64,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "p50")]
+    p50: String,
+    #[tabled(rename = "p95")]
+    p95: String,
+    #[tabled(rename = "p99")]
+    p99: String,
+}
+
+// Serve aos passos do cenário e às requisições do mix; só o título muda
+pub fn print_steps_report(title: &str, steps: &[StepStats]) {
+    println!("\n{}", title.bold().bright_white());
+
+    let to_ms_str = |v| format!("{:.2}ms", to_ms(v));
+    let rows: Vec<StepRow> = steps
+        .iter()
+        .map(|s| StepRow {
+            name: s.name.clone(),
+            requests: s.total(),
+            failures: s.failures,
+            status: {
+                let mut codes: Vec<_> = s.status_counts.iter().collect();
+                codes.sort();
+                codes
+                    .iter()
+                    .map(|(code, count)| format!("{}×{}", code, count))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            },
+            p50: to_ms_str(s.histogram.value_at_quantile(0.5)),
+            p95: to_ms_str(s.histogram.value_at_quantile(0.95)),
+            p99: to_ms_str(s.histogram.value_at_quantile(0.99)),
+        })
+        .collect();
+    println!(
+        "{}",
+        tabled::Table::new(rows).with(tabled::settings::Style::modern())
+    );
+}
+
+#[derive(Tabled)]
+struct PhaseRow {
+    #[tabled(rename = "Fase")]
+    phase: &'static str,
+    #[tabled(rename = "Amostras")]
+    samples: u64,
+    #[tabled(rename = "Média")]
+    avg: String,
+    #[tabled(rename = "p50")]
+    p50: String,
+    #[tabled(rename = "p95")]
+    p95: String,
+    #[tabled(rename = "p99")]
+    p99: String,
+    #[tabled(rename = "Máximo")]
+    max: String,
+}
+
+pub fn print_phases_report(stats: &PhaseStats) {
+    println!(
+        "\n{}",
+        "🔬 FASES DA REQUISIÇÃO (DNS / TCP / TLS só em conexões novas)"
+            .bold()
+            .bright_white()
+    );
+
+    let to_ms_str = |v| format!("{:.2}ms", to_ms(v));
+    let rows: Vec<PhaseRow> = [
+        ("DNS", &stats.dns),
+        ("Conexão TCP", &stats.connect),
+        ("Handshake TLS", &stats.tls),
+        ("TTFB (servidor)", &stats.ttfb),
+        ("Download do corpo", &stats.download),
+    ]
+    .into_iter()
+    .filter(|(_, h)| !h.is_empty())
+    .map(|(phase, h)| PhaseRow {
+        phase,
+        samples: h.len(),
+        avg: to_ms_str(h.mean() as u64),
+        p50: to_ms_str(h.value_at_quantile(0.5)),
+        p95: to_ms_str(h.value_at_quantile(0.95)),
+        p99: to_ms_str(h.value_at_quantile(0.99)),
+        max: to_ms_str(h.max()),
+    })
+    .collect();
+    println!(
+        "{}",
+        tabled::Table::new(rows).with(tabled::settings::Style::modern())
+    );
+
+    let total = stats.new_connections + stats.reused_connections;
+    println!(
+        "🔌 Conexões: {} novas, {} reutilizadas ({:.1}% de reuso)",
+        stats.new_connections.to_string().yellow(),
+        stats.reused_connections.to_string().green(),
+        stats.reused_connections as f64 / total.max(1) as f64 * 100.0
+    );
+}
+
+pub fn print_udp_report(stats: &UdpStats) {
+    println!("\n{}", "📡 DATAGRAMAS (UDP)".bold().bright_white());
+    if stats.awaited == 0 {
+        println!(
+            "Enviados: {} (sem espera por resposta; use --udp-wait-reply para medir RTT e perda)",
+            stats.sent.to_string().cyan()
+        );
+        return;
+    }
+    let loss = stats.loss_rate() * 100.0;
+    let loss_text = format!("{:.2}%", loss);
+    println!(
+        "Enviados: {} | Respostas: {} | Perdidos: {} ({})",
+        stats.sent.to_string().cyan(),
+        stats.replies.to_string().green(),
+        stats.lost.to_string().red(),
+        if loss > 0.0 {
+            loss_text.red()
+        } else {
+            loss_text.green()
+        }
+    );
+    println!(
+        "Respostas duplicadas: {} | Atrasadas (depois do prazo): {}",
+        stats.duplicates.to_string().yellow(),
+        stats.late.to_string().yellow()
+    );
+}
+
+// As mensagens contam desde o começo (as conexões abrem no warm-up), então a taxa usa a
+// duração total
+pub fn print_ws_report(stats: &WsStats, duration: Duration) {
+    println!("\n{}", "🔗 WEBSOCKET".bold().bright_white());
+    let report = WsReport::from_stats(stats, duration);
+    println!(
+        "Conexões: {} | Falhas de handshake: {}",
+        report.connections.to_string().cyan(),
+        if report.handshake_failures > 0 {
+            report.handshake_failures.to_string().red()
+        } else {
+            report.handshake_failures.to_string().green()
+        }
+    );
+    if let Some(setup) = &report.setup {
+        println!(
+            "Handshake: média {:.2}ms | p50 {:.2}ms | p95 {:.2}ms | p99 {:.2}ms | máx {:.2}ms",
+            setup.avg_ms, setup.p50_ms, setup.p95_ms, setup.p99_ms, setup.max_ms
+        );
+    }
+    println!(
+        "Mensagens: {} enviadas ({:.1}/s) | {} respostas | {} sem correspondência",
+        report.messages_sent.to_string().cyan(),
+        report.messages_per_sec,
+        report.replies.to_string().green(),
+        report.unmatched.to_string().yellow()
+    );
+    if report.disconnects.is_empty() {
+        println!("Desconexões: {}", "nenhuma".green());
+        return;
+    }
+    let mut reasons: Vec<_> = report.disconnects.iter().collect();
+    reasons.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    println!("Desconexões:");
+    for (reason, n) in reasons {
+        println!("  {} × {}", n.to_string().red(), reason);
+    }
+}
+
+#[derive(Tabled)]
+struct ThresholdRow {
+    #[tabled(rename = "Limite")]
+    check: String,
+    #[tabled(rename = "Medido")]
+    actual: String,
+    #[tabled(rename = "Resultado")]
+    verdict: String,
+}
+
+pub fn print_thresholds_report(results: &[ThresholdResult]) {
+    println!("\n{}", "📏 THRESHOLDS".bold().bright_white());
+
+    let rows: Vec<ThresholdRow> = results
+        .iter()
+        .map(|t| ThresholdRow {
+            check: t.check.clone(),
+            actual: match t.actual {
+                Some(v) => format!("{:.2}{}", v, t.unit),
+                None => "sem dados".to_string(),
+            },
+            verdict: match (t.passed, t.aborted_run) {
+                (true, _) => "✅".to_string(),
+                (false, true) => "❌ (abortou o teste)".to_string(),
+                (false, false) => "❌".to_string(),
+            },
+        })
+        .collect();
+    println!(
+        "{}",
+        tabled::Table::new(rows).with(tabled::settings::Style::modern())
+    );
+
+    let failed = results.iter().filter(|t| !t.passed).count();
+    if failed == 0 {
+        println!("{}", "✅ Todos os thresholds passaram".green().bold());
+    } else {
+        println!(
+            "{}",
+            format!("❌ {} de {} threshold(s) falharam", failed, results.len())
+                .red()
+                .bold()
+        );
+    }
+}
+
+pub fn print_adaptive_report(report: &AdaptiveReport) {
+    println!(
+        "\n{}",
+        format!("🎛️ CONCORRÊNCIA ADAPTATIVA ({})", report.algorithm)
+            .bold()
+            .bright_white()
+    );
+
+    // Uma linha por mudança de limite, para a trajetória caber no terminal
+    let mut last = None;
+    for s in &report.trajectory {
+        if last == Some(s.concurrency) {
+            continue;
+        }
+        last = Some(s.concurrency);
+        println!(
+            "  {:>7.1}s  {:>5} workers  {:>9.1} RPS  {:>8.2}ms  {:>5.1}% erros",
+            s.elapsed_secs,
+            s.concurrency.to_string().magenta(),
+            s.rps,
+            s.avg_latency_ms,
+            s.error_rate * 100.0
+        );
+    }
+
+    match report.knee_concurrency {
+        Some(knee) => println!(
+            "🏔️ A vazão parou de crescer em {} workers (pico de {:.1} RPS)",
+            knee.to_string().green().bold(),
+            report.max_throughput_rps
+        ),
+        None => println!(
+            "{}",
+            "⚠️ Nenhuma janela completa para estimar o joelho".yellow()
+        ),
+    }
+}
+
 pub fn print_banner() {
     let banner = r#"
       _____          _   _ _   _  ____  _   _ 
@@ -296,4 +819,16 @@ mod tests {
         assert_eq!(to_ms(1_500_000), 1500.0);
         assert_eq!(to_ms(0), 0.0);
     }
+
+    #[test]
+    fn test_latency_stats_from_histogram() {
+        let mut hist = Histogram::<u64>::new(3).unwrap();
+        for us in [500, 1_000, 1_500, 2_000] {
+            hist.record(us).unwrap();
+        }
+        let stats = LatencyStats::from_histogram(&hist);
+        assert_eq!(stats.min_ms, 0.5);
+        assert_eq!(stats.p50_ms, 1.0);
+        assert_eq!(stats.max_ms, 2.0);
+    }
 }
@@ -0,0 +1,448 @@
+// src/report/thresholds.rs
+//
+// Limites declarativos (`thresholds:` no YAML), avaliados no fim do teste:
+//   "p95 < 200ms", "error_rate < 1%", "rps > 900", "apdex > 0.9"
+// Com escopo entre chaves: "p99{request:checkout} < 1s", "rate{status:5xx} < 0.5%"
+
+use crate::args::parser::ThresholdConfig;
+use crate::load_test::{apdex, LoadTestResult};
+use hdrhistogram::Histogram;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    // Quantil (0..1) da latência
+    Percentile(f64),
+    Avg,
+    Min,
+    Max,
+    ErrorRate,
+    // Fração das requisições que caíram no status do escopo
+    Rate,
+    Rps,
+    Apdex,
+    Count,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatusMatch {
+    Exact(u16),
+    // 5 = 5xx
+    Class(u16),
+}
+
+impl StatusMatch {
+    fn matches(&self, code: u16) -> bool {
+        match self {
+            StatusMatch::Exact(c) => code == *c,
+            StatusMatch::Class(c) => code / 100 == *c,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Threshold {
+    pub check: String,
+    metric: Metric,
+    request: Option<String>,
+    status: Option<StatusMatch>,
+    op: Op,
+    limit: f64,
+    pub abort_on_fail: bool,
+    pub abort_delay: Duration,
+}
+
+// Resultado de um limite no relatório. `actual` está na unidade de `unit` (ms, % ou nenhuma).
+#[derive(Serialize, Clone, Debug)]
+pub struct ThresholdResult {
+    pub check: String,
+    pub actual: Option<f64>,
+    pub unit: &'static str,
+    pub passed: bool,
+    // Foi este limite que interrompeu o teste (abort_on_fail)
+    pub aborted_run: bool,
+}
+
+// O recorte das métricas que um limite enxerga: o teste todo ou uma requisição / passo
+struct View<'a> {
+    histogram: &'a Histogram<u64>,
+    successes: u64,
+    failures: u64,
+    status_counts: &'a HashMap<u16, u64>,
+}
+
+impl Threshold {
+    pub fn parse(conf: &ThresholdConfig) -> Result<Self, String> {
+        let (check, abort_on_fail, abort_delay) = match conf {
+            ThresholdConfig::Check(check) => (check, false, None),
+            ThresholdConfig::Full {
+                check,
+                abort_on_fail,
+                abort_delay,
+            } => (check, *abort_on_fail, abort_delay.as_deref()),
+        };
+        let invalid = |why: &str| format!("Threshold inválido '{}': {}", check, why);
+
+        let (pos, op, op_len) = ["<=", ">=", "==", "<", ">"]
+            .iter()
+            .find_map(|sym| check.find(sym).map(|pos| (pos, *sym, sym.len())))
+            .ok_or_else(|| invalid("faltou o operador (<, <=, >, >=, ==)"))?;
+        let op = match op {
+            "<=" => Op::Le,
+            ">=" => Op::Ge,
+            "==" => Op::Eq,
+            "<" => Op::Lt,
+            _ => Op::Gt,
+        };
+        let lhs = check[..pos].trim();
+        let rhs = check[pos + op_len..].trim();
+
+        let (name, scope) = match lhs.split_once('{') {
+            Some((name, rest)) => (
+                name.trim(),
+                Some(
+                    rest.strip_suffix('}')
+                        .ok_or_else(|| invalid("faltou fechar o '}'"))?,
+                ),
+            ),
+            None => (lhs, None),
+        };
+        let metric = match name.to_lowercase().as_str() {
+            "avg" => Metric::Avg,
+            "min" => Metric::Min,
+            "max" => Metric::Max,
+            "error_rate" => Metric::ErrorRate,
+            "rate" => Metric::Rate,
+            "rps" => Metric::Rps,
+            "apdex" => Metric::Apdex,
+            "count" | "requests" => Metric::Count,
+            other => match other.strip_prefix('p').and_then(|p| p.parse::<f64>().ok()) {
+                Some(p) if p > 0.0 && p <= 100.0 => Metric::Percentile(p / 100.0),
+                _ => return Err(invalid(&format!("métrica desconhecida '{}'", name))),
+            },
+        };
+
+        let (mut request, mut status) = (None, None);
+        for part in scope.into_iter().flat_map(|s| s.split(',')) {
+            let (key, value) = part
+                .split_once(':')
+                .or_else(|| part.split_once('='))
+                .ok_or_else(|| invalid(&format!("escopo '{}' sem chave:valor", part)))?;
+            let value = value.trim();
+            match key.trim() {
+                "request" | "step" => request = Some(value.to_string()),
+                "status" => {
+                    status =
+                        Some(parse_status(value).ok_or_else(|| {
+                            invalid(&format!("status '{}' (use 503 ou 5xx)", value))
+                        })?)
+                }
+                other => return Err(invalid(&format!("escopo desconhecido '{}'", other))),
+            }
+        }
+        // Por status só existem contagens: latência e vazão não são separadas por código
+        match (metric, status) {
+            (Metric::Rate, None) => return Err(invalid("rate precisa de {status:...}")),
+            (Metric::Rate | Metric::Count, _) | (_, None) => {}
+            _ => return Err(invalid("com {status:...} só valem rate e count")),
+        }
+
+        let limit = parse_limit(metric, rhs).ok_or_else(|| invalid("valor do limite"))?;
+
+        // Em tempo real só há os contadores atômicos (também no modo distribuído)
+        if abort_on_fail && (metric != Metric::ErrorRate || request.is_some() || status.is_some()) {
+            return Err(invalid(
+                "abort_on_fail só vale para error_rate sem escopo (os demais precisam do histograma final)",
+            ));
+        }
+        let abort_delay = match abort_delay {
+            Some(d) => crate::utils::parse_duration(d).map_err(|e| invalid(&e))?,
+            None => Duration::from_secs(10),
+        };
+
+        Ok(Self {
+            check: check.clone(),
+            metric,
+            request,
+            status,
+            op,
+            limit,
+            abort_on_fail,
+            abort_delay,
+        })
+    }
+
+    // Nome da requisição (mix) ou passo (cenário) do escopo, se houver
+    pub fn request(&self) -> Option<&str> {
+        self.request.as_deref()
+    }
+
+    // Avaliação antecipada (abort_on_fail) com os contadores do teste em andamento
+    pub fn tripped_live(&self, successes: u64, failures: u64, elapsed: Duration) -> bool {
+        let total = successes + failures;
+        if !self.abort_on_fail || elapsed < self.abort_delay || total == 0 {
+            return false;
+        }
+        !self.compare(failures as f64 / total as f64)
+    }
+
+    fn compare(&self, actual: f64) -> bool {
+        match self.op {
+            Op::Lt => actual < self.limit,
+            Op::Le => actual <= self.limit,
+            Op::Gt => actual > self.limit,
+            Op::Ge => actual >= self.limit,
+            Op::Eq => (actual - self.limit).abs() < f64::EPSILON,
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self.metric {
+            Metric::Percentile(_) | Metric::Avg | Metric::Min | Metric::Max => "ms",
+            Metric::ErrorRate | Metric::Rate => "%",
+            Metric::Rps | Metric::Apdex | Metric::Count => "",
+        }
+    }
+
+    // Valor da métrica na unidade do limite (ms, fração ou número). None = sem dados.
+    fn value(&self, view: &View, duration: Duration, apdex_t_ms: u64) -> Option<f64> {
+        let hist = view.histogram;
+        let total = view.successes + view.failures;
+        let in_status = |m: &StatusMatch| -> u64 {
+            view.status_counts
+                .iter()
+                .filter(|(code, _)| m.matches(**code))
+                .map(|(_, n)| n)
+                .sum()
+        };
+        let latency = |us: f64| (!hist.is_empty()).then(|| us / 1000.0);
+
+        match self.metric {
+            Metric::Percentile(q) => latency(hist.value_at_quantile(q) as f64),
+            Metric::Avg => latency(hist.mean()),
+            Metric::Min => latency(hist.min() as f64),
+            Metric::Max => latency(hist.max() as f64),
+            Metric::ErrorRate => (total > 0).then(|| view.failures as f64 / total as f64),
+            Metric::Rate => {
+                let m = self.status.as_ref()?;
+                (total > 0).then(|| in_status(m) as f64 / total as f64)
+            }
+            Metric::Count => Some(match &self.status {
+                Some(m) => in_status(m),
+                None => total,
+            } as f64),
+            Metric::Rps => {
+                (!duration.is_zero()).then(|| view.successes as f64 / duration.as_secs_f64())
+            }
+            Metric::Apdex => (!hist.is_empty()).then(|| apdex(hist, apdex_t_ms)),
+        }
+    }
+}
+
+fn parse_status(value: &str) -> Option<StatusMatch> {
+    let lower = value.to_lowercase();
+    match lower.strip_suffix("xx") {
+        Some(class) => class.parse().ok().map(StatusMatch::Class),
+        None => lower.parse().ok().map(StatusMatch::Exact),
+    }
+}
+
+// Latência em ms (aceita us/ms/s, sem unidade = ms); taxas em fração (aceita "1%")
+fn parse_limit(metric: Metric, raw: &str) -> Option<f64> {
+    let raw = raw.trim();
+    let number = |s: &str| s.trim().parse::<f64>().ok();
+    match metric {
+        Metric::Percentile(_) | Metric::Avg | Metric::Min | Metric::Max => {
+            if let Some(v) = raw.strip_suffix("ms") {
+                number(v)
+            } else if let Some(v) = raw.strip_suffix("us").or_else(|| raw.strip_suffix("µs")) {
+                number(v).map(|v| v / 1000.0)
+            } else if let Some(v) = raw.strip_suffix('s') {
+                number(v).map(|v| v * 1000.0)
+            } else {
+                number(raw)
+            }
+        }
+        Metric::ErrorRate | Metric::Rate => match raw.strip_suffix('%') {
+            Some(v) => number(v).map(|v| v / 100.0),
+            None => number(raw),
+        },
+        Metric::Rps | Metric::Apdex | Metric::Count => number(raw),
+    }
+}
+
+// Avalia todos os limites. `aborted_by` é o índice do limite que interrompeu o teste.
+pub fn evaluate(
+    thresholds: &[Threshold],
+    result: &LoadTestResult,
+    apdex_t_ms: u64,
+    aborted_by: Option<usize>,
+) -> Vec<ThresholdResult> {
+    let duration = result.stable_duration();
+    thresholds
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let view = match &t.request {
+                // Tudo da mesma janela (fora do warm-up), como nos passos abaixo
+                None => Some(View {
+                    histogram: &result.histogram,
+                    successes: result.measured_successes,
+                    failures: result.measured_failures,
+                    status_counts: &result.status_counts,
+                }),
+                Some(name) => result
+                    .requests
+                    .iter()
+                    .chain(&result.steps)
+                    .find(|s| &s.name == name)
+                    .map(|s| View {
+                        histogram: &s.histogram,
+                        successes: s.successes,
+                        failures: s.failures,
+                        status_counts: &s.status_counts,
+                    }),
+            };
+            let actual = view.and_then(|v| t.value(&v, duration, apdex_t_ms));
+            let unit = t.unit();
+            ThresholdResult {
+                check: t.check.clone(),
+                actual: actual.map(|v| if unit == "%" { v * 100.0 } else { v }),
+                unit,
+                passed: actual.is_some_and(|v| t.compare(v)),
+                aborted_run: aborted_by == Some(i),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(check: &str) -> Result<Threshold, String> {
+        Threshold::parse(&ThresholdConfig::Check(check.to_string()))
+    }
+
+    #[test]
+    fn test_parse_checks_and_scopes() {
+        let t = parse("p95 < 200ms").unwrap();
+        assert_eq!(t.metric, Metric::Percentile(0.95));
+        assert_eq!((t.op, t.limit), (Op::Lt, 200.0));
+
+        assert_eq!(parse("p99.9 <= 1.5s").unwrap().limit, 1500.0);
+        assert_eq!(parse("error_rate < 1%").unwrap().limit, 0.01);
+        assert_eq!(parse("apdex>=0.9").unwrap().op, Op::Ge);
+
+        let t = parse("rate{status:5xx} < 0.5%").unwrap();
+        assert_eq!(t.status, Some(StatusMatch::Class(5)));
+        let t = parse("p99{request:checkout} < 1s").unwrap();
+        assert_eq!(t.request(), Some("checkout"));
+
+        assert!(parse("p95 200ms").is_err());
+        assert!(parse("latency < 1").is_err());
+        assert!(parse("rate < 1%").is_err());
+        assert!(parse("p95{status:5xx} < 1").is_err());
+        assert!(Threshold::parse(&ThresholdConfig::Full {
+            check: "p95 < 1".to_string(),
+            abort_on_fail: true,
+            abort_delay: None,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_values_follow_the_view() {
+        let mut histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap();
+        for ms in 1..=100 {
+            histogram.record(ms * 1000).unwrap();
+        }
+        let status_counts = HashMap::from([(200, 100), (503, 25)]);
+        let view = View {
+            histogram: &histogram,
+            successes: 100,
+            failures: 25,
+            status_counts: &status_counts,
+        };
+        let value = |check: &str| {
+            parse(check)
+                .unwrap()
+                .value(&view, Duration::from_secs(10), 50)
+                .unwrap()
+        };
+
+        assert!((value("p95 < 1") - 95.0).abs() < 0.1);
+        assert_eq!(value("error_rate < 1%"), 0.2);
+        assert_eq!(value("rate{status:5xx} < 1%"), 0.2);
+        assert_eq!(value("count{status:200} > 1"), 100.0);
+        assert_eq!(value("rps > 1"), 10.0);
+
+        let live = Threshold::parse(&ThresholdConfig::Full {
+            check: "error_rate < 10%".to_string(),
+            abort_on_fail: true,
+            abort_delay: Some("5s".to_string()),
+        })
+        .unwrap();
+        assert!(!live.tripped_live(80, 20, Duration::from_secs(1)));
+        assert!(live.tripped_live(80, 20, Duration::from_secs(6)));
+        assert!(!live.tripped_live(95, 5, Duration::from_secs(6)));
+    }
+
+    // Falha sem status nos primeiros 100ms (dentro do warm-up), depois só 200
+    struct ColdStart(std::time::Instant);
+
+    #[async_trait::async_trait]
+    impl crate::client::protocol::Protocol for ColdStart {
+        async fn fire(&self, _payload: &[u8]) -> crate::client::target::TargetResult {
+            if self.0.elapsed() < Duration::from_millis(100) {
+                return crate::client::target::TargetResult::fail(
+                    Duration::from_micros(100),
+                    "Connection refused".to_string(),
+                );
+            }
+            let mut res =
+                crate::client::target::TargetResult::success(Duration::from_micros(100), 0, 0);
+            res.status_code = Some(200);
+            res
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rates_ignore_the_warmup() {
+        let result = crate::LoadTest::builder()
+            .protocol(std::sync::Arc::new(ColdStart(std::time::Instant::now())))
+            .executor(crate::Executor::constant_rate(
+                2,
+                200,
+                Duration::from_millis(600),
+            ))
+            .warmup(Duration::from_millis(250))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert!(result.failures > 0);
+
+        let checks = [
+            "rate{status:200} == 100%",
+            "error_rate == 0",
+            "count{status:200} > 1",
+        ]
+        .map(|c| parse(c).unwrap());
+        let results = evaluate(&checks, &result, 50, None);
+        assert!(results.iter().all(|r| r.passed), "{:?}", results);
+        assert_eq!(results[2].actual, Some(result.measured_successes as f64));
+    }
+}
@@ -0,0 +1,163 @@
+// src/scenario/extract.rs
+
+use crate::args::parser::ExtractConfig;
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+
+enum Source {
+    // Já convertido para JSON Pointer (RFC 6901): "$.data.items[0].id" → "/data/items/0/id"
+    Json(String),
+    // Com grupo de captura, vale o primeiro grupo; sem, o match inteiro
+    Regex(Regex),
+    Header(String),
+}
+
+// Tira um valor da resposta e guarda na variável `var` do usuário virtual
+pub struct Extractor {
+    pub var: String,
+    source: Source,
+}
+
+impl Extractor {
+    pub fn parse(var: &str, conf: &ExtractConfig) -> Result<Self, String> {
+        let source = match (&conf.json, &conf.regex, &conf.header) {
+            (Some(path), None, None) => Source::Json(json_pointer(path)?),
+            (None, Some(pattern), None) => Source::Regex(
+                Regex::new(pattern).map_err(|e| format!("Regex inválida em '{}': {}", var, e))?,
+            ),
+            (None, None, Some(name)) => Source::Header(name.clone()),
+            _ => {
+                return Err(format!(
+                    "A extração '{}' precisa de exatamente uma fonte: json, regex ou header",
+                    var
+                ))
+            }
+        };
+        Ok(Self {
+            var: var.to_string(),
+            source,
+        })
+    }
+
+    // O corpo só é parseado como JSON se algum extrator pedir
+    pub fn needs_json(&self) -> bool {
+        matches!(self.source, Source::Json(_))
+    }
+
+    pub fn extract(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        json: Option<&Value>,
+    ) -> Option<String> {
+        match &self.source {
+            Source::Json(pointer) => match json?.pointer(pointer)? {
+                Value::Null => None,
+                Value::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            },
+            Source::Regex(re) => {
+                let text = String::from_utf8_lossy(body);
+                let caps = re.captures(&text)?;
+                caps.get(1)
+                    .or_else(|| caps.get(0))
+                    .map(|m| m.as_str().to_string())
+            }
+            Source::Header(name) => headers
+                .get(name.as_str())?
+                .to_str()
+                .ok()
+                .map(str::to_string),
+        }
+    }
+}
+
+// Caminho no estilo JSONPath simples ($.a.b[0].c) para JSON Pointer
+fn json_pointer(path: &str) -> Result<String, String> {
+    let trimmed = path.trim();
+    let rest = trimmed
+        .strip_prefix('$')
+        .unwrap_or(trimmed)
+        .trim_start_matches('.');
+    let mut pointer = String::new();
+
+    for segment in rest.split('.').filter(|s| !s.is_empty()) {
+        let (key, mut indexes) = match segment.find('[') {
+            Some(i) => segment.split_at(i),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            pointer.push('/');
+            pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+        }
+        while let Some(open) = indexes.strip_prefix('[') {
+            let (index, tail) = open
+                .split_once(']')
+                .ok_or_else(|| format!("Caminho JSON inválido: '{}'", path))?;
+            index
+                .parse::<usize>()
+                .map_err(|_| format!("Índice inválido '{}' em '{}'", index, path))?;
+            pointer.push('/');
+            pointer.push_str(index);
+            indexes = tail;
+        }
+        if !indexes.is_empty() {
+            return Err(format!("Caminho JSON inválido: '{}'", path));
+        }
+    }
+
+    Ok(pointer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conf(json: Option<&str>, regex: Option<&str>, header: Option<&str>) -> ExtractConfig {
+        ExtractConfig {
+            json: json.map(str::to_string),
+            regex: regex.map(str::to_string),
+            header: header.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_json_path_becomes_pointer() {
+        assert_eq!(
+            json_pointer("$.data.items[0].id").unwrap(),
+            "/data/items/0/id"
+        );
+        assert_eq!(json_pointer("token").unwrap(), "/token");
+        assert_eq!(json_pointer("$").unwrap(), "");
+        assert!(json_pointer("$.items[x]").is_err());
+    }
+
+    #[test]
+    fn test_extract_from_json_regex_and_header() {
+        let body = br#"{"data":{"items":[{"id":42,"sku":"A-1"}]},"token":"abc"}"#;
+        let json: Value = serde_json::from_slice(body).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("location", "/orders/7".parse().unwrap());
+
+        let id = Extractor::parse("id", &conf(Some("$.data.items[0].id"), None, None)).unwrap();
+        assert_eq!(
+            id.extract(&headers, body, Some(&json)).as_deref(),
+            Some("42")
+        );
+
+        let sku = Extractor::parse("sku", &conf(None, Some(r#""sku":"([^"]+)""#), None)).unwrap();
+        assert_eq!(sku.extract(&headers, body, None).as_deref(), Some("A-1"));
+
+        let loc = Extractor::parse("loc", &conf(None, None, Some("Location"))).unwrap();
+        assert_eq!(
+            loc.extract(&headers, body, None).as_deref(),
+            Some("/orders/7")
+        );
+
+        let missing = Extractor::parse("x", &conf(Some("$.nope"), None, None)).unwrap();
+        assert!(missing.extract(&headers, body, Some(&json)).is_none());
+
+        assert!(Extractor::parse("both", &conf(Some("$.a"), Some("a"), None)).is_err());
+    }
+}
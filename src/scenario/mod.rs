@@ -0,0 +1,583 @@
+// src/scenario/mod.rs
+//
+// Jornadas em passos (login → lista → item → checkout). Cada disparo do motor é um usuário
+// virtual percorrendo todos os passos em ordem, com as suas próprias variáveis: o que um passo
+// extrai da resposta entra na URL, nos headers e no corpo dos passos seguintes.
+//
+// O mix ponderado (`requests:`) usa a mesma estrutura: cada disparo sorteia um único passo.
+
+pub mod extract;
+
+use crate::args::parser::{RequestConfig, StepConfig};
+use crate::client::target::{StepResult, TargetResult};
+use crate::client::timing;
+use crate::payload::generator::PayloadTemplate;
+use extract::Extractor;
+use hdrhistogram::Histogram;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+struct Step {
+    name: String,
+    method: reqwest::Method,
+    url: Arc<PayloadTemplate>,
+    headers: Vec<Arc<PayloadTemplate>>,
+    body: Option<Arc<PayloadTemplate>>,
+    expect: Option<String>,
+    extract: Vec<Extractor>,
+    think_time: Option<Duration>,
+}
+
+pub struct Scenario {
+    steps: Vec<Step>,
+    // Mix ponderado (`requests:`): pesos acumulados. Sem isto, os passos rodam em sequência.
+    cumulative_weights: Option<Vec<u32>>,
+}
+
+impl Scenario {
+    // URLs relativas são resolvidas contra `base_url` (o --url)
+    pub fn from_config(base_url: &str, configs: &[StepConfig]) -> Result<Self, String> {
+        if configs.is_empty() {
+            return Err("O cenário precisa de pelo menos um passo".to_string());
+        }
+
+        let steps = configs
+            .iter()
+            .enumerate()
+            .map(|(i, conf)| Step::from_config(base_url, i, conf))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            steps,
+            cumulative_weights: None,
+        })
+    }
+
+    // Cada disparo sorteia UMA das requisições, na proporção dos pesos
+    pub fn mix(base_url: &str, configs: &[RequestConfig]) -> Result<Self, String> {
+        if configs.is_empty() {
+            return Err("A lista requests: precisa de pelo menos uma requisição".to_string());
+        }
+
+        let mut steps = Vec::with_capacity(configs.len());
+        let mut cumulative = Vec::with_capacity(configs.len());
+        let mut total: u32 = 0;
+        for (i, conf) in configs.iter().enumerate() {
+            steps.push(Step::from_config(base_url, i, &conf.as_step())?);
+            total = total
+                .checked_add(conf.weight)
+                .ok_or("A soma dos pesos estourou o limite")?;
+            cumulative.push(total);
+        }
+        if total == 0 {
+            return Err("Pelo menos uma requisição precisa de peso maior que zero".to_string());
+        }
+
+        Ok(Self {
+            steps,
+            cumulative_weights: Some(cumulative),
+        })
+    }
+
+    pub fn is_mix(&self) -> bool {
+        self.cumulative_weights.is_some()
+    }
+
+    pub fn step_names(&self) -> Vec<String> {
+        self.steps.iter().map(|s| s.name.clone()).collect()
+    }
+
+    // Uma jornada completa. Para no primeiro passo que falhar (os seguintes dependem dele);
+    // o resultado agregado leva a duração total e o status do último passo executado.
+    pub async fn run(&self, client: &reqwest::Client, headers: &[String]) -> TargetResult {
+        let start = Instant::now();
+        let mut vars = HashMap::new();
+        let mut buffer = Vec::with_capacity(1024);
+
+        if let Some(cumulative) = &self.cumulative_weights {
+            let ticket = fastrand::u32(0..*cumulative.last().unwrap());
+            let index = cumulative.partition_point(|&w| w <= ticket);
+            let step = &self.steps[index];
+            let mut res = step.fire(client, headers, &mut vars, &mut buffer).await;
+            // As fases já vão no resultado do disparo
+            res.steps.push(StepResult {
+                index,
+                success: res.success,
+                duration: res.duration,
+                status_code: res.status_code,
+                phases: None,
+            });
+            res.error = res.error.map(|e| format!("{}: {}", step.name, e));
+            return res;
+        }
+
+        let mut steps = Vec::with_capacity(self.steps.len());
+        let (mut bytes_sent, mut bytes_received) = (0, 0);
+        // Tempo parado nos think times: sai da duração da jornada
+        let mut thinking = Duration::ZERO;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            if let Some(pause) = step.think_time {
+                tokio::time::sleep(pause).await;
+                thinking += pause;
+            }
+            let res = step.fire(client, headers, &mut vars, &mut buffer).await;
+            bytes_sent += res.bytes_sent;
+            bytes_received += res.bytes_received;
+            steps.push(StepResult {
+                index,
+                success: res.success,
+                duration: res.duration,
+                status_code: res.status_code,
+                phases: res.phases,
+            });
+
+            if !res.success {
+                return TargetResult {
+                    success: false,
+                    duration: start.elapsed().saturating_sub(thinking),
+                    status_code: res.status_code,
+                    error: Some(format!(
+                        "{}: {}",
+                        step.name,
+                        res.error
+                            .unwrap_or_else(|| format!("HTTP {}", res.status_code.unwrap_or(0)))
+                    )),
+                    bytes_sent,
+                    bytes_received,
+                    assertion_success: res.assertion_success,
+                    steps,
+                    phases: None,
+                    datagram: None,
+                    ws: None,
+                };
+            }
+        }
+
+        TargetResult {
+            status_code: steps.last().and_then(|s| s.status_code),
+            steps,
+            ..TargetResult::success(
+                start.elapsed().saturating_sub(thinking),
+                bytes_sent,
+                bytes_received,
+            )
+        }
+    }
+}
+
+impl Step {
+    fn from_config(base_url: &str, position: usize, conf: &StepConfig) -> Result<Self, String> {
+        let method =
+            reqwest::Method::from_bytes(conf.method.to_uppercase().as_bytes()).map_err(|_| {
+                format!(
+                    "Método inválido '{}' no passo {}",
+                    conf.method,
+                    position + 1
+                )
+            })?;
+        let url = if conf.url.starts_with("http://") || conf.url.starts_with("https://") {
+            conf.url.clone()
+        } else {
+            format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                conf.url.trim_start_matches('/')
+            )
+        };
+        let extract = conf
+            .extract
+            .iter()
+            .map(|(var, source)| Extractor::parse(var, source))
+            .collect::<Result<Vec<_>, _>>()?;
+        let think_time = conf
+            .think_time
+            .as_deref()
+            .map(crate::utils::parse_duration)
+            .transpose()
+            .map_err(|e| format!("think_time inválido no passo {}: {}", position + 1, e))?;
+
+        let template = |text: &str| {
+            PayloadTemplate::try_parse(text)
+                .map_err(|e| format!("Template inválido no passo {}: {}", position + 1, e))
+        };
+
+        Ok(Self {
+            name: conf
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{} {}", method, conf.url)),
+            method,
+            url: template(&url)?,
+            headers: conf
+                .headers
+                .iter()
+                .map(|h| template(h))
+                .collect::<Result<_, _>>()?,
+            body: conf.body.as_deref().map(template).transpose()?,
+            expect: conf.expect.clone(),
+            extract,
+            think_time,
+        })
+    }
+
+    async fn fire(
+        &self,
+        client: &reqwest::Client,
+        global_headers: &[String],
+        vars: &mut HashMap<String, String>,
+        buffer: &mut Vec<u8>,
+    ) -> TargetResult {
+        let start = Instant::now();
+
+        self.url.render_with(buffer, vars);
+        let mut req = client.request(
+            self.method.clone(),
+            String::from_utf8_lossy(buffer).into_owned(),
+        );
+        for h in global_headers {
+            if let Some((k, v)) = h.split_once(':') {
+                req = req.header(k.trim(), v.trim());
+            }
+        }
+        for h in &self.headers {
+            h.render_with(buffer, vars);
+            if let Some((k, v)) = String::from_utf8_lossy(buffer).split_once(':') {
+                req = req.header(k.trim(), v.trim());
+            }
+        }
+        let mut bytes_sent = 0;
+        if let Some(body) = &self.body {
+            body.render_with(buffer, vars);
+            bytes_sent = buffer.len() as u64;
+            req = req.body(buffer.clone());
+        }
+
+        let (res, mut phases) = timing::send(req).await;
+        let resp = match res {
+            Ok(resp) => resp,
+            Err(e) => return TargetResult::fail(start.elapsed(), format!("Network Error: {}", e)),
+        };
+        let status = resp.status().as_u16();
+        let resp_headers = resp.headers().clone();
+        let bytes = match timing::read_body(resp, &mut phases).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return TargetResult {
+                    status_code: Some(status),
+                    ..TargetResult::fail(start.elapsed(), format!("Read Error: {}", e))
+                }
+            }
+        };
+        let duration = start.elapsed();
+
+        let mut error = None;
+        if let Some(expected) = &self.expect {
+            if !String::from_utf8_lossy(&bytes).contains(expected.as_str()) {
+                error = Some(format!("Mismatch: missing '{}'", expected));
+            }
+        }
+
+        // Resposta de erro não alimenta variáveis: a jornada já vai parar aqui
+        let ok_status = (200..300).contains(&status);
+        let json = (ok_status && self.extract.iter().any(Extractor::needs_json))
+            .then(|| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+            .flatten();
+        for extractor in self.extract.iter().filter(|_| ok_status) {
+            match extractor.extract(&resp_headers, &bytes, json.as_ref()) {
+                Some(value) => {
+                    vars.insert(extractor.var.clone(), value);
+                }
+                None => {
+                    error.get_or_insert_with(|| format!("Extraction failed: '{}'", extractor.var));
+                }
+            }
+        }
+
+        let assert_ok = error.is_none();
+        TargetResult {
+            success: ok_status && assert_ok,
+            duration,
+            status_code: Some(status),
+            error,
+            bytes_sent,
+            bytes_received: bytes.len() as u64,
+            assertion_success: assert_ok,
+            steps: Vec::new(),
+            phases: Some(phases),
+            datagram: None,
+            ws: None,
+        }
+    }
+}
+
+// Acumulador de um passo / requisição do mix (um por worker, fundidos no fim como o resto das métricas)
+pub struct StepStats {
+    pub name: String,
+    pub successes: u64,
+    pub failures: u64,
+    // Latência dos sucessos (µs), sem o warm-up
+    pub histogram: Histogram<u64>,
+    pub status_counts: HashMap<u16, u64>,
+}
+
+impl StepStats {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            successes: 0,
+            failures: 0,
+            histogram: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            status_counts: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, res: &StepResult) {
+        if res.success {
+            self.successes += 1;
+            let _ = self.histogram.record(res.duration.as_micros() as u64);
+        } else {
+            self.failures += 1;
+        }
+        if let Some(code) = res.status_code {
+            *self.status_counts.entry(code).or_insert(0) += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: StepStats) {
+        self.successes += other.successes;
+        self.failures += other.failures;
+        let _ = self.histogram.add(other.histogram);
+        for (k, v) in other.status_counts {
+            *self.status_counts.entry(k).or_insert(0) += v;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.successes + self.failures
+    }
+}
+
+// Funde listas de passos por posição (workers que nunca chegaram num passo têm listas menores)
+pub fn merge_steps(into: &mut Vec<StepStats>, other: Vec<StepStats>) {
+    for (i, stats) in other.into_iter().enumerate() {
+        match into.get_mut(i) {
+            Some(existing) => existing.merge(stats),
+            None => into.push(stats),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::parser::ExtractConfig;
+    use crate::{Executor, LoadTest};
+    use std::collections::BTreeMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Servidor HTTP mínimo: POST /login devolve um token e um id; GET /items/7 só
+    // responde 200 com o Bearer certo
+    async fn shop() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                    let (status, body) = if req.starts_with("post /login") {
+                        ("200 OK", r#"{"token":"s3cr3t","user":{"cart":[7]}}"#)
+                    } else if req.starts_with("get /items/7")
+                        && req.contains("authorization: bearer s3cr3t")
+                    {
+                        ("200 OK", r#"{"ok":true}"#)
+                    } else {
+                        ("401 Unauthorized", "{}")
+                    };
+                    let resp = format!(
+                        "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(resp.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn journey() -> Vec<StepConfig> {
+        let json = |path: &str| ExtractConfig {
+            json: Some(path.to_string()),
+            ..Default::default()
+        };
+        vec![
+            StepConfig {
+                name: Some("login".to_string()),
+                method: "post".to_string(),
+                url: "/login".to_string(),
+                headers: Vec::new(),
+                body: Some(r#"{"user":"{{username}}"}"#.to_string()),
+                expect: None,
+                think_time: None,
+                extract: BTreeMap::from([
+                    ("token".to_string(), json("$.token")),
+                    ("item".to_string(), json("$.user.cart[0]")),
+                ]),
+            },
+            StepConfig {
+                name: None,
+                method: "GET".to_string(),
+                url: "/items/{{item}}".to_string(),
+                headers: vec!["Authorization: Bearer {{token}}".to_string()],
+                body: None,
+                expect: Some("ok".to_string()),
+                think_time: Some("50ms".to_string()),
+                extract: BTreeMap::new(),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_extracted_values_feed_later_steps() {
+        let base = shop().await;
+        let scenario = Scenario::from_config(&base, &journey()).unwrap();
+        assert_eq!(scenario.step_names(), vec!["login", "GET /items/{{item}}"]);
+
+        let started = Instant::now();
+        let res = scenario.run(&reqwest::Client::new(), &[]).await;
+        assert!(res.success, "{:?}", res.error);
+        assert_eq!(res.steps.len(), 2);
+        assert_eq!(res.status_code, Some(200));
+        // O think time do segundo passo acontece, mas fica fora da latência
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert!(res.duration < started.elapsed() - Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_journey_stops_at_the_first_failed_step() {
+        let base = shop().await;
+        let mut steps = journey();
+        steps[0].extract.insert(
+            "missing".to_string(),
+            ExtractConfig {
+                header: Some("X-Nope".to_string()),
+                ..Default::default()
+            },
+        );
+        let scenario = Scenario::from_config(&base, &steps).unwrap();
+
+        let res = scenario.run(&reqwest::Client::new(), &[]).await;
+        assert!(!res.success);
+        assert_eq!(res.steps.len(), 1);
+        assert_eq!(
+            res.error.as_deref(),
+            Some("login: Extraction failed: 'missing'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_reports_per_step_metrics() {
+        let base = shop().await;
+        let scenario = Scenario::from_config(&base, &journey()).unwrap();
+
+        let result = LoadTest::builder()
+            .scenario(scenario)
+            .executor(Executor::iterations(3, 12))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(result.successes, 12);
+        assert_eq!(result.steps.len(), 2);
+        assert_eq!(result.steps[0].name, "login");
+        assert_eq!(result.steps[1].successes, 12);
+        assert_eq!(result.steps[1].histogram.len(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_mix_follows_the_weights() {
+        let base = shop().await;
+        let request = |name: &str, method: &str, url: &str, weight| RequestConfig {
+            name: Some(name.to_string()),
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+            expect: None,
+            weight,
+        };
+        let mix = Scenario::mix(
+            &base,
+            &[
+                request("login", "POST", "/login", 3),
+                request("never", "GET", "/items/7", 0),
+                request("denied", "GET", "/items/7", 1),
+            ],
+        )
+        .unwrap();
+        assert!(mix.is_mix());
+
+        let result = LoadTest::builder()
+            .scenario(mix)
+            .executor(Executor::iterations(4, 400))
+            .build()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert!(result.steps.is_empty());
+        assert_eq!(result.requests.len(), 3);
+        let (login, never, denied) = (
+            &result.requests[0],
+            &result.requests[1],
+            &result.requests[2],
+        );
+        assert_eq!(login.total() + denied.total(), 400);
+        assert_eq!(never.total(), 0);
+        assert!(
+            (240..=360).contains(&login.successes),
+            "{}",
+            login.successes
+        );
+        assert_eq!(denied.status_counts.get(&401), Some(&denied.failures));
+        assert_eq!(result.failures, denied.failures);
+    }
+
+    #[test]
+    fn test_unclosed_tags_are_config_errors() {
+        let mut steps = journey();
+        steps[1].headers.push("X-Trace: {{trace".to_string());
+        let err = Scenario::from_config("http://x", &steps).err().unwrap();
+        assert!(err.contains("passo 2"), "{}", err);
+        assert!(err.contains("{{trace"), "{}", err);
+
+        // Um "}}" antes da tag não conta como fechamento
+        let mut steps = journey();
+        steps[0].url = "/a}}/{{b".to_string();
+        assert!(Scenario::from_config("http://x", &steps).is_err());
+    }
+
+    #[test]
+    fn test_mix_needs_some_weight() {
+        let zero = RequestConfig {
+            name: None,
+            method: "GET".to_string(),
+            url: "/".to_string(),
+            headers: Vec::new(),
+            body: None,
+            expect: None,
+            weight: 0,
+        };
+        assert!(Scenario::mix("http://x", &[zero]).is_err());
+        assert!(Scenario::mix("http://x", &[]).is_err());
+    }
+}
@@ -21,3 +21,24 @@ pub fn validate_and_extract(url_option: &Option<String>) -> String {
 
     url_str
 }
+
+// Mesma regra, sem matar o processo. Na linha de comando (`allow_unix`) aceita também
+// unix:///caminho; um agente do modo distribuído não abre sockets locais a pedido da rede
+pub fn check(url_option: &Option<String>, allow_unix: bool) -> Result<String, String> {
+    let url_str = url_option
+        .clone()
+        .ok_or("É necessário fornecer uma URL via flag (-u) ou no ficheiro YAML (--config)")?;
+    if url_str.starts_with("http://") || url_str.starts_with("https://") {
+        return Ok(url_str);
+    }
+    if !url_str.starts_with("unix://") {
+        return Err("A URL do alvo deve começar com http://, https:// ou unix://".to_string());
+    }
+    if !allow_unix {
+        return Err(UNIX_REFUSED.to_string());
+    }
+    Ok(url_str)
+}
+
+pub const UNIX_REFUSED: &str =
+    "Alvos unix:// só valem na linha de comando local; o agente aceita apenas http://, https:// ou host:porta";
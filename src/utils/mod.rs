@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+// Converte durações humanas ("500ms", "30s", "2m", "1h" ou só "45") em Duration.
+// Sem sufixo, o valor é interpretado como segundos.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let s = input.trim();
+    let split_at = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split_at);
+
+    let value: f64 = num
+        .parse()
+        .map_err(|_| format!("Duração inválida: '{}'", input))?;
+
+    let secs = match unit.trim() {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        other => {
+            return Err(format!(
+                "Unidade de duração desconhecida '{}' em '{}' (use ms, s, m ou h)",
+                other, input
+            ))
+        }
+    };
+
+    // Números que cabem num f64 mas não numa Duration (ex: "99999999999999999999h")
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("Duração fora do limite: '{}'", input))
+}
+
+// "2024-05-01T12:00:00.250Z" / "2024-05-01 12:00:00+02:00" → segundos desde a epoch
+pub fn parse_rfc3339(s: &str) -> Option<f64> {
+    let num = |s: &str| s.parse::<i64>().ok();
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+    let mut d = date.splitn(3, '-');
+    let (year, month, day) = (num(d.next()?)?, num(d.next()?)?, num(d.next()?)?);
+
+    // Fuso: Z ou ±HH:MM no fim
+    let (clock, tz_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let i = time.rfind(['+', '-'])?;
+        let (h, m) = time[i + 1..].split_once(':')?;
+        let offset = num(h)? * 3600 + num(m)? * 60;
+        (
+            &time[..i],
+            if time[i..].starts_with('-') {
+                -offset
+            } else {
+                offset
+            },
+        )
+    };
+    let mut c = clock.splitn(3, ':');
+    let (hour, minute) = (num(c.next()?)?, num(c.next()?)?);
+    let second = c.next()?.parse::<f64>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    // Dias desde 1970-01-01 (algoritmo days_from_civil)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Some((days * 86_400 + hour * 3600 + minute * 60 - tz_secs) as f64 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("1.5s").unwrap(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_garbage() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("10d").is_err());
+        assert!(parse_duration("abc").is_err());
+        assert!(parse_duration("1e300s").is_err());
+        assert!(parse_duration("99999999999999999999h").is_err());
+        assert!(parse_duration(&format!("{}s", "9".repeat(400))).is_err());
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        assert_eq!(parse_rfc3339("1970-01-02T01:00:00+01:00"), Some(86_400.0));
+        assert_eq!(
+            parse_rfc3339("2024-05-01T12:00:00.250Z"),
+            Some(1_714_564_800.25)
+        );
+        assert!(parse_rfc3339("2024-13-01T00:00:00Z").is_none());
+        assert!(parse_rfc3339("ontem").is_none());
+    }
+}